@@ -204,3 +204,29 @@ set_local_network_bitcoin_enabled() {
   assert_command_fail dfx canister call --wallet default aaaaa-aa --candid bitcoin.did bitcoin_send_transaction '(record { transaction = vec {0:nat8}; network = variant { regtest } })'
   assert_contains "send_transaction failed: MalformedTransaction"
 }
+
+@test "dfx start --bitcoind runs a regtest node that dfx bitcoin uses" {
+  dfx_new hello
+  define_project_network
+  # Ports other than the defaults, which the node started in setup() uses.
+  jq '.networks.local.bitcoin.bitcoind={"port":18544,"rpc_port":18543}' dfx.json | sponge dfx.json
+
+  dfx_start
+  BITCOIND_PID=$(cat .dfx/network/local/bitcoind-pid)
+
+  assert_command dfx bitcoin mine 101
+  assert_command dfx bitcoin balance
+  assert_eq "50.00000000 BTC"
+
+  ADDRESS="$(bitcoin-cli -regtest -rpcport=18543 -rpccookiefile=.dfx/network/local/bitcoind-rpc-cookie -rpcwallet=dfx getnewaddress)"
+  assert_command dfx bitcoin send "$ADDRESS" 1.5
+  assert_command dfx bitcoin mine 1
+  assert_command dfx bitcoin balance "$ADDRESS" --satoshis
+  assert_eq "150000000"
+
+  dfx_stop
+  assert_process_exits "$BITCOIND_PID" 15s
+
+  assert_command_fail dfx bitcoin balance
+  assert_contains "No running bitcoind found for network 'local'"
+}
@@ -371,3 +371,36 @@ teardown() {
   install_asset memory64
   assert_command dfx build --check
 }
+
+@test "dfx build --watch rebuilds a canister when an imported file changes" {
+  echo 'module { public let greeting = "Hello" }' > src/e2e_project_backend/greeting.mo
+  sed -i '1i import Greeting "greeting";' src/e2e_project_backend/main.mo
+
+  dfx build --check --watch > watch.log 2>&1 &
+  WATCH_PID=$!
+  timeout 120 sh -c 'until grep -q "Watching for changes" watch.log; do sleep 1; done'
+
+  echo 'module { public let greeting = "Howdy" }' > src/e2e_project_backend/greeting.mo
+  timeout 120 sh -c 'until grep -q "Rebuilding e2e_project_backend" watch.log; do sleep 1; done'
+  timeout 120 sh -c 'until [ "$(grep -c "Finished building canisters" watch.log)" -ge 2 ]; do sleep 1; done'
+
+  echo 'syntax error' > src/e2e_project_backend/greeting.mo
+  timeout 120 sh -c 'until grep -q "Failed while trying to build" watch.log; do sleep 1; done'
+  kill -0 "$WATCH_PID"
+
+  kill "$WATCH_PID"
+}
+
+@test "dfx build --watch keeps watching after the first build fails" {
+  echo 'syntax error' >> src/e2e_project_backend/main.mo
+
+  dfx build --check --watch > watch.log 2>&1 &
+  WATCH_PID=$!
+  timeout 120 sh -c 'until grep -q "Watching for changes" watch.log; do sleep 1; done'
+  assert_command grep -q "Failed while trying to build" watch.log
+
+  sed -i '$d' src/e2e_project_backend/main.mo
+  timeout 120 sh -c 'until grep -q "Finished building canisters" watch.log; do sleep 1; done'
+
+  kill "$WATCH_PID"
+}
@@ -204,3 +204,42 @@ set_shared_local_network_canister_http_empty() {
   assert_command dfx start --background --verbose
   assert_match "log level: Critical"
 }
+
+@test "HTTPS outcalls are answered from mock rules" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: only PocketIC supports mocking HTTPS outcalls"
+  dfx_new
+  install_asset canister_http
+  define_project_network
+  cat > mocks.json <<'END'
+{ "rules": [ { "url": "https://api\\.example\\.com/price", "method": "GET", "body": "42" } ] }
+END
+  jq '.networks.local.canister_http.mock="mocks.json"' dfx.json | sponge dfx.json
+
+  dfx_start
+  dfx deploy
+
+  assert_command dfx canister call e2e_project_backend get_url '("api.example.com:443","https://api.example.com/price")'
+  assert_eq '("42")'
+  assert_command_fail dfx canister call e2e_project_backend get_url '("api.example.com:443","https://api.example.com/other")'
+  assert_contains "No mock rule matches GET https://api.example.com/other"
+
+  assert_command dfx pocketic http-outcalls
+  assert_contains "GET https://api.example.com/price -> 200"
+  assert_contains "GET https://api.example.com/other -> rejected (no matching rule)"
+  assert_command dfx pocketic http-outcalls --json
+  assert_contains '"User-Agent","sdk-e2e-test"'
+
+  assert_command_fail dfx pocketic time pause
+  assert_contains "while HTTPS outcalls are mocked"
+}
+
+@test "dfx start rejects invalid mock rules" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: only PocketIC supports mocking HTTPS outcalls"
+  dfx_new
+  define_project_network
+  echo '{ "rules": [ { "url": "https://(unclosed" } ] }' > mocks.json
+  jq '.networks.local.canister_http.mock="mocks.json"' dfx.json | sponge dfx.json
+
+  assert_command_fail dfx start --background
+  assert_contains "Invalid rule 0."
+}
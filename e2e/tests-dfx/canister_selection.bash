@@ -0,0 +1,74 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new hello
+  # svc_a and svc_b are tagged "backend"; web is tagged "frontend" and depends on svc_a.
+  jq '.canisters.svc_a = (.canisters.hello_backend + {tags: ["backend"]})
+    | .canisters.svc_b = (.canisters.hello_backend + {tags: ["backend"]})
+    | .canisters.web = (.canisters.hello_backend + {tags: ["frontend"], dependencies: ["svc_a"]})
+    | del(.canisters.hello_backend)' dfx.json | sponge dfx.json
+}
+
+teardown() {
+  dfx_stop
+
+  standard_teardown
+}
+
+@test "build selects canisters by tag and their dependencies" {
+  dfx_start
+  dfx canister create --all
+
+  assert_command dfx build --tag frontend
+  assert_file_exists .dfx/local/canisters/web/web.wasm
+  assert_file_exists .dfx/local/canisters/svc_a/svc_a.wasm
+  assert_file_not_exists .dfx/local/canisters/svc_b/svc_b.wasm
+}
+
+@test "deploy selects canisters by glob pattern" {
+  dfx_start
+
+  assert_command dfx deploy 'svc_*'
+  assert_contains "Deploying: svc_a svc_b"
+  assert_command_fail dfx canister id web
+}
+
+@test "bulk canister commands accept tags and patterns" {
+  dfx_start
+  dfx deploy
+
+  assert_command dfx canister stop --tag backend
+  assert_command dfx canister status svc_a
+  assert_contains "Status: Stopped"
+  assert_command dfx canister status web
+  assert_contains "Status: Running"
+
+  assert_command dfx canister status 'svc_?'
+  assert_contains "Canister status call result for svc_a."
+  assert_contains "Canister status call result for svc_b."
+  assert_not_contains "Canister status call result for web."
+
+  assert_command dfx canister snapshot create --tag backend
+  assert_contains "Created a new snapshot of canister svc_a."
+  assert_contains "Created a new snapshot of canister svc_b."
+
+  assert_command dfx canister start 'svc_*' --tag backend
+  assert_command dfx canister status svc_b
+  assert_contains "Status: Running"
+}
+
+@test "selections that match nothing are rejected" {
+  dfx_start
+
+  assert_command_fail dfx canister status --tag nope
+  assert_contains "No canisters tagged 'nope' in dfx.json."
+
+  assert_command_fail dfx build 'api_*'
+  assert_contains "No canisters matching 'api_*' in dfx.json."
+
+  assert_command_fail dfx canister status --all --tag backend
+}
@@ -43,3 +43,20 @@ dfx_canister_status_grep_balance() {
   assert_not_contains "Memory allocation: "
   assert_contains "Balance: "
 }
+
+@test "cycles report records snapshots per network" {
+  dfx_start
+  assert_command dfx canister create --all --no-wallet
+
+  assert_command dfx canister cycles-report e2e_project_frontend
+  assert_contains "CANISTER"
+  assert_contains "e2e_project_frontend"
+  assert_contains "(idle)"
+  assert_contains "Total burn rate:"
+  assert_file_exists .dfx/local/cycles_history.json
+  assert_eq "1" "$(jq '.canisters[].snapshots | length' .dfx/local/cycles_history.json)"
+
+  assert_command dfx canister cycles-report --all --json
+  assert_eq "2" "$(echo "$stdout" | jq length)"
+  assert_eq "3" "$(jq '[.canisters[].snapshots | length] | add' .dfx/local/cycles_history.json)"
+}
@@ -0,0 +1,144 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new hello
+}
+
+teardown() {
+  standard_teardown
+}
+
+@test "dfx config get prints values from dfx.json" {
+  assert_command dfx config get canisters.hello_backend.main
+  assert_eq "src/hello_backend/main.mo"
+
+  assert_command dfx config get /canisters/hello_backend/type
+  assert_eq "motoko"
+
+  assert_command dfx config get canisters.hello_backend
+  assert_command jq -r .main <<<"$stdout"
+  assert_eq "src/hello_backend/main.mo"
+
+  assert_command_fail dfx config get canisters.nonexistent
+  assert_contains "No value at 'canisters.nonexistent'"
+}
+
+@test "dfx config set and unset edit dfx.json" {
+  assert_command dfx config set canisters.hello_backend.optimize cycles
+  assert_command jq -r .canisters.hello_backend.optimize dfx.json
+  assert_eq "cycles"
+
+  assert_command dfx config set canisters.hello_backend.dependencies '[]'
+  assert_command jq -c .canisters.hello_backend.dependencies dfx.json
+  assert_eq "[]"
+
+  assert_command dfx config set canisters.hello_backend.declarations.env_override 1234 --string
+  assert_command jq .canisters.hello_backend.declarations.env_override dfx.json
+  assert_eq '"1234"'
+
+  assert_command dfx config unset canisters.hello_backend.optimize
+  assert_command jq .canisters.hello_backend.optimize dfx.json
+  assert_eq "null"
+
+  assert_command_fail dfx config unset canisters.hello_backend.optimize
+}
+
+@test "dfx config set preserves key order" {
+  jq -r 'keys_unsorted | join(",")' dfx.json > before
+  dfx config set canisters.hello_backend.gzip true
+  jq -r 'keys_unsorted | join(",")' dfx.json > after
+  assert_command diff before after
+}
+
+@test "dfx config set rejects changes that make dfx.json invalid" {
+  cp dfx.json dfx.json.orig
+  assert_command_fail dfx config set canisters.hello_backend.type rust
+  assert_contains "The change would make dfx.json invalid."
+  assert_command diff dfx.json dfx.json.orig
+
+  assert_command_fail dfx config set canisters.hello_backend.optimize not-a-level
+  assert_command diff dfx.json dfx.json.orig
+}
+
+@test "dfx config set rejects unknown fields" {
+  cp dfx.json dfx.json.orig
+  assert_command_fail dfx config set canisters.hello_backend.tpye rust
+  assert_contains "/canisters/hello_backend/tpye: unknown field 'tpye'"
+  assert_command diff dfx.json dfx.json.orig
+
+  assert_command_fail dfx config set local.bnid 127.0.0.1:8123 --networks
+  assert_contains "/local/bnid: unknown field 'bnid'"
+}
+
+@test "dfx config can edit networks.json" {
+  assert_command dfx config set local.bind 127.0.0.1:8123 --networks
+  assert_command jq -r .local.bind "$E2E_NETWORKS_JSON"
+  assert_eq "127.0.0.1:8123"
+
+  assert_command dfx config get local.bind --networks
+  assert_eq "127.0.0.1:8123"
+
+  assert_command dfx config unset local --networks
+  assert_command jq -c . "$E2E_NETWORKS_JSON"
+  assert_eq "{}"
+}
+
+@test "dfx config validate accepts a new project" {
+  assert_command dfx config validate
+  assert_contains "dfx.json is valid"
+
+  assert_command dfx config validate --json
+  assert_eq "[]"
+}
+
+@test "dfx config validate reports problems with positions" {
+  jq '.canisters.hello_backend.dependencies=["nope"]' dfx.json | sponge dfx.json
+  assert_command_fail dfx config validate
+  assert_match "dfx.json:[0-9]+:[0-9]+: error: .*'nope'.* \[unknown-dependency\]"
+  assert_contains "Found 1 error(s)"
+
+  assert_command_fail dfx config validate --json
+  assert_command jq -r '.[0].code' <<< "$stdout"
+  assert_eq "unknown-dependency"
+}
+
+@test "dfx config validate reports cycles next to unknown dependencies" {
+  jq '.canisters.hello_backend.dependencies=["nope", "hello_frontend"]' dfx.json | sponge dfx.json
+  jq '.canisters.hello_frontend.dependencies=["hello_backend"]' dfx.json | sponge dfx.json
+  assert_command_fail dfx config validate
+  assert_contains "[unknown-dependency]"
+  assert_contains "circular canister dependencies: hello_backend -> hello_frontend -> hello_backend"
+  assert_contains "Found 2 error(s)"
+}
+
+@test "dfx config validate reports missing files and schema errors" {
+  rm src/hello_backend/main.mo
+  jq '.canisters.hello_backend.optimize="fastest"' dfx.json | sponge dfx.json
+  assert_command_fail dfx config validate
+  assert_contains "[missing-file]"
+  assert_contains "[schema]"
+}
+
+@test "dfx config validate reports invalid json" {
+  echo '{ "canisters": ' > dfx.json
+  assert_command_fail dfx config validate
+  assert_contains "[invalid-json]"
+}
+
+@test "dfx config validate warns about remote ids for unknown networks" {
+  jq '.canisters.hello_backend.remote.id.nowhere="aaaaa-aa"' dfx.json | sponge dfx.json
+  assert_command dfx config validate
+  assert_contains "[unknown-network]"
+}
+
+@test "dfx config validate still knows canisters with unknown types as dependencies" {
+  jq '.canisters.hello_backend.type="unknown_canister_type"' dfx.json | sponge dfx.json
+  assert_command_fail dfx config validate
+  assert_contains "[unknown-canister-type]"
+  assert_not_contains "[unknown-dependency]"
+  assert_contains "Found 1 error(s)"
+}
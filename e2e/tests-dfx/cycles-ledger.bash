@@ -204,6 +204,42 @@ current_time_nanoseconds() {
   assert_eq "500000 cycles."
 }
 
+@test "history" {
+  start_and_install_nns
+
+  ALICE=$(dfx identity get-principal --identity alice)
+  BOB=$(dfx identity get-principal --identity bob)
+
+  deploy_cycles_ledger
+
+  assert_command dfx canister call depositor deposit "(record {to = record{owner = principal \"$ALICE\";};cycles = 3_000_000_000_000;})" --identity cycle-giver
+  assert_command dfx cycles transfer "$BOB" 100000 --identity alice --memo 7
+  assert_eq "Transfer sent at block index 1"
+
+  assert_command dfx cycles history --identity alice --json
+  assert_eq "2" "$(echo "$stdout" | jq length)"
+  assert_eq "mint" "$(echo "$stdout" | jq -r '.[0].kind')"
+  assert_eq "3000000000000" "$(echo "$stdout" | jq -r '.[0].amount')"
+  assert_eq "transfer" "$(echo "$stdout" | jq -r '.[1].kind')"
+  assert_eq "$BOB" "$(echo "$stdout" | jq -r '.[1].to')"
+  assert_eq "100000" "$(echo "$stdout" | jq -r '.[1].amount')"
+
+  # bob only took part in the transfer
+  assert_command dfx cycles history --identity bob --json
+  assert_eq "1" "$(echo "$stdout" | jq length)"
+  assert_eq "1" "$(echo "$stdout" | jq -r '.[0].index')"
+
+  assert_command dfx cycles history --identity bob
+  assert_contains "INDEX"
+  assert_contains "transfer"
+  assert_contains "0.0000001 TCYCLES"
+  assert_not_contains "mint"
+
+  assert_command dfx cycles history --all-accounts --start 1 --limit 1 --json
+  assert_eq "1" "$(echo "$stdout" | jq length)"
+  assert_eq "transfer" "$(echo "$stdout" | jq -r '.[0].kind')"
+}
+
 @test "transfer deduplication" {
   start_and_install_nns
 
@@ -467,6 +503,49 @@ current_time_nanoseconds() {
   assert_not_contains "Depositing 10000 cycles onto remote"
 }
 
+@test "ensure tops up canisters below their cycles policy" {
+  start_and_install_nns
+
+  dfx_new
+  add_cycles_ledger_canisters_to_project
+  install_cycles_ledger_canisters
+
+  deploy_cycles_ledger
+
+  assert_command dfx deploy
+
+  PRINCIPAL=$(dfx identity get-principal)
+  assert_command dfx canister call depositor deposit "(record {to = record{owner = principal \"$PRINCIPAL\";};cycles = 2_400_000_000_000;})" --identity cycle-giver
+
+  # shellcheck disable=SC2031
+  export DFX_DISABLE_AUTO_WALLET=1
+
+  jq '.canisters.e2e_project_backend.cycles={"minimum":4000000000000,"target":4500000000000}' dfx.json | sponge dfx.json
+  assert_command dfx cycles ensure --all --dry-run
+  assert_contains "e2e_project_backend"
+  assert_contains "1.000 TC"
+  assert_command dfx canister status e2e_project_backend
+  assert_contains "Balance: 3_500_000_000_000 Cycles"
+
+  assert_command dfx cycles ensure --all
+  assert_command dfx canister status e2e_project_backend
+  assert_contains "Balance: 4_500_000_000_000 Cycles"
+  assert_command dfx cycles balance --precise
+  assert_eq "1399900000000 cycles."
+
+  # above the minimum, nothing happens
+  assert_command dfx cycles ensure e2e_project_backend
+  assert_command dfx cycles balance --precise
+  assert_eq "1399900000000 cycles."
+
+  assert_command_fail dfx cycles ensure e2e_project_frontend
+  assert_contains "has no cycles policy"
+
+  jq '.canisters.e2e_project_backend.cycles.target=1' dfx.json | sponge dfx.json
+  assert_command_fail dfx cycles ensure --all
+  assert_contains "below its minimum"
+}
+
 @test "top-up deduplication" {
   start_and_install_nns
 
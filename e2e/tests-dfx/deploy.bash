@@ -291,3 +291,19 @@ teardown() {
   assert_command dfx canister call hyphenated-lib name
   assert_contains lib
 }
+
+@test "dfx deploy --watch upgrades a canister when its sources change" {
+  dfx_start
+  dfx deploy hello_backend --watch > watch.log 2>&1 &
+  WATCH_PID=$!
+  timeout 300 sh -c 'until grep -q "Watching for changes" watch.log; do sleep 1; done'
+  assert_command dfx canister call hello_backend greet '("you")'
+  assert_eq '("Hello, you!")'
+
+  sed -i 's/Hello, /Howdy, /' src/hello_backend/main.mo
+  timeout 300 sh -c 'until [ "$(grep -c "Deployed canisters" watch.log)" -ge 2 ]; do sleep 1; done'
+  assert_command dfx canister call hello_backend greet '("you")'
+  assert_eq '("Howdy, you!")'
+
+  kill "$WATCH_PID"
+}
@@ -100,6 +100,53 @@ teardown() {
   assert_file_not_exists "src/declarations/hello_backend/index.d.ts"
 }
 
+@test "dfx generate creates Rust bindings" {
+  dfx_new hello
+  jq '.canisters.hello_backend.declarations.bindings=["rs"]' dfx.json | sponge dfx.json
+
+  assert_command dfx generate hello_backend
+
+  assert_file_exists "src/declarations/hello_backend/hello_backend.rs"
+  assert_file_not_exists "src/declarations/hello_backend/hello_backend.did.js"
+  assert_command cat "src/declarations/hello_backend/hello_backend.rs"
+  assert_contains "ic_cdk"
+  assert_contains "greet"
+
+  jq '.canisters.hello_backend.declarations.rust.target="ic-agent"' dfx.json | sponge dfx.json
+  assert_command dfx generate hello_backend
+  assert_command cat "src/declarations/hello_backend/hello_backend.rs"
+  assert_contains "ic_agent"
+  assert_not_contains "ic_cdk"
+}
+
+@test "dfx generate renders custom templates" {
+  dfx_new hello
+  mkdir templates
+  echo 'export const canisterName = "{{canister_name}}"; // custom' > templates/index.js.hbs
+  cat > templates/methods.txt.hbs <<EOF
+{{#each methods}}{{name}} {{mode}} ({{#each args}}{{this}}{{/each}}) -> ({{#each rets}}{{this}}{{/each}})
+{{/each}}
+EOF
+  jq '.canisters.hello_backend.declarations.templates={"index.js": "templates/index.js.hbs", "extra/methods.txt": "templates/methods.txt.hbs"}' dfx.json | sponge dfx.json
+
+  assert_command dfx generate hello_backend
+
+  assert_command cat "src/declarations/hello_backend/index.js"
+  assert_eq 'export const canisterName = "hello_backend"; // custom'
+  assert_file_exists "src/declarations/hello_backend/index.d.ts"
+  assert_command cat "src/declarations/hello_backend/extra/methods.txt"
+  assert_contains "greet query (text) -> (text)"
+}
+
+@test "dfx generate rejects custom templates outside of the declarations directory" {
+  dfx_new hello
+  echo 'x' > outside.hbs
+  jq '.canisters.hello_backend.declarations.templates={"../outside.js": "outside.hbs"}' dfx.json | sponge dfx.json
+
+  assert_command_fail dfx generate hello_backend
+  assert_contains "it must be a relative path inside the declarations directory"
+}
+
 @test "dfx generate does not create any files" {
   dfx_new hello
   jq '.canisters.hello_backend.declarations.bindings=[]' dfx.json | sponge dfx.json
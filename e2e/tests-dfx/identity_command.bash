@@ -27,6 +27,93 @@ teardown() {
   fi
 }
 
+##
+## dfx identity delegate
+##
+
+@test "identity delegate: the session identity makes calls as the delegating identity" {
+  assert_command dfx identity new root --storage-mode plaintext
+  assert_command dfx identity new session --storage-mode plaintext
+  ROOT_PRINCIPAL="$(dfx identity get-principal --identity root)"
+
+  assert_command dfx identity delegate --identity root --to session --expiry 1h
+  assert_match "Identity 'session' makes calls as 'root' for 1h."
+  assert_command dfx identity get-principal --identity session
+  assert_eq "$ROOT_PRINCIPAL"
+
+  assert_command_fail dfx identity delegate --identity root --to root
+  assert_match "An identity cannot delegate to itself."
+  assert_command_fail dfx identity delegate --identity root --to not-a-key
+  assert_match "is neither an identity nor a public key in hex."
+}
+
+@test "identity delegate: an expired delegation cannot be used" {
+  assert_command dfx identity new root --storage-mode plaintext
+  assert_command dfx identity new session --storage-mode plaintext
+
+  assert_command dfx identity delegate --identity root --to session --expiry 1s
+  sleep 2
+  assert_command_fail dfx identity get-principal --identity session
+  assert_match "The delegation of identity 'session' expired"
+
+  assert_command dfx identity delegate --identity root --to session --expiry 1h
+  assert_command dfx identity get-principal --identity session
+  assert_eq "$(dfx identity get-principal --identity root)"
+}
+
+@test "identity delegate: a delegation to a public key can be imported" {
+  assert_command dfx identity new root --storage-mode plaintext
+  assert_command dfx identity new session --storage-mode plaintext
+  dfx identity export session > session.pem
+  PUBLIC_KEY="$(openssl ec -in session.pem -pubout -outform DER 2>/dev/null | xxd -p -c 1000)"
+
+  assert_command dfx identity delegate --identity root --to "$PUBLIC_KEY" --output delegation.json
+  assert_command dfx identity import ci session.pem --storage-mode plaintext --delegation delegation.json
+  assert_command dfx identity get-principal --identity ci
+  assert_eq "$(dfx identity get-principal --identity root)"
+
+  assert_command dfx identity new other --storage-mode plaintext
+  dfx identity export other > other.pem
+  assert_command_fail dfx identity import other-ci other.pem --storage-mode plaintext --delegation delegation.json
+  assert_match "does not delegate to the key of identity 'other-ci'"
+}
+
+##
+## dfx identity new --type ii-local
+##
+
+@test "identity new --type ii-local: makes calls as the Internet Identity user of a frontend" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: only PocketIC supports system canisters"
+  dfx_new hello
+  define_project_network
+  jq '.networks.local.system_canisters={"internet_identity":{}}' dfx.json | sponge dfx.json
+  dfx_start
+  dfx deploy hello_backend
+
+  assert_command dfx identity new alice --type ii-local --storage-mode plaintext --origin http://frontend.localhost:4943
+  assert_match 'It signs in to http://frontend.localhost:4943 with anchor [0-9]+'
+  ANCHOR="$(echo "$output" | sed -n 's/.*with anchor \([0-9]*\) as.*/\1/p')"
+  ALICE="$(dfx identity get-principal --identity alice)"
+  assert_command dfx canister call hello_backend greet '("ii")' --identity alice
+  assert_eq '("Hello, ii!")'
+
+  # the same anchor and origin give the same principal, another origin gives another principal
+  assert_command dfx identity new alice-laptop --type ii-local --storage-mode plaintext --anchor "$ANCHOR" --origin http://frontend.localhost:4943/
+  assert_command dfx identity get-principal --identity alice-laptop
+  assert_eq "$ALICE"
+  assert_command dfx identity new alice-admin --type ii-local --storage-mode plaintext --anchor "$ANCHOR" --origin http://admin.localhost:4943
+  assert_command dfx identity get-principal --identity alice-admin
+  assert_neq "$ALICE"
+
+  # re-creating the identity renews the delegation and keeps the anchor and origin
+  assert_command dfx identity new alice --type ii-local --storage-mode plaintext --force
+  assert_command dfx identity get-principal --identity alice
+  assert_eq "$ALICE"
+
+  assert_command_fail dfx identity new bob --type ii-local --storage-mode plaintext --anchor 123 --origin http://frontend.localhost:4943
+  assert_match "No identity signs in with anchor 123."
+}
+
 ##
 ## dfx identity list
 ##
@@ -570,3 +657,48 @@ EOF
   assert_command dfx identity get-principal --identity alice
   assert_eq "qimd7-lqrvx-kdvsm-7zeqn-bgoix-ukjfi-hgmfg-ur2he-odgb2-joms4-nae"
 }
+
+@test "identity set-storage-mode: moves a plaintext identity to the keyring and back" {
+  assert_command dfx identity new alice --storage-mode plaintext
+  PRINCIPAL="$(dfx identity get-principal --identity alice)"
+
+  assert_command dfx identity set-storage-mode alice keyring
+  assert_match 'Identity "alice" now uses storage mode keyring.' "$stderr"
+  assert_file_not_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.pem"
+  assert_command cat "$MOCK_KEYRING_LOCATION"
+  assert_match "internet_computer_identity_alice"
+  assert_command dfx identity get-principal --identity alice
+  assert_eq "$PRINCIPAL"
+
+  assert_command_fail dfx identity set-storage-mode alice keyring
+  assert_match "Identity 'alice' already uses storage mode 'keyring'."
+
+  assert_command dfx identity set-storage-mode alice plaintext
+  assert_file_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.pem"
+  assert_command cat "$MOCK_KEYRING_LOCATION"
+  assert_not_match "internet_computer_identity_alice"
+  assert_command dfx identity get-principal --identity alice
+  assert_eq "$PRINCIPAL"
+}
+
+@test "identity set-storage-mode --all-plaintext: moves all plaintext identities to the keyring" {
+  assert_command dfx identity new alice --storage-mode plaintext
+  assert_command dfx identity new bob --storage-mode plaintext
+  assert_command dfx identity new charlie
+
+  assert_command dfx identity set-storage-mode --all-plaintext keyring
+  assert_match 'Identity "alice" now uses storage mode keyring.' "$stderr"
+  assert_match 'Identity "bob" now uses storage mode keyring.' "$stderr"
+  assert_not_match 'charlie' "$stderr"
+  assert_file_not_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.pem"
+  assert_file_not_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/bob/identity.pem"
+
+  assert_command dfx identity set-storage-mode --all-plaintext keyring
+  assert_match "There are no plaintext identities."
+}
+
+@test "identity change-password: rejects identities that are not password-protected" {
+  assert_command dfx identity new alice --storage-mode plaintext
+  assert_command_fail dfx identity change-password alice
+  assert_match "Identity 'alice' is not password-protected."
+}
@@ -61,3 +61,37 @@ EOF
   assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/init_alice_with_storage_mode_pwprotected.exp"
   assert_command dfx identity remove alice
 }
+
+@test "backup and restore identities with a password" {
+  assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/init_alice_with_pw.exp"
+  assert_command dfx identity new bob --storage-mode plaintext
+  BOB_PRINCIPAL="$(dfx identity get-principal --identity bob)"
+  assert_command dfx identity new root --storage-mode plaintext
+  assert_command dfx identity new session --storage-mode plaintext
+  assert_command dfx identity delegate --identity root --to session --expiry 1h
+  ROOT_PRINCIPAL="$(dfx identity get-principal --identity root)"
+  assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/backup_identities_with_password.exp"
+  assert_file_exists identities.backup
+  assert_not_contains "BEGIN EC PRIVATE KEY" "$(cat identities.backup)"
+
+  assert_command dfx identity remove alice
+  assert_command dfx identity remove bob
+  assert_command dfx identity remove session
+
+  assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/restore_identities_with_password.exp"
+  assert_contains "Identity 'default' already exists and was not restored."
+  assert_command dfx identity get-principal --identity bob
+  assert_eq "$BOB_PRINCIPAL"
+  assert_command dfx identity get-principal --identity session
+  assert_eq "$ROOT_PRINCIPAL"
+  assert_file_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.pem.encrypted"
+  assert_file_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/bob/identity.pem"
+}
+
+@test "change the password of an identity" {
+  assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/init_alice_with_pw.exp"
+  SALT="$(jq -r .encryption.pw_salt "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.json")"
+  assert_command "${BATS_TEST_DIRNAME}/../assets/expect_scripts/change_identity_password.exp"
+  assert_neq "$SALT" "$(jq -r .encryption.pw_salt "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.json")"
+  assert_file_not_exists "$DFX_CONFIG_ROOT/.config/dfx/identity/alice/identity.pem.encrypted.tmp"
+}
@@ -173,6 +173,54 @@ current_time_nanoseconds() {
   assert_match "100.00000000 ICP"
 }
 
+@test "token commands work with the ICP ledger" {
+  install_nns
+
+  ALICE=$(dfx identity get-principal --identity alice)
+  BOB=$(dfx identity get-principal --identity bob)
+  DAVID=$(dfx identity get-principal --identity david)
+  LEDGER=ryjl3-tyaaa-aaaaa-aaaba-cai
+
+  dfx identity use alice
+
+  assert_command dfx token --ledger "$LEDGER" metadata
+  assert_contains "icrc1:symbol: ICP"
+  assert_contains "icrc1:decimals: 8"
+  assert_contains "icrc1:fee: 10000"
+
+  assert_command dfx token --ledger "$LEDGER" balance
+  assert_eq "1000000000 ICP"
+
+  assert_command dfx token --ledger "$LEDGER" transfer "$DAVID" 1.5 --memo 1
+  assert_contains "Transferred 1.5 ICP"
+
+  # The sender(alice) paid transaction fee which is 0.0001 ICP.
+  assert_command dfx token --ledger "$LEDGER" balance
+  assert_eq "999999998.4999 ICP"
+  assert_command dfx token --ledger "$LEDGER" balance --of "$DAVID" --precise
+  assert_eq "150000000"
+
+  assert_command_fail dfx token --ledger "$LEDGER" transfer "$DAVID" 0.000000001
+  assert_contains "Expected ICP with up to 8 decimals"
+
+  assert_command dfx token --ledger "$LEDGER" approve "$BOB" 10
+  assert_contains "Approved 10 ICP"
+
+  assert_command dfx token --ledger "$LEDGER" allowance "$BOB"
+  assert_eq "10 ICP"
+
+  dfx identity use bob
+
+  assert_command dfx token --ledger "$LEDGER" transfer-from --from "$ALICE" "$DAVID" 2.25
+  assert_contains "Transferred 2.25 ICP"
+
+  assert_command dfx token --ledger "$LEDGER" allowance --owner "$ALICE" "$BOB"
+  assert_eq "7.7499 ICP"
+
+  assert_command dfx token --ledger "$LEDGER" balance --of "$DAVID"
+  assert_eq "3.75 ICP"
+}
+
 @test "ledger subaccounts" {
   install_nns
 
@@ -272,6 +320,37 @@ tc_to_num() {
   assert_contains "Canister was topped up with 500000000000000 cycles"
 }
 
+@test "ledger transfer and top-up signed offline" {
+  install_nns
+
+  dfx identity use alice
+  wallet=$(dfx identity get-wallet)
+  BOB=$(dfx identity get-principal --identity bob)
+
+  assert_command dfx ledger transfer --amount 1 --memo 1 --to-principal "$BOB" --sign-only transfer.json
+  assert_contains "Signed transaction written to [transfer.json]"
+  assert_command dfx ledger top-up "$wallet" --icp 5 --sign-only top-up.json
+  assert_command_fail dfx ledger top-up "$wallet" --icp 5 --sign-only top-up.json
+  assert_contains "already exists"
+  assert_eq "notify_top_up" "$(jq -r .follow_up.type top-up.json)"
+
+  balance=$(tc_to_num "$(dfx wallet balance)")
+  assert_command dfx send transfer.json top-up.json --yes
+  assert_contains "variant { Ok"
+  assert_contains "Canister was topped up with 500000000000000 cycles"
+
+  assert_command dfx ledger balance --identity bob
+  assert_eq "1000000001.00000000 ICP"
+  balance_now=$(tc_to_num "$(dfx wallet balance)")
+  (( balance_now - balance > 400000000000000 ))
+
+  # a transfer that fails stops the sending
+  assert_command dfx ledger transfer --amount 2000000000 --memo 2 --to-principal "$BOB" --sign-only too-much.json
+  assert_command_fail dfx send too-much.json transfer.json --yes
+  assert_contains "InsufficientFunds"
+  assert_contains "The transaction in [too-much.json] failed."
+}
+
 @test "ledger create-canister" {
   install_nns
 
@@ -113,3 +113,52 @@ teardown() {
   assert_not_contains "identity is not stored securely"
   assert_eq "No problems found"
 }
+
+@test "a network sets its default identity and the identities allowed on it" {
+  dfx identity new --storage-mode plaintext alice
+  dfx identity new --storage-mode plaintext bob
+  define_project_network
+  jq '.networks.local.identities={"default":"alice","allowed":["alice","bob"]}' dfx.json | sponge dfx.json
+  dfx_start
+
+  assert_command dfx canister create e2e_project_backend --no-wallet
+  assert_command dfx canister info e2e_project_backend
+  assert_contains "$(dfx identity get-principal --identity alice)"
+  assert_command dfx identity whoami
+  assert_eq "test_id"
+
+  assert_command_fail dfx canister status e2e_project_backend --identity test_id
+  assert_contains "Identity 'test_id' cannot be used on network 'local'. Its allowed identities are: alice, bob"
+
+  jq '.networks.local.identities.allow_plaintext=false' dfx.json | sponge dfx.json
+  assert_command_fail dfx canister status e2e_project_backend
+  assert_contains "Identity 'alice' is stored in plaintext, which network 'local' does not allow."
+}
+
+@test "identities that a network does not allow are rejected wherever dfx loads them" {
+  dfx identity new --storage-mode plaintext alice
+  define_project_network
+  jq '.networks.local.identities={"allowed":["alice"]}' dfx.json | sponge dfx.json
+  dfx_start
+
+  assert_command_fail dfx canister create e2e_project_backend --no-wallet --identity test_id
+  assert_contains "Identity 'test_id' cannot be used on network 'local'. Its allowed identities are: alice"
+
+  assert_command_fail dfx canister create e2e_project_backend --no-wallet --identity alice --controller test_id
+  assert_contains "Identity 'test_id' cannot be used on network 'local'."
+
+  assert_command_fail dfx identity get-principal --identity test_id
+  assert_contains "Identity 'test_id' cannot be used on network 'local'."
+  assert_command dfx identity get-principal --identity test_id --network ic
+
+  assert_command_fail dfx identity delegate --identity test_id --to alice
+  assert_contains "Identity 'test_id' cannot be used on network 'local'."
+
+  assert_command dfx canister create e2e_project_backend --no-wallet --identity alice
+}
+
+@test "the ic network can be listed only for its identities" {
+  jq '.networks.ic={"providers":["https://icp0.io"],"identities":{"allowed":["ic_admin"]}}' dfx.json | sponge dfx.json
+  assert_command_fail dfx diagnose --network ic
+  assert_contains "Identity 'test_id' cannot be used on network 'ic'."
+}
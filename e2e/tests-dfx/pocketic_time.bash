@@ -0,0 +1,46 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new
+}
+
+teardown() {
+  dfx_stop
+
+  standard_teardown
+}
+
+@test "dfx pocketic time controls the time of PocketIC" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: time can only be controlled with PocketIC"
+  dfx_start
+
+  assert_command dfx pocketic time pause
+  assert_command dfx pocketic time set 2099-01-01T00:00:00Z
+  assert_command dfx pocketic time show
+  assert_eq "2099-01-01T00:00:00Z"
+
+  assert_command dfx pocketic time advance 1day
+  assert_command dfx pocketic time show
+  assert_eq "2099-01-02T00:00:00Z"
+
+  assert_command_fail dfx pocketic time set 2098-01-01T00:00:00Z
+  assert_contains "The time of PocketIC cannot be set back."
+
+  assert_command dfx pocketic time tick 3
+  assert_contains "Executed 3 round(s)."
+
+  assert_command dfx pocketic time resume
+  assert_command dfx deploy
+  assert_command dfx canister call e2e_project_backend greet '("time")'
+}
+
+@test "dfx pocketic time requires PocketIC" {
+  [[ ! "$USE_REPLICA" ]] && skip "skipped for pocketic"
+  dfx_start
+
+  assert_command_fail dfx pocketic time show
+}
@@ -81,3 +81,65 @@ teardown() {
 
   rm "$TMP_NAME_FILE"
 }
+
+@test "propose + approve + send with a threshold of approvers" {
+  install_asset counter
+  dfx_start
+  dfx deploy
+
+  dfx identity new alice --storage-mode plaintext
+  dfx identity new bob --storage-mode plaintext
+  ALICE="$(dfx identity get-principal --identity alice)"
+  BOB="$(dfx identity get-principal --identity bob)"
+  CANISTER_ID="$(dfx canister id hello_backend)"
+  dfx canister update-settings hello_backend --add-controller "$ALICE" --add-controller "$BOB"
+
+  assert_command dfx canister propose aaaaa-aa stop_canister "(record { canister_id = principal \"$CANISTER_ID\" })" \
+    --threshold 2 --approver "$ALICE" --approver "$BOB" --description "stop the counter"
+  assert_match "Proposal written to \[proposal.json\]"
+
+  assert_command dfx canister review proposal.json
+  assert_match "Description: stop the counter"
+  assert_match "Method name: stop_canister"
+  assert_match "canister_id = principal \"$CANISTER_ID\""
+  assert_match "Needs 2 more approvals."
+
+  assert_command_fail dfx canister approve proposal.json
+  assert_match "is not an approver of this proposal."
+
+  assert_command dfx canister approve proposal.json --identity alice
+  assert_match "1 of 2 approvals"
+  assert_command_fail dfx canister approve proposal.json --identity alice
+  assert_match "already approved this proposal."
+
+  echo y | assert_command_fail dfx canister send proposal.json
+  assert_match "has 1 of the 2 approvals it needs."
+
+  assert_command dfx canister approve proposal.json --identity bob
+  assert_match "2 of 2 approvals"
+  assert_command dfx canister review proposal.json
+  assert_match "Ready to send"
+
+  echo y | assert_command dfx canister send proposal.json
+  assert_command dfx canister status hello_backend
+  assert_match "Status: Stopped"
+}
+
+@test "sign --inspect decodes the message and checks its signature" {
+  install_asset greet
+  dfx_start
+  dfx deploy
+
+  assert_command dfx canister sign --update hello_backend greet '("Alice")'
+  assert_command dfx canister sign --inspect message.json
+  assert_match "Sender:         $(dfx identity get-principal)"
+  assert_match "Canister:       hello_backend \($(dfx canister id hello_backend)\)"
+  assert_match "Method name:    greet"
+  assert_match '"Alice"'
+  assert_match "Signature:      valid"
+
+  # the arg field of the file does not match the signed content
+  jq '.arg = [68, 73, 68, 76, 0, 0]' message.json > tampered.json
+  assert_command_fail dfx canister sign --inspect tampered.json
+  assert_match "do not match the signed message"
+}
@@ -531,3 +531,143 @@ teardown() {
     dfx killall
     assert_command_fail pgrep dfx replica pocket-ic
 }
+
+@test "dfx start creates the configured subnets and canisters can be pinned to them" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: only PocketIC supports multiple subnets"
+  dfx_new_assets hello
+  define_project_network
+  jq '.networks.local.replica.subnets={"application":2,"system":1}' dfx.json | sponge dfx.json
+  jq '.canisters.hello_backend.subnet="application:1"' dfx.json | sponge dfx.json
+
+  dfx_start
+  assert_command dfx info subnets
+  assert_contains "application:0"
+  assert_contains "application:1"
+  assert_contains "system:0"
+  assert_not_contains "verified_application:0"
+
+  # The first canister on a fresh subnet gets the first ID of its range.
+  RANGE_START=$(dfx info subnets | grep "^application:1 " | cut -d ' ' -f 3 | sed 's/\.\..*//')
+  assert_command dfx canister create hello_backend
+  assert_command dfx canister id hello_backend
+  assert_eq "$RANGE_START"
+
+  jq '.canisters.hello_frontend.subnet="application:5"' dfx.json | sponge dfx.json
+  assert_command_fail dfx canister create hello_frontend
+  assert_contains "Canister 'hello_frontend' is configured for subnet 'application:5', which does not exist."
+}
+
+@test "the replica rejects configured subnets" {
+  [[ ! "$USE_REPLICA" ]] && skip "skipped for pocketic: PocketIC supports multiple subnets"
+  dfx_new hello
+  define_project_network
+  jq '.networks.local.replica.subnets={"application":2}' dfx.json | sponge dfx.json
+
+  assert_command_fail dfx start --replica
+  assert_contains "The replica does not support configuring \`replica.subnets\`."
+}
+
+@test "dfx start --checkpoint saves and restores the local network state" {
+  dfx_new hello
+  define_project_network
+  # saving and restoring requires the settings that dfx_start uses
+  SETTINGS=(--artificial-delay 100)
+  [[ "$USE_REPLICA" ]] && SETTINGS+=(--replica)
+
+  dfx_start
+  dfx deploy hello_backend
+  CANISTER_ID=$(dfx canister id hello_backend)
+  dfx_stop
+
+  assert_command_fail dfx start --checkpoint restore seeded "${SETTINGS[@]}"
+  assert_contains "Checkpoint 'seeded' does not exist."
+
+  assert_command dfx start --checkpoint save seeded "${SETTINGS[@]}"
+  assert_contains "Saved checkpoint 'seeded'."
+  assert_command dfx start --checkpoint list
+  assert_contains "seeded"
+
+  dfx_start --clean
+  assert_command_fail dfx canister id hello_backend
+  dfx_stop
+
+  dfx_start --checkpoint restore seeded
+  assert_command dfx canister id hello_backend
+  assert_eq "$CANISTER_ID"
+  assert_command dfx canister call hello_backend greet '("checkpoint")'
+  assert_eq '("Hello, checkpoint!")'
+  dfx_stop
+
+  assert_command_fail dfx start --checkpoint restore seeded --artificial-delay 200
+  assert_contains "Checkpoint 'seeded' was saved with different network settings."
+}
+
+@test "dfx start installs the configured system canisters" {
+  [[ "$USE_REPLICA" ]] && skip "skipped for replica: only PocketIC supports system canisters"
+  dfx_new hello
+  define_project_network
+  dfx identity new --storage-mode plaintext alice
+  jq '.networks.local.system_canisters={"icp_ledger":{"initial_balances":{"alice":150000000}},"internet_identity":{}}' dfx.json | sponge dfx.json
+
+  dfx_start
+  assert_command dfx ledger balance --identity alice
+  assert_eq "1.50000000 ICP"
+  assert_command dfx canister info rdmx6-jaaaa-aaaaa-aaadq-cai
+  assert_contains "Module hash: 0x"
+
+  # restarting keeps the installed canisters and their state
+  dfx_stop
+  dfx_start
+  assert_command dfx ledger balance --identity alice
+  assert_eq "1.50000000 ICP"
+}
+
+@test "several named local networks run side by side" {
+  dfx_new hello
+  jq '.networks.local.bind="127.0.0.1:8000" | .networks.shard1={} | .networks.shard2={}' dfx.json | sponge dfx.json
+
+  assert_command dfx start --network shard1 --background
+  assert_command dfx start --network shard2 --background
+  assert_command_fail dfx start --network shard1 --background
+  assert_contains "dfx is already running"
+
+  SHARD1_PORT=$(dfx info webserver-port --network shard1)
+  SHARD2_PORT=$(dfx info webserver-port --network shard2)
+  assert_neq "$SHARD1_PORT" "$SHARD2_PORT"
+  assert_neq "$SHARD1_PORT" "8000"
+
+  assert_command dfx info local-networks
+  assert_contains "shard1 $SHARD1_PORT"
+  assert_contains "shard2 $SHARD2_PORT"
+  assert_not_contains "local "
+
+  assert_command dfx deploy hello_backend --network shard1
+  assert_command dfx canister call hello_backend greet '("shard1")' --network shard1
+  assert_contains "shard1"
+  assert_command_fail dfx canister id hello_backend --network shard2
+
+  assert_command dfx stop --network shard1
+  assert_command dfx ping shard2
+  assert_command dfx info local-networks
+  assert_not_contains "shard1"
+
+  assert_command dfx killall --network shard2
+  assert_contains "processes of network 'shard2'"
+  assert_command_fail dfx ping shard2
+}
+
+@test "dfx start --clean forgets the wallets of a named local network" {
+  dfx_new hello
+  jq '.networks.shard1={}' dfx.json | sponge dfx.json
+
+  assert_command dfx start --network shard1 --background
+  assert_command dfx identity get-wallet --network shard1
+  assert_command jq -r ".identities.default.shard1" .dfx/local/wallets.json
+  assert_neq "null"
+  assert_command dfx stop --network shard1
+
+  assert_command dfx start --network shard1 --clean --background
+  assert_command jq -r ".identities.default.shard1" .dfx/local/wallets.json
+  assert_eq "null"
+  assert_command dfx stop --network shard1
+}
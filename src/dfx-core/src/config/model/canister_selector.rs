@@ -0,0 +1,159 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Selects which canisters of dfx.json a command operates on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CanisterSelector {
+    /// Every canister in dfx.json.
+    All,
+
+    /// A single canister, by name.
+    Name(String),
+
+    /// Several canisters, by name.
+    Names(Vec<String>),
+
+    /// The canisters whose names match a glob pattern (if any)
+    /// and that have at least one of the given tags (if any).
+    Matching {
+        pattern: Option<String>,
+        tags: Vec<String>,
+    },
+}
+
+impl CanisterSelector {
+    /// Interprets a canister argument and the `--tag` options of a command.
+    ///
+    /// A canister argument containing `*` or `?` is treated as a glob pattern.
+    /// Without a canister argument or tags, all canisters are selected.
+    pub fn new(canister: Option<&str>, tags: &[String]) -> Self {
+        match canister {
+            None if tags.is_empty() => CanisterSelector::All,
+            Some(name) if tags.is_empty() && !is_glob_pattern(name) => {
+                CanisterSelector::Name(name.to_string())
+            }
+            _ => CanisterSelector::Matching {
+                pattern: canister.map(String::from),
+                tags: tags.to_vec(),
+            },
+        }
+    }
+
+    /// Returns the canister name if exactly one canister was named.
+    pub fn single(&self) -> Option<&str> {
+        match self {
+            CanisterSelector::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Whether a canister with the given name and tags is selected.
+    pub fn matches(&self, name: &str, canister_tags: &[String]) -> bool {
+        match self {
+            CanisterSelector::All => true,
+            CanisterSelector::Name(n) => n == name,
+            CanisterSelector::Names(names) => names.iter().any(|n| n == name),
+            CanisterSelector::Matching { pattern, tags } => {
+                pattern
+                    .as_deref()
+                    .is_none_or(|pattern| glob_match(pattern, name))
+                    && (tags.is_empty() || tags.iter().any(|tag| canister_tags.contains(tag)))
+            }
+        }
+    }
+}
+
+impl Display for CanisterSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CanisterSelector::All => write!(f, "all canisters"),
+            CanisterSelector::Name(name) => write!(f, "canister '{name}'"),
+            CanisterSelector::Names(names) => write!(f, "canisters '{}'", names.join("', '")),
+            CanisterSelector::Matching { pattern, tags } => {
+                write!(f, "canisters")?;
+                if let Some(pattern) = pattern {
+                    write!(f, " matching '{pattern}'")?;
+                }
+                if !tags.is_empty() {
+                    if pattern.is_some() {
+                        write!(f, " and")?;
+                    }
+                    write!(f, " tagged '{}'", tags.join("' or '"))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn is_glob_pattern(s: &str) -> bool {
+    s.contains(['*', '?'])
+}
+
+/// Matches `name` against a glob pattern where `*` matches any sequence of characters
+/// and `?` matches a single character.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` in the pattern, and the position in the name it was matched at.
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match("svc_*", "svc_users"));
+        assert!(glob_match("svc_*", "svc_"));
+        assert!(!glob_match("svc_*", "web_users"));
+        assert!(glob_match("*_backend", "hello_backend"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("svc_?", "svc_1"));
+        assert!(!glob_match("svc_?", "svc_12"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn selector_from_arguments() {
+        let tags = vec!["backend".to_string()];
+        assert_eq!(CanisterSelector::new(None, &[]), CanisterSelector::All);
+        assert_eq!(
+            CanisterSelector::new(Some("app"), &[]),
+            CanisterSelector::Name("app".to_string())
+        );
+        let selector = CanisterSelector::new(Some("svc_*"), &tags);
+        assert_eq!(selector.single(), None);
+        assert!(selector.matches("svc_users", &tags));
+        assert!(!selector.matches("svc_users", &[]));
+        assert!(!selector.matches("web", &tags));
+        assert_eq!(
+            selector.to_string(),
+            "canisters matching 'svc_*' and tagged 'backend'"
+        );
+        assert!(CanisterSelector::new(None, &tags).matches("web", &tags));
+    }
+}
@@ -3,6 +3,7 @@
 use crate::config::directories::get_user_dfx_config_dir;
 use crate::config::model::bitcoin_adapter::BitcoinAdapterLogLevel;
 use crate::config::model::canister_http_adapter::HttpAdapterLogLevel;
+use crate::config::model::canister_selector::CanisterSelector;
 use crate::config::model::extension_canister_type::apply_extension_canister_types;
 use crate::error::config::{GetOutputEnvFileError, GetTempPathError};
 use crate::error::dfx_config::AddDependenciesError::CanisterCircularDependency;
@@ -22,7 +23,7 @@ use crate::error::dfx_config::{
     GetComputeAllocationError, GetFreezingThresholdError, GetLogVisibilityError,
     GetMemoryAllocationError, GetPullCanistersError, GetRemoteCanisterIdError,
     GetReservedCyclesLimitError, GetSpecifiedIdError, GetWasmMemoryLimitError,
-    GetWasmMemoryThresholdError,
+    GetWasmMemoryThresholdError, SelectCanistersError,
 };
 use crate::error::fs::CanonicalizePathError;
 use crate::error::load_dfx_config::LoadDfxConfigError;
@@ -48,7 +49,7 @@ use crate::error::structured_file::StructuredFileError::DeserializeJsonFileFaile
 use crate::extension::manager::ExtensionManager;
 use crate::fs::create_dir_all;
 use crate::json::structure::{PossiblyStr, SerdeVec};
-use crate::json::{load_json_file, save_json_file};
+use crate::json::{load_json_file, save_json_file, save_json_file_with_format, JsonFormat};
 use crate::util::ByteSchema;
 use byte_unit::Byte;
 use candid::Principal;
@@ -102,6 +103,50 @@ pub struct ConfigCanistersCanisterRemote {
     pub id: BTreeMap<String, Principal>,
 }
 
+/// # Cycles Policy
+/// Keeps the cycles balance of a canister above a minimum.
+/// `dfx cycles ensure` tops the canister up to `target` when its balance is below `minimum`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigCanistersCanisterCycles {
+    /// # Minimum Balance
+    /// The number of cycles below which the canister is topped up.
+    #[schemars(with = "u128")]
+    pub minimum: u128,
+
+    /// # Target Balance
+    /// The number of cycles that the canister is topped up to. Must be at least `minimum`.
+    #[schemars(with = "u128")]
+    pub target: u128,
+
+    /// # Funding Source
+    /// Where the cycles for top-ups come from. Defaults to the cycles ledger.
+    #[serde(default)]
+    pub source: CyclesSource,
+}
+
+/// # Cycles Funding Source
+/// `cycles_ledger`: the cycles ledger account of the identity.
+/// `wallet`: the cycles wallet of the identity.
+/// `icp`: ICP of the identity, converted to cycles by the cycles minting canister.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CyclesSource {
+    #[default]
+    CyclesLedger,
+    Wallet,
+    Icp,
+}
+
+impl Display for CyclesSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CyclesSource::CyclesLedger => write!(f, "cycles ledger"),
+            CyclesSource::Wallet => write!(f, "wallet"),
+            CyclesSource::Icp => write!(f, "ICP"),
+        }
+    }
+}
+
 /// # Wasm Optimization Levels
 /// Wasm optimization levels that are passed to `wasm-opt`. "cycles" defaults to O3, "size" defaults to Oz.
 /// O4 through O0 focus on performance (with O0 performing no optimizations), and Oz and Os focus on reducing binary size, where Oz is more aggressive than Os.
@@ -234,6 +279,8 @@ pub struct TechStack {
 
 pub const DEFAULT_SHARED_LOCAL_BIND: &str = "127.0.0.1:4943"; // hex for "IC"
 pub const DEFAULT_PROJECT_LOCAL_BIND: &str = "127.0.0.1:8000";
+/// Local networks other than `local` bind to a free port, so that several can run side by side.
+pub const DEFAULT_NAMED_LOCAL_BIND: &str = "127.0.0.1:0";
 pub const DEFAULT_IC_GATEWAY: &str = "https://icp0.io";
 pub const DEFAULT_IC_GATEWAY_TRAILING_SLASH: &str = "https://icp0.io/";
 pub const DEFAULT_REPLICA_PORT: u16 = 8080;
@@ -262,11 +309,26 @@ pub struct ConfigCanistersCanister {
     #[serde(default)]
     pub initialization_values: InitializationValues,
 
+    /// # Cycles Policy
+    /// The cycles balance that `dfx cycles ensure` keeps the canister at.
+    pub cycles: Option<ConfigCanistersCanisterCycles>,
+
     /// # Dependencies
     /// Defines on which canisters this canister depends on.
     #[serde(default)]
     pub dependencies: Vec<String>,
 
+    /// # Tags
+    /// Labels used to select groups of canisters, e.g. `dfx deploy --tag backend`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// # Watched Paths
+    /// Additional files and directories that `dfx build --watch` and `dfx deploy --watch` watch for changes to this canister.
+    /// For custom canisters with build commands, list the inputs of the build here.
+    #[serde(default)]
+    pub watch: Vec<PathBuf>,
+
     /// # Force Frontend URL
     /// Mostly unused.
     /// If this value is not null, a frontend URL is displayed after deployment even if the canister type is not 'asset'.
@@ -333,6 +395,14 @@ pub struct ConfigCanistersCanister {
     #[schemars(with = "Option<String>")]
     pub specified_id: Option<Principal>,
 
+    /// # Local Subnet
+    /// The subnet of the local PocketIC instance to create the canister on.
+    /// Either a subnet kind with an optional zero-based index, e.g. `application` or `application:1`, or a subnet principal.
+    /// `dfx info subnets` lists the subnets of the running instance.
+    /// Ignored on networks other than local PocketIC networks.
+    /// The canister is created by the identity, as a wallet can only create canisters on its own subnet.
+    pub subnet: Option<String>,
+
     /// # Init Arg
     /// The Candid initialization argument for installing the canister.
     /// If the `--argument` or `--argument-file` argument is also provided, this `init_arg` field will be ignored.
@@ -532,7 +602,7 @@ pub struct CanisterDeclarationsConfig {
 
     /// # Languages to generate
     /// A list of languages to generate type declarations.
-    /// Supported options are 'js', 'ts', 'did', 'mo'.
+    /// Supported options are 'js', 'ts', 'did', 'mo', 'rs'.
     /// Default is ['js', 'ts', 'did'].
     pub bindings: Option<Vec<String>>,
 
@@ -546,6 +616,48 @@ pub struct CanisterDeclarationsConfig {
     /// Default is false
     #[serde(default)]
     pub node_compatibility: bool,
+
+    /// # Rust Bindings Configuration
+    /// Options for the Rust client that is generated if `bindings` contains 'rs'.
+    pub rust: Option<RustBindingsConfig>,
+
+    /// # Custom Templates
+    /// Handlebars templates to render into the declarations directory, keyed by the name of the file to generate.
+    /// Template paths are relative to the project root.
+    /// A template for 'index.js' or 'index.d.ts' replaces the bundled template for that file.
+    pub templates: Option<BTreeMap<String, PathBuf>>,
+}
+
+/// # Rust Bindings Configuration
+/// Options for the Rust client generated from a canister's Candid interface.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RustBindingsConfig {
+    /// # Target
+    /// Which kind of caller the generated client is for.
+    /// Default is 'ic-cdk'.
+    #[serde(default)]
+    pub target: RustBindingsTarget,
+
+    /// # Type Attributes
+    /// Attributes added to every generated type, e.g. '#[derive(Debug, Clone)]'.
+    pub type_attributes: Option<String>,
+
+    /// # Candid Crate
+    /// The path under which the generated code refers to the candid crate.
+    /// Default is 'candid'.
+    pub candid_crate: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RustBindingsTarget {
+    /// Inter-canister calls from a canister that uses ic-cdk.
+    #[default]
+    #[serde(rename = "ic-cdk")]
+    IcCdk,
+
+    /// Calls from off-chain code that uses ic-agent.
+    #[serde(rename = "ic-agent")]
+    IcAgent,
 }
 
 /// # Bitcoin Adapter Configuration
@@ -569,6 +681,49 @@ pub struct ConfigDefaultsBitcoin {
     /// The initialization argument for the bitcoin canister.
     #[serde(default = "default_bitcoin_canister_init_arg")]
     pub canister_init_arg: String,
+
+    /// # bitcoind
+    /// Runs a bitcoind node in regtest mode as part of the local network.
+    /// Implies `enabled`, and the adapter connects to it unless `nodes` are set.
+    #[serde(default)]
+    pub bitcoind: Option<ConfigBitcoind>,
+}
+
+/// # bitcoind Configuration
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigBitcoind {
+    /// # bitcoind Path
+    /// Path of the bitcoind binary. Defaults to bitcoind in the dfx cache, then on the PATH.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+
+    /// # P2P Port
+    /// The port that bitcoind listens on for peers, including the bitcoin adapter.
+    #[serde(default = "default_bitcoind_port")]
+    pub port: u16,
+
+    /// # RPC Port
+    /// The port of the JSON-RPC interface of bitcoind, used by `dfx bitcoin`.
+    #[serde(default = "default_bitcoind_rpc_port")]
+    pub rpc_port: u16,
+}
+
+pub fn default_bitcoind_port() -> u16 {
+    18444
+}
+
+pub fn default_bitcoind_rpc_port() -> u16 {
+    18443
+}
+
+impl Default for ConfigBitcoind {
+    fn default() -> Self {
+        ConfigBitcoind {
+            path: None,
+            port: default_bitcoind_port(),
+            rpc_port: default_bitcoind_rpc_port(),
+        }
+    }
 }
 
 pub fn default_bitcoin_log_level() -> BitcoinAdapterLogLevel {
@@ -586,6 +741,7 @@ impl Default for ConfigDefaultsBitcoin {
             nodes: None,
             log_level: default_bitcoin_log_level(),
             canister_init_arg: default_bitcoin_canister_init_arg(),
+            bitcoind: None,
         }
     }
 }
@@ -601,6 +757,13 @@ pub struct ConfigDefaultsCanisterHttp {
     /// The logging level of the adapter.
     #[serde(default)]
     pub log_level: HttpAdapterLogLevel,
+
+    /// # Mock Rules
+    /// Path of a JSON file with rules that HTTPS outcalls are matched against.
+    /// If set, dfx answers outcalls from these rules instead of sending them to the internet.
+    /// Relative paths are relative to the project root. Only supported by PocketIC.
+    #[serde(default)]
+    pub mock: Option<PathBuf>,
 }
 
 impl Default for ConfigDefaultsCanisterHttp {
@@ -608,6 +771,7 @@ impl Default for ConfigDefaultsCanisterHttp {
         ConfigDefaultsCanisterHttp {
             enabled: true,
             log_level: HttpAdapterLogLevel::default(),
+            mock: None,
         }
     }
 }
@@ -720,6 +884,29 @@ pub struct ConfigDefaultsReplica {
 
     /// Run replica with the provided log level. Default is 'error'. Debug prints still get displayed
     pub log_level: Option<ReplicaLogLevel>,
+
+    /// # Subnets
+    /// The number of application, system and verified application subnets PocketIC creates.
+    /// If set, `subnet_type` is ignored.
+    /// Not supported by the native replica.
+    pub subnets: Option<ReplicaSubnetsConfig>,
+}
+
+/// # Local Subnets
+/// Number of subnets of each type to create, in addition to the NNS, SNS, II, fiduciary and bitcoin subnets.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ReplicaSubnetsConfig {
+    /// # Application Subnets
+    #[serde(default)]
+    pub application: u8,
+
+    /// # System Subnets
+    #[serde(default)]
+    pub system: u8,
+
+    /// # Verified Application Subnets
+    #[serde(default)]
+    pub verified_application: u8,
 }
 
 /// Configuration for the HTTP gateway.
@@ -798,6 +985,7 @@ pub struct ConfigNetworkProvider {
     #[serde(default = "NetworkType::persistent")]
     pub r#type: NetworkType,
     pub playground: Option<PlaygroundConfig>,
+    pub identities: Option<ConfigNetworkIdentities>,
 }
 
 /// # Local Replica Configuration
@@ -806,6 +994,7 @@ pub struct ConfigLocalProvider {
     /// Bind address for the webserver.
     /// For the shared local network, the default is 127.0.0.1:4943.
     /// For project-specific local networks, the default is 127.0.0.1:8000.
+    /// For local networks with other names than `local`, the default is 127.0.0.1:0, which binds to a free port.
     pub bind: Option<String>,
 
     /// Persistence type of this network.
@@ -818,6 +1007,70 @@ pub struct ConfigLocalProvider {
     pub replica: Option<ConfigDefaultsReplica>,
     pub playground: Option<PlaygroundConfig>,
     pub proxy: Option<ConfigDefaultsProxy>,
+    pub system_canisters: Option<ConfigSystemCanisters>,
+    pub identities: Option<ConfigNetworkIdentities>,
+}
+
+/// # Network Identities
+/// The identities that dfx uses on a network.
+/// They also apply to the `ic` network if it is listed only for them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigNetworkIdentities {
+    /// # Default Identity
+    /// The identity used on this network instead of the selected identity, unless `--identity` is specified.
+    pub default: Option<String>,
+
+    /// # Allowed Identities
+    /// If set, only these identities can be used on this network.
+    pub allowed: Option<Vec<String>>,
+
+    /// # Allow Plaintext Identities
+    /// Whether identities whose keys are stored in plaintext can be used on this network. Defaults to true.
+    pub allow_plaintext: Option<bool>,
+}
+
+/// # System Canisters
+/// System canisters that `dfx start` installs at their mainnet canister IDs.
+/// Only supported by PocketIC.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigSystemCanisters {
+    /// # ICP Ledger
+    pub icp_ledger: Option<ConfigSystemLedger>,
+
+    /// # Cycles Minting Canister
+    pub cmc: Option<ConfigSystemCanister>,
+
+    /// # Internet Identity
+    /// The development build of Internet Identity.
+    pub internet_identity: Option<ConfigSystemCanister>,
+
+    /// # Cycles Ledger
+    pub cycles_ledger: Option<ConfigSystemCanister>,
+
+    /// # NNS Governance
+    pub nns_governance: Option<ConfigSystemCanister>,
+}
+
+/// # System Canister Configuration
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigSystemCanister {
+    /// # Initialization Argument
+    /// Candid replacing the initialization argument that dfx passes by default.
+    pub init_arg: Option<String>,
+}
+
+/// # System Ledger Configuration
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigSystemLedger {
+    /// # Initialization Argument
+    /// Candid replacing the initialization argument that dfx passes by default.
+    /// `initial_balances` is ignored if this is set.
+    pub init_arg: Option<String>,
+
+    /// # Initial Balances
+    /// Balances in e8s of the default accounts of identities, by identity name.
+    #[serde(default)]
+    pub initial_balances: BTreeMap<String, u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
@@ -827,6 +1080,15 @@ pub enum ConfigNetwork {
     ConfigLocalProvider(ConfigLocalProvider),
 }
 
+impl ConfigNetwork {
+    pub fn identities(&self) -> Option<&ConfigNetworkIdentities> {
+        match self {
+            ConfigNetwork::ConfigNetworkProvider(network) => network.identities.as_ref(),
+            ConfigNetwork::ConfigLocalProvider(network) => network.identities.as_ref(),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum Profile {
     // debug is for development only
@@ -963,6 +1225,59 @@ impl ConfigInterface {
             })
     }
 
+    /// Return the names of the canisters selected by `selector`, in alphabetical order.
+    /// Selecting all canisters of a configuration without canisters returns no names.
+    pub fn get_selected_canister_names(
+        &self,
+        selector: &CanisterSelector,
+    ) -> Result<Vec<String>, SelectCanistersError> {
+        let Some(canister_map) = &self.canisters else {
+            return match selector {
+                CanisterSelector::All => Ok(vec![]),
+                _ => Err(SelectCanistersError::CanistersFieldDoesNotExist()),
+            };
+        };
+        let named = match selector {
+            CanisterSelector::Name(name) => std::slice::from_ref(name),
+            CanisterSelector::Names(names) => names.as_slice(),
+            _ => &[],
+        };
+        if let Some(name) = named.iter().find(|name| !canister_map.contains_key(*name)) {
+            return Err(SelectCanistersError::CanisterNotFound(name.clone()));
+        }
+        let names: Vec<String> = canister_map
+            .iter()
+            .filter(|(name, canister)| selector.matches(name, &canister.tags))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if names.is_empty() && *selector != CanisterSelector::All {
+            return Err(SelectCanistersError::NoCanistersMatch(selector.to_string()));
+        }
+        Ok(names)
+    }
+
+    /// Return the names of the canisters selected by `selector` and all of their dependencies.
+    pub fn get_selected_canister_names_with_dependencies(
+        &self,
+        selector: &CanisterSelector,
+    ) -> Result<Vec<String>, SelectCanistersError> {
+        let canister_map = self
+            .canisters
+            .as_ref()
+            .ok_or(SelectCanistersError::CanistersFieldDoesNotExist())?;
+        if *selector == CanisterSelector::All {
+            return Ok(canister_map.keys().cloned().collect());
+        }
+        let mut names = HashSet::new();
+        for selected in self.get_selected_canister_names(selector)? {
+            let mut path = vec![];
+            add_dependencies(canister_map, &mut names, &mut path, &selected).map_err(|err| {
+                SelectCanistersError::AddDependenciesFailed(selected.clone(), err)
+            })?;
+        }
+        Ok(names.into_iter().collect())
+    }
+
     pub fn get_remote_canister_id(
         &self,
         canister: &str,
@@ -1148,6 +1463,7 @@ fn add_dependencies(
 pub struct Config {
     path: PathBuf,
     json: Value,
+    format: JsonFormat,
     // public interface to the config:
     pub config: ConfigInterface,
 }
@@ -1196,6 +1512,12 @@ impl Config {
         Config::from_dir(&working_dir, extension_manager)
     }
 
+    /// Returns the path of the dfx.json that applies to the current directory, without loading it.
+    pub fn path_from_current_dir() -> Result<Option<PathBuf>, LoadDfxConfigError> {
+        let working_dir = std::env::current_dir().map_err(DetermineCurrentWorkingDirFailed)?;
+        Config::resolve_config_path(&working_dir).map_err(ResolveConfigPath)
+    }
+
     fn from_slice(
         path: PathBuf,
         content: &[u8],
@@ -1203,11 +1525,25 @@ impl Config {
     ) -> Result<Config, LoadDfxConfigError> {
         let json: Value = serde_json::from_slice(content)
             .map_err(|e| LoadDfxConfigError::DeserializeValueFailed(Box::new(path.clone()), e))?;
-        let effective_json = apply_extension_canister_types(json.clone(), extension_manager)?;
+        let config = Config::interface_from_json(&path, &json, extension_manager)?;
+        let format = JsonFormat::detect(content);
+        Ok(Config {
+            path,
+            json,
+            format,
+            config,
+        })
+    }
 
-        let config = serde_json::from_value(effective_json)
-            .map_err(|e| LoadDfxConfigError::DeserializeValueFailed(Box::new(path.clone()), e))?;
-        Ok(Config { path, json, config })
+    fn interface_from_json(
+        path: &Path,
+        json: &Value,
+        extension_manager: Option<&ExtensionManager>,
+    ) -> Result<ConfigInterface, LoadDfxConfigError> {
+        let effective_json = apply_extension_canister_types(json.clone(), extension_manager)?;
+        serde_json::from_value(effective_json).map_err(|e| {
+            LoadDfxConfigError::DeserializeValueFailed(Box::new(path.to_path_buf()), e)
+        })
     }
 
     /// Create a configuration from a string.
@@ -1242,6 +1578,18 @@ impl Config {
         &self.config
     }
 
+    /// Replaces the JSON of this configuration,
+    /// provided that it still deserializes into a valid dfx.json.
+    pub fn update_json(
+        &mut self,
+        json: Value,
+        extension_manager: Option<&ExtensionManager>,
+    ) -> Result<(), LoadDfxConfigError> {
+        self.config = Config::interface_from_json(&self.path, &json, extension_manager)?;
+        self.json = json;
+        Ok(())
+    }
+
     pub fn get_project_root(&self) -> &Path {
         // a configuration path contains a file name specifically. As
         // such we should be returning at least root as parent. If
@@ -1279,7 +1627,7 @@ impl Config {
     }
 
     pub fn save(&self) -> Result<(), StructuredFileError> {
-        save_json_file(&self.path, &self.json)
+        save_json_file_with_format(&self.path, &self.json, &self.format)
     }
 }
 
@@ -1361,6 +1709,7 @@ impl<'de> Visitor<'de> for PropertiesVisitor {
 pub struct NetworksConfig {
     path: PathBuf,
     json: Value,
+    format: JsonFormat,
     // public interface to the networks config:
     networks_config: NetworksConfigInterface,
 }
@@ -1372,6 +1721,23 @@ impl NetworksConfig {
     pub fn get_interface(&self) -> &NetworksConfigInterface {
         &self.networks_config
     }
+    pub fn get_json(&self) -> &Value {
+        &self.json
+    }
+
+    /// Replaces the JSON of this configuration,
+    /// provided that it still deserializes into a valid networks.json.
+    pub fn update_json(&mut self, json: Value) -> Result<(), StructuredFileError> {
+        let networks = serde_json::from_value(json.clone())
+            .map_err(|e| DeserializeJsonFileFailed(Box::new(self.path.clone()), e))?;
+        self.networks_config = NetworksConfigInterface { networks };
+        self.json = json;
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), StructuredFileError> {
+        save_json_file_with_format(&self.path, &self.json, &self.format)
+    }
 
     pub fn new() -> Result<NetworksConfig, LoadNetworksConfigError> {
         let dir = get_user_dfx_config_dir().map_err(GetNetworkConfigPathFailed)?;
@@ -1383,6 +1749,7 @@ impl NetworksConfig {
             Ok(NetworksConfig {
                 path,
                 json: Default::default(),
+                format: JsonFormat::default(),
                 networks_config: NetworksConfigInterface {
                     networks: BTreeMap::new(),
                 },
@@ -1399,9 +1766,11 @@ impl NetworksConfig {
         let json = serde_json::from_slice(&content)
             .map_err(|e| DeserializeJsonFileFailed(Box::new(path.to_path_buf()), e))?;
         let path = PathBuf::from(path);
+        let format = JsonFormat::detect(&content);
         Ok(NetworksConfig {
             path,
             json,
+            format,
             networks_config,
         })
     }
@@ -1652,10 +2021,74 @@ mod tests {
                 providers: vec![String::from("https://1.2.3.4:5000")],
                 r#type: NetworkType::Ephemeral,
                 playground: None,
+                identities: None,
             })
         );
     }
 
+    #[test]
+    fn update_json_validates_config() {
+        let mut config = Config::from_str(r#"{"canisters": {}}"#).unwrap();
+
+        let mut json = config.get_json().clone();
+        json["canisters"]["app"] = serde_json::json!({ "type": "rust" });
+        assert!(config.update_json(json, None).is_err());
+        assert!(config.get_config().get_canister_config("app").is_err());
+
+        let mut json = config.get_json().clone();
+        json["canisters"]["app"] = serde_json::json!({ "type": "motoko", "main": "main.mo" });
+        config.update_json(json, None).unwrap();
+        assert!(config.get_config().get_canister_config("app").is_ok());
+    }
+
+    #[test]
+    fn select_canisters_by_tag_and_pattern() {
+        let config = Config::from_str(
+            r#"{
+              "canisters": {
+                "svc_a": { "main": "a.mo", "tags": ["backend"] },
+                "svc_b": { "main": "b.mo", "tags": ["backend"], "dependencies": ["shared"] },
+                "shared": { "main": "shared.mo" },
+                "web": { "main": "web.mo", "tags": ["frontend"], "dependencies": ["svc_a"] }
+              }
+        }"#,
+        )
+        .unwrap();
+        let config_interface = config.get_config();
+        let select = |canister: Option<&str>, tags: &[&str]| {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            CanisterSelector::new(canister, &tags)
+        };
+
+        assert_eq!(
+            config_interface
+                .get_selected_canister_names(&select(None, &["backend"]))
+                .unwrap(),
+            ["svc_a", "svc_b"]
+        );
+        assert_eq!(
+            config_interface
+                .get_selected_canister_names(&select(Some("svc_*"), &["frontend"]))
+                .map_err(|e| e.to_string())
+                .unwrap_err(),
+            "No canisters matching 'svc_*' and tagged 'frontend' in dfx.json."
+        );
+        assert_eq!(
+            config_interface
+                .get_selected_canister_names(&select(None, &[]))
+                .unwrap()
+                .len(),
+            4
+        );
+
+        let mut names = config_interface
+            .get_selected_canister_names_with_dependencies(&select(Some("*b"), &[]))
+            .unwrap();
+        names.sort();
+        // "web" matches "*b" too, and pulls in "svc_a" as a dependency.
+        assert_eq!(names, ["shared", "svc_a", "svc_b", "web"]);
+    }
+
     #[test]
     fn get_correct_initialization_values() {
         let config = Config::from_str(
@@ -1,9 +1,9 @@
 use crate::config::model::bitcoin_adapter;
 use crate::config::model::canister_http_adapter::HttpAdapterLogLevel;
 use crate::config::model::dfinity::{
-    to_socket_addr, ConfigDefaultsBitcoin, ConfigDefaultsCanisterHttp, ConfigDefaultsProxy,
-    ConfigDefaultsReplica, ReplicaLogLevel, ReplicaSubnetType, DEFAULT_PROJECT_LOCAL_BIND,
-    DEFAULT_SHARED_LOCAL_BIND,
+    to_socket_addr, ConfigBitcoind, ConfigDefaultsBitcoin, ConfigDefaultsCanisterHttp,
+    ConfigDefaultsProxy, ConfigDefaultsReplica, ConfigSystemCanisters, ReplicaLogLevel,
+    ReplicaSubnetType, DEFAULT_PROJECT_LOCAL_BIND, DEFAULT_SHARED_LOCAL_BIND,
 };
 use crate::config::model::replica_config::CachedConfig;
 use crate::error::network_config::{
@@ -48,6 +48,7 @@ pub struct LocalServerDescriptor {
     pub canister_http: ConfigDefaultsCanisterHttp,
     pub proxy: ConfigDefaultsProxy,
     pub replica: ConfigDefaultsReplica,
+    pub system_canisters: ConfigSystemCanisters,
 
     pub scope: LocalNetworkScopeDescriptor,
 
@@ -70,6 +71,7 @@ impl LocalServerDescriptor {
         canister_http: ConfigDefaultsCanisterHttp,
         proxy: ConfigDefaultsProxy,
         replica: ConfigDefaultsReplica,
+        system_canisters: ConfigSystemCanisters,
         scope: LocalNetworkScopeDescriptor,
         legacy_pid_path: Option<PathBuf>,
     ) -> Result<Self, NetworkConfigError> {
@@ -83,6 +85,7 @@ impl LocalServerDescriptor {
             canister_http,
             proxy,
             replica,
+            system_canisters,
             scope,
             legacy_pid_path,
         })
@@ -124,6 +127,22 @@ impl LocalServerDescriptor {
         self.data_directory.join("ic-btc-adapter-socket-path")
     }
 
+    /// This file contains the pid of the bitcoind process
+    pub fn bitcoind_pid_path(&self) -> PathBuf {
+        self.data_directory.join("bitcoind-pid")
+    }
+
+    /// This file contains the credentials for the JSON-RPC interface of bitcoind.
+    /// bitcoind removes it when it stops.
+    pub fn bitcoind_rpc_cookie_path(&self) -> PathBuf {
+        self.data_directory.join("bitcoind-rpc-cookie")
+    }
+
+    /// The blocks and wallets of bitcoind.
+    pub fn bitcoind_data_dir(&self) -> PathBuf {
+        self.data_dir_by_settings_digest().join("bitcoind")
+    }
+
     /// This file contains the configuration for the ic-https-outcalls-adapter
     pub fn canister_http_adapter_config_path(&self) -> PathBuf {
         self.data_directory.join("ic-canister-http-config.json")
@@ -139,6 +158,11 @@ impl LocalServerDescriptor {
         self.data_directory.join("ic-canister-http-socket-path")
     }
 
+    /// This file contains the HTTPS outcalls answered from mock rules, one JSON object per line
+    pub fn canister_http_mock_log_path(&self) -> PathBuf {
+        self.data_directory.join("canister-http-mock.log")
+    }
+
     /// The replica configuration directory doesn't actually contain replica configuration.
     /// It contains two files:
     ///   - replica-1.port  contains the listening port of the running replica process
@@ -227,6 +251,18 @@ impl LocalServerDescriptor {
         self.data_directory.join("replica-effective-config.json")
     }
 
+    /// Checkpoints saved with `dfx start --checkpoint save`.
+    /// This is a sibling of the data directory so that `dfx start --clean` keeps them.
+    pub fn checkpoints_dir(&self) -> PathBuf {
+        let mut name = self
+            .data_directory
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        name.push("-checkpoints");
+        self.data_directory.with_file_name(name)
+    }
+
     pub fn effective_config_path_by_settings_digest(&self) -> PathBuf {
         self.data_dir_by_settings_digest()
             .join("replica-effective-config.json")
@@ -257,6 +293,14 @@ impl LocalServerDescriptor {
         Self { bitcoin, ..self }
     }
 
+    pub fn with_bitcoind(self, bitcoind: ConfigBitcoind) -> LocalServerDescriptor {
+        let bitcoin = ConfigDefaultsBitcoin {
+            bitcoind: Some(bitcoind),
+            ..self.bitcoin
+        };
+        Self { bitcoin, ..self }
+    }
+
     pub fn with_proxy_domains(self, domains: Vec<String>) -> LocalServerDescriptor {
         let proxy = ConfigDefaultsProxy {
             domain: Some(SerdeVec::Many(domains)),
@@ -302,6 +346,12 @@ impl LocalServerDescriptor {
                 "".to_string()
             };
             debug!(log, "    nodes: {:?}{}", nodes, diffs);
+            if let Some(bitcoind) = &self.bitcoin.bitcoind {
+                debug!(
+                    log,
+                    "    bitcoind: port {}, rpc port {}", bitcoind.port, bitcoind.rpc_port
+                );
+            }
         } else {
             debug!(log, "  bitcoin: disabled");
         }
@@ -335,6 +385,15 @@ impl LocalServerDescriptor {
             "".to_string()
         };
         debug!(log, "    subnet type: {:?}{}", subnet_type, diffs);
+        if let Some(subnets) = &self.replica.subnets {
+            debug!(
+                log,
+                "    subnets: {} application, {} system, {} verified application",
+                subnets.application,
+                subnets.system,
+                subnets.verified_application
+            );
+        }
 
         let log_level = self.replica.log_level.unwrap_or_default();
         let diffs: String = if log_level != ReplicaLogLevel::default() {
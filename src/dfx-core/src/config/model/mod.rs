@@ -1,6 +1,7 @@
 pub mod bitcoin_adapter;
 pub mod canister_http_adapter;
 pub mod canister_id_store;
+pub mod canister_selector;
 pub mod dfinity;
 pub mod extension_canister_type;
 pub mod local_server_descriptor;
@@ -1,4 +1,4 @@
-use crate::config::model::dfinity::{ReplicaLogLevel, ReplicaSubnetType};
+use crate::config::model::dfinity::{ReplicaLogLevel, ReplicaSubnetType, ReplicaSubnetsConfig};
 use candid::Principal;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -55,6 +55,8 @@ pub struct ReplicaConfig {
     pub canister_http_adapter: CanisterHttpAdapterConfig,
     pub log_level: ReplicaLogLevel,
     pub artificial_delay: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subnets: Option<ReplicaSubnetsConfig>,
 }
 
 impl ReplicaConfig {
@@ -89,9 +91,23 @@ impl ReplicaConfig {
             },
             log_level,
             artificial_delay,
+            subnets: None,
         }
     }
 
+    pub fn with_instance_id(self, instance_id: usize) -> Self {
+        Self {
+            instance_id: Some(instance_id),
+            ..self
+        }
+    }
+    pub fn get_instance_id(&self) -> Option<usize> {
+        self.instance_id
+    }
+    pub fn with_subnets(self, subnets: Option<ReplicaSubnetsConfig>) -> Self {
+        ReplicaConfig { subnets, ..self }
+    }
+
     pub fn with_port(self, port: u16) -> Self {
         ReplicaConfig {
             http_handler: self.http_handler.with_port(port),
@@ -190,10 +206,84 @@ pub enum CachedReplicaConfig<'a> {
     PocketIc { config: Cow<'a, ReplicaConfig> },
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalSubnetKind {
+    Application,
+    System,
+    VerifiedApplication,
+    Nns,
+    Sns,
+    Ii,
+    Fiduciary,
+    Bitcoin,
+}
+
+impl std::fmt::Display for LocalSubnetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Application => "application",
+            Self::System => "system",
+            Self::VerifiedApplication => "verified_application",
+            Self::Nns => "nns",
+            Self::Sns => "sns",
+            Self::Ii => "ii",
+            Self::Fiduciary => "fiduciary",
+            Self::Bitcoin => "bitcoin",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct CanisterIdRange {
+    pub start: Principal,
+    pub end: Principal,
+}
+
+/// A subnet of a running PocketIC instance.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LocalSubnet {
+    pub kind: LocalSubnetKind,
+    /// Position among the subnets of the same kind, starting at 0.
+    pub index: usize,
+    pub subnet_id: Principal,
+    pub canister_ranges: Vec<CanisterIdRange>,
+}
+
+impl LocalSubnet {
+    /// The name used to refer to the subnet in dfx.json, e.g. `application:1`.
+    pub fn name(&self) -> String {
+        format!("{}:{}", self.kind, self.index)
+    }
+
+    /// A canister ID on this subnet, to use as effective canister ID when creating canisters on it.
+    pub fn effective_canister_id(&self) -> Option<Principal> {
+        self.canister_ranges.first().map(|range| range.start)
+    }
+
+    /// Whether `selector` refers to this subnet.
+    /// The selector is either a subnet kind with an optional index (`application`, `application:1`) or the subnet principal.
+    pub fn matches(&self, selector: &str) -> bool {
+        if self.subnet_id.to_text() == selector {
+            return true;
+        }
+        let (kind, index) = match selector.split_once(':') {
+            Some((kind, index)) => (kind, index.parse::<usize>().ok()),
+            None => (selector, Some(0)),
+        };
+        kind == self.kind.to_string() && index == Some(self.index)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct CachedConfig<'a> {
     pub replica_rev: String,
     pub effective_canister_id: Option<Principal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subnets: Vec<LocalSubnet>,
     #[serde(flatten)]
     pub config: CachedReplicaConfig<'a>,
 }
@@ -203,6 +293,8 @@ impl<'a> CachedConfig<'a> {
         Self {
             replica_rev,
             effective_canister_id: None,
+            instance_id: None,
+            subnets: vec![],
             config: CachedReplicaConfig::Replica {
                 config: Cow::Borrowed(config),
             },
@@ -216,6 +308,8 @@ impl<'a> CachedConfig<'a> {
         Self {
             replica_rev,
             effective_canister_id,
+            instance_id: None,
+            subnets: vec![],
             config: CachedReplicaConfig::PocketIc {
                 config: Cow::Borrowed(config),
             },
@@ -228,4 +322,47 @@ impl<'a> CachedConfig<'a> {
     pub fn get_effective_canister_id(&self) -> Option<Principal> {
         self.effective_canister_id
     }
+    pub fn with_instance_id(self, instance_id: usize) -> Self {
+        Self {
+            instance_id: Some(instance_id),
+            ..self
+        }
+    }
+    pub fn get_instance_id(&self) -> Option<usize> {
+        self.instance_id
+    }
+    pub fn with_subnets(self, subnets: Vec<LocalSubnet>) -> Self {
+        Self { subnets, ..self }
+    }
+    pub fn get_subnets(&self) -> &[LocalSubnet] {
+        &self.subnets
+    }
+    pub fn find_subnet(&self, selector: &str) -> Option<&LocalSubnet> {
+        self.subnets.iter().find(|subnet| subnet.matches(selector))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_subnet_selectors() {
+        let subnet = LocalSubnet {
+            kind: LocalSubnetKind::VerifiedApplication,
+            index: 1,
+            subnet_id: Principal::management_canister(),
+            canister_ranges: vec![],
+        };
+        assert!(subnet.matches("verified_application:1"));
+        assert!(subnet.matches(&Principal::management_canister().to_text()));
+        assert!(!subnet.matches("verified_application"));
+        assert!(!subnet.matches("application:1"));
+        assert!(!subnet.matches("verified_application:x"));
+
+        let first = LocalSubnet { index: 0, ..subnet };
+        assert!(first.matches("verified_application"));
+        assert!(first.matches("verified_application:0"));
+        assert_eq!(first.name(), "verified_application:0");
+    }
 }
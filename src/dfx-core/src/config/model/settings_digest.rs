@@ -1,4 +1,4 @@
-use crate::config::model::dfinity::{ReplicaLogLevel, ReplicaSubnetType};
+use crate::config::model::dfinity::{ReplicaLogLevel, ReplicaSubnetType, ReplicaSubnetsConfig};
 use crate::config::model::local_server_descriptor::LocalServerDescriptor;
 use candid::Deserialize;
 use serde::Serialize;
@@ -38,6 +38,8 @@ struct ReplicaSettings {
     pub canister_http_adapter: CanisterHttpAdapterSettings,
     pub log_level: ReplicaLogLevel,
     pub artificial_delay: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subnets: Option<ReplicaSubnetsConfig>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
@@ -100,6 +102,7 @@ fn get_replica_backend_settings(
             .log_level
             .unwrap_or_default(),
         artificial_delay,
+        subnets: local_server_descriptor.replica.subnets,
     };
     BackendSettings {
         settings: Cow::Owned(replica_settings),
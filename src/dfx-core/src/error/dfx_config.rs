@@ -28,6 +28,21 @@ pub enum GetCanisterNamesWithDependenciesError {
     AddDependenciesFailed(String, #[source] AddDependenciesError),
 }
 
+#[derive(Error, Debug)]
+pub enum SelectCanistersError {
+    #[error("No canisters in the configuration file.")]
+    CanistersFieldDoesNotExist(),
+
+    #[error("Canister '{0}' not found in dfx.json.")]
+    CanisterNotFound(String),
+
+    #[error("No {0} in dfx.json.")]
+    NoCanistersMatch(String),
+
+    #[error("Failed to add dependencies for canister '{0}'")]
+    AddDependenciesFailed(String, #[source] AddDependenciesError),
+}
+
 #[derive(Error, Debug)]
 pub enum GetComputeAllocationError {
     #[error("Failed to get compute allocation for canister '{0}'")]
@@ -1,7 +1,7 @@
 use crate::error::config::GetSharedWalletConfigPathError;
 use crate::error::fs::{
-    ReadFileError, ReadPermissionsError, RemoveDirectoryAndContentsError, RemoveDirectoryError,
-    RemoveFileError, RenameError, SetPermissionsError, WriteFileError,
+    ReadDirError, ReadFileError, ReadPermissionsError, RemoveDirectoryAndContentsError,
+    RemoveDirectoryError, RemoveFileError, RenameError, SetPermissionsError, WriteFileError,
 };
 use crate::error::{
     config::ConfigError,
@@ -12,6 +12,7 @@ use crate::error::{
     structured_file::StructuredFileError,
     wallet_config::{SaveWalletConfigError, WalletConfigError},
 };
+use crate::identity::identity_manager::IdentityStorageMode;
 use candid::types::principal::PrincipalError;
 use ic_agent::identity::PemError;
 use ic_identity_hsm::HardwareIdentityError;
@@ -19,6 +20,39 @@ use std::path::PathBuf;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
+#[derive(Error, Debug)]
+pub enum BackupIdentitiesError {
+    #[error("Failed to encrypt identity backup")]
+    EncryptBackupFailed(#[source] EncryptionError),
+
+    #[error("Failed to generate encryption configuration for identity backup")]
+    GenerateBackupEncryptionConfigurationFailed(#[source] EncryptionError),
+
+    #[error("Failed to get identity config")]
+    GetIdentityConfigFailed(#[source] GetIdentityConfigOrDefaultError),
+
+    #[error("Failed to list identities")]
+    ListIdentitiesFailed(#[source] ReadDirError),
+
+    #[error("Failed to load delegation chain")]
+    LoadDelegationChainFailed(#[source] StructuredFileError),
+
+    #[error("Failed to load pem")]
+    LoadPemFailed(#[source] LoadPemError),
+
+    #[error("Failed to load wallet configuration")]
+    LoadWalletConfigFailed(#[source] WalletConfigError),
+
+    #[error("Failed to read encrypted pem file")]
+    ReadEncryptedPemFailed(#[source] ReadFileError),
+
+    #[error("Failed to serialize identity backup")]
+    SerializeBackupContentFailed(#[source] serde_json::Error),
+
+    #[error("Failed to write identity backup")]
+    WriteBackupFailed(#[source] StructuredFileError),
+}
+
 #[derive(Error, Debug)]
 pub enum CallSenderFromWalletError {
     #[error("Failed to read principal from id '{0}', and did not find a wallet for that identity")]
@@ -33,6 +67,27 @@ pub enum CallSenderFromWalletError {
     ),
 }
 
+#[derive(Error, Debug)]
+pub enum ChangeIdentityPasswordError {
+    #[error("Failed to generate a fresh encryption configuration")]
+    GenerateFreshEncryptionConfigurationFailed(#[source] EncryptionError),
+
+    #[error("Failed to get identity config")]
+    GetIdentityConfigFailed(#[source] GetIdentityConfigOrDefaultError),
+
+    #[error("Identity does not exist")]
+    IdentityDoesNotExist(#[source] RequireIdentityExistsError),
+
+    #[error("Identity '{0}' is not password-protected.")]
+    IdentityNotPasswordProtected(String),
+
+    #[error("Failed to load pem")]
+    LoadPemFailed(#[source] LoadPemError),
+
+    #[error("Failed to store pem with the new password")]
+    ReplacePemFailed(#[source] ReplacePemError),
+}
+
 #[derive(Error, Debug)]
 pub enum ConvertMnemonicToKeyError {
     #[error("Failed to derive extended secret key from path")]
@@ -99,6 +154,30 @@ pub enum CreateNewIdentityError {
     ValidatePemFileFailed(#[source] ValidatePemFileError),
 }
 
+#[derive(Error, Debug)]
+pub enum DelegateError {
+    #[error("The anonymous identity cannot delegate")]
+    CannotDelegateAnonymousIdentity(),
+
+    #[error("Failed to sign the delegation: {0}")]
+    SignDelegationFailed(String),
+}
+
+#[derive(Error, Debug)]
+pub enum DelegationChainError {
+    #[error("The delegation chain is empty")]
+    EmptyChain(),
+
+    #[error("Invalid expiration in delegation chain")]
+    InvalidExpiration(#[source] std::num::ParseIntError),
+
+    #[error("Invalid hex in delegation chain")]
+    InvalidHex(#[source] hex::FromHexError),
+
+    #[error("Invalid target in delegation chain")]
+    InvalidTarget(#[source] PrincipalError),
+}
+
 #[derive(Error, Debug)]
 pub enum ExportIdentityError {
     #[error("Failed to get identity config")]
@@ -169,6 +248,27 @@ pub enum InstantiateIdentityFromNameError {
 
     #[error("Identity must exist")]
     RequireIdentityExistsFailed(#[source] RequireIdentityExistsError),
+
+    #[error(transparent)]
+    IdentityNotAllowed(#[from] IdentityNotAllowedError),
+}
+
+#[derive(Error, Debug)]
+pub enum LoadDelegatedIdentityError {
+    #[error("The delegation of identity '{0}' expired at {1}. Renew it with `dfx identity delegate --to {0}`, or with `dfx identity new {0} --type ii-local --force` for an Internet Identity identity.")]
+    DelegationExpired(String, String),
+
+    #[error("Invalid delegation chain for identity '{0}'")]
+    InvalidDelegationChain(String, #[source] DelegationChainError),
+
+    #[error("Failed to load the delegation chain of identity '{0}'")]
+    LoadDelegationChainFailed(String, #[source] StructuredFileError),
+
+    #[error("The delegation chain of identity '{0}' does not delegate to its key")]
+    SessionKeyMismatch(String),
+
+    #[error("The delegation chain of identity '{0}' is not valid: {1}")]
+    VerifyDelegationChainFailed(String, String),
 }
 
 #[derive(Error, Debug)]
@@ -178,6 +278,21 @@ pub enum LoadIdentityError {
 
     #[error("Failed to instantiate identity")]
     NewIdentityFailed(#[source] NewIdentityError),
+
+    #[error(transparent)]
+    IdentityNotAllowed(#[from] IdentityNotAllowedError),
+}
+
+#[derive(Error, Debug)]
+pub enum IdentityNotAllowedError {
+    #[error("Identity '{0}' cannot be used on network '{1}'. Its allowed identities are: {2}")]
+    NotAllowed(String, String, String),
+
+    #[error("Identity '{0}' is stored in plaintext, which network '{1}' does not allow. Change it with `dfx identity set-storage-mode`.")]
+    PlaintextNotAllowed(String, String),
+
+    #[error("Failed to get identity config")]
+    GetIdentityConfigFailed(#[source] GetIdentityConfigOrDefaultError),
 }
 
 #[derive(Error, Debug)]
@@ -233,6 +348,9 @@ pub enum NewIdentityError {
     #[error("Failed to load PEM identity")]
     LoadPemIdentityFailed(#[source] LoadPemIdentityError),
 
+    #[error("Failed to load delegated identity")]
+    LoadDelegatedIdentityFailed(#[source] LoadDelegatedIdentityError),
+
     #[error("Failed to instantiate hardware identity")]
     NewHardwareIdentityFailed(#[source] NewHardwareIdentityError),
 }
@@ -327,6 +445,27 @@ pub enum RenameWalletGlobalConfigKeyError {
     SaveWalletConfig(#[from] SaveWalletConfigError),
 }
 
+#[derive(Error, Debug)]
+pub enum ReplacePemError {
+    #[error("Failed to remove previous PEM file")]
+    RemovePemFileFailed(#[from] RemoveFileError),
+
+    #[error("Failed to remove previous PEM from keyring")]
+    RemovePemFromKeyringFailed(#[source] KeyringError),
+
+    #[error("Failed to move new identity files into place")]
+    RenameTemporaryFileFailed(#[from] RenameError),
+
+    #[error("Failed to save identity configuration")]
+    SaveIdentityConfigurationFailed(#[source] SaveIdentityConfigurationError),
+
+    #[error("Failed to write PEM to file")]
+    WritePemToFileFailed(#[source] WritePemToFileError),
+
+    #[error("Failed to write PEM to keyring")]
+    WritePemToKeyringFailed(#[source] KeyringError),
+}
+
 #[derive(Error, Debug)]
 pub enum RequireIdentityExistsError {
     #[error("Identity {0} does not exist at '{1}'.")]
@@ -336,6 +475,57 @@ pub enum RequireIdentityExistsError {
     ReservedIdentityName(String),
 }
 
+#[derive(Error, Debug)]
+pub enum RestoreIdentitiesError {
+    #[error("Failed to clean up previous restore attempts")]
+    CleanupPreviousRestoreAttemptsFailed(#[from] RemoveDirectoryAndContentsError),
+
+    #[error("Failed to create temporary identity directory")]
+    CreateTemporaryIdentityDirectoryFailed(#[source] CreateDirAllError),
+
+    #[error("Failed to decrypt identity backup. Is the password correct?")]
+    DecryptBackupFailed(#[source] EncryptionError),
+
+    #[error("Failed to generate a fresh encryption configuration")]
+    GenerateFreshEncryptionConfigurationFailed(#[source] EncryptionError),
+
+    #[error("Failed to parse identity backup content")]
+    ParseBackupContentFailed(#[source] serde_json::Error),
+
+    #[error("Failed to read identity backup")]
+    ReadBackupFailed(#[source] StructuredFileError),
+
+    #[error("Failed to remove identity")]
+    RemoveIdentityFailed(#[source] RemoveIdentityError),
+
+    #[error("Failed to rename temporary directory to permanent identity directory")]
+    RenameTemporaryIdentityDirectoryFailed(#[from] RenameError),
+
+    #[error("Failed to save delegation chain")]
+    SaveDelegationChainFailed(#[source] StructuredFileError),
+
+    #[error("Failed to save identity configuration")]
+    SaveIdentityConfigurationFailed(#[source] SaveIdentityConfigurationError),
+
+    #[error("Failed to save pem")]
+    SavePemFailed(#[source] SavePemError),
+
+    #[error("Failed to save wallet configuration")]
+    SaveWalletConfigFailed(#[source] SaveWalletConfigError),
+
+    #[error("Failed to switch back over to the identity you're replacing")]
+    SwitchBackToIdentityFailed(#[source] UseIdentityByNameError),
+
+    #[error("Failed to temporarily switch over to anonymous identity")]
+    SwitchToAnonymousIdentityFailed(#[source] UseIdentityByNameError),
+
+    #[error("Unsupported identity backup version {0}. Please upgrade dfx.")]
+    UnsupportedBackupVersion(u32),
+
+    #[error("Failed to write encrypted pem file")]
+    WriteEncryptedPemFailed(#[source] WritePemContentError),
+}
+
 #[derive(Error, Debug)]
 pub enum SaveIdentityConfigurationError {
     #[error("failed to ensure identity configuration directory exists")]
@@ -357,6 +547,66 @@ pub enum SavePemError {
     WritePemToKeyringFailed(#[source] KeyringError),
 }
 
+#[derive(Error, Debug)]
+pub enum SetDelegationChainError {
+    #[error("Cannot delegate to the anonymous identity.")]
+    CannotDelegateToAnonymousIdentity(),
+
+    #[error("The delegation chain does not delegate to the key of identity '{0}'")]
+    SessionKeyMismatch(String),
+
+    #[error("Identity must exist")]
+    IdentityDoesNotExist(#[source] RequireIdentityExistsError),
+
+    #[error("Invalid delegation chain")]
+    InvalidDelegationChain(#[source] DelegationChainError),
+
+    #[error("Failed to load the key of identity '{0}'")]
+    LoadIdentityKeyFailed(String, #[source] Box<LoadIdentityError>),
+
+    #[error("Failed to save the delegation chain")]
+    SaveDelegationChainFailed(#[source] StructuredFileError),
+}
+
+#[derive(Error, Debug)]
+pub enum SetInternetIdentityError {
+    #[error("Failed to get identity config")]
+    GetIdentityConfigFailed(#[source] GetIdentityConfigOrDefaultError),
+
+    #[error("Failed to save identity configuration")]
+    SaveIdentityConfigurationFailed(#[source] SaveIdentityConfigurationError),
+}
+
+#[derive(Error, Debug)]
+pub enum SetIdentityStorageModeError {
+    #[error("Cannot change the storage mode of the anonymous identity.")]
+    CannotChangeAnonymousIdentity(),
+
+    #[error("Cannot change the storage mode of identity '{0}' because it is stored in an HSM.")]
+    CannotChangeHsmIdentity(String),
+
+    #[error("Failed to generate a fresh encryption configuration")]
+    GenerateFreshEncryptionConfigurationFailed(#[source] EncryptionError),
+
+    #[error("Failed to get identity config")]
+    GetIdentityConfigFailed(#[source] GetIdentityConfigOrDefaultError),
+
+    #[error("Identity does not exist")]
+    IdentityDoesNotExist(#[source] RequireIdentityExistsError),
+
+    #[error("The keyring is not available.")]
+    KeyringNotAvailable(),
+
+    #[error("Failed to load pem")]
+    LoadPemFailed(#[source] LoadPemError),
+
+    #[error("Failed to store pem with the new storage mode")]
+    ReplacePemFailed(#[source] ReplacePemError),
+
+    #[error("Identity '{0}' already uses storage mode '{1}'.")]
+    StorageModeUnchanged(String, IdentityStorageMode),
+}
+
 #[derive(Error, Debug)]
 pub enum UseIdentityByNameError {
     #[error("Identity must exist")]
@@ -386,6 +636,18 @@ pub enum WriteDefaultIdentityError {
     SaveIdentityManagerConfigurationFailed(#[source] StructuredFileError),
 }
 
+#[derive(Error, Debug)]
+pub enum VerifySignatureError {
+    #[error("Invalid {0} public key")]
+    InvalidPublicKey(&'static str),
+
+    #[error("Invalid {0} signature")]
+    InvalidSignature(&'static str),
+
+    #[error("Signatures by {0} keys cannot be verified offline")]
+    UnsupportedKeyType(String),
+}
+
 #[derive(Error, Debug)]
 pub enum WritePemContentError {
     #[error(transparent)]
@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JsonPathError {
+    #[error("cannot remove the root of the document")]
+    CannotRemoveRoot,
+
+    #[error("path '{0}' contains an empty segment")]
+    EmptySegment(String),
+
+    #[error("'{path}' is not a valid index into an array of length {len}")]
+    InvalidArrayIndex { path: String, len: usize },
+
+    #[error("the value at '{0}' is neither an object nor an array")]
+    NotAContainer(String),
+}
@@ -13,6 +13,7 @@ pub mod get_current_exe;
 pub mod get_user_home;
 pub mod identity;
 pub mod interface;
+pub mod json_path;
 pub mod keyring;
 pub mod load_dfx_config;
 pub mod load_networks_config;
@@ -0,0 +1,217 @@
+//! Delegation chains, which let a session key sign calls on behalf of another identity.
+use crate::error::identity::{
+    DelegateError,
+    DelegateError::{CannotDelegateAnonymousIdentity, SignDelegationFailed},
+    DelegationChainError,
+    DelegationChainError::{EmptyChain, InvalidExpiration, InvalidHex, InvalidTarget},
+};
+use candid::Principal;
+use ic_agent::identity::{Delegation, SignedDelegation};
+use serde::{Deserialize, Serialize};
+
+/// A chain of delegations from the key of an identity to a session key,
+/// in the JSON format of the JavaScript agent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationChain {
+    delegations: Vec<JsonSignedDelegation>,
+    /// The DER-encoded public key of the delegating identity, in hex.
+    public_key: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct JsonSignedDelegation {
+    delegation: JsonDelegation,
+    signature: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct JsonDelegation {
+    pubkey: String,
+    /// Nanoseconds since the Unix epoch, in hex.
+    expiration: String,
+    /// The canisters the delegated key may call, as principals in hex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    targets: Option<Vec<String>>,
+}
+
+impl DelegationChain {
+    /// Delegates from an identity to a session key until `expiration`, in nanoseconds since the Unix epoch.
+    /// If the identity is itself delegated, the new delegation extends its chain.
+    pub fn delegate(
+        from: &dyn ic_agent::Identity,
+        to_public_key: Vec<u8>,
+        expiration: u64,
+        targets: Option<Vec<Principal>>,
+    ) -> Result<Self, DelegateError> {
+        let delegation = Delegation {
+            pubkey: to_public_key,
+            expiration,
+            targets,
+        };
+        let signature = from
+            .sign_delegation(&delegation)
+            .map_err(SignDelegationFailed)?;
+        let (Some(public_key), Some(signature_bytes)) = (signature.public_key, signature.signature)
+        else {
+            return Err(CannotDelegateAnonymousIdentity());
+        };
+        let mut chain = signature.delegations.unwrap_or_default();
+        chain.push(SignedDelegation {
+            delegation,
+            signature: signature_bytes,
+        });
+        Ok(Self::from_signed_delegations(&public_key, &chain))
+    }
+
+    pub fn from_signed_delegations(public_key: &[u8], chain: &[SignedDelegation]) -> Self {
+        let delegations = chain
+            .iter()
+            .map(|signed| JsonSignedDelegation {
+                delegation: JsonDelegation {
+                    pubkey: hex::encode(&signed.delegation.pubkey),
+                    expiration: format!("{:x}", signed.delegation.expiration),
+                    targets: signed.delegation.targets.as_ref().map(|targets| {
+                        targets
+                            .iter()
+                            .map(|target| hex::encode(target.as_slice()))
+                            .collect()
+                    }),
+                },
+                signature: hex::encode(&signed.signature),
+            })
+            .collect();
+        Self {
+            delegations,
+            public_key: hex::encode(public_key),
+        }
+    }
+
+    /// The DER-encoded public key of the delegating identity, whose principal signs the calls.
+    pub fn public_key(&self) -> Result<Vec<u8>, DelegationChainError> {
+        hex::decode(&self.public_key).map_err(InvalidHex)
+    }
+
+    /// The DER-encoded public key that the chain delegates to.
+    pub fn session_public_key(&self) -> Result<Vec<u8>, DelegationChainError> {
+        let last = self.delegations.last().ok_or(EmptyChain())?;
+        hex::decode(&last.delegation.pubkey).map_err(InvalidHex)
+    }
+
+    /// When the first delegation of the chain expires, in nanoseconds since the Unix epoch.
+    pub fn expiration(&self) -> Result<u64, DelegationChainError> {
+        self.signed_delegations()?
+            .iter()
+            .map(|signed| signed.delegation.expiration)
+            .min()
+            .ok_or(EmptyChain())
+    }
+
+    /// The canisters that every delegation of the chain allows to call, or None for any canister.
+    pub fn targets(&self) -> Result<Option<Vec<Principal>>, DelegationChainError> {
+        let mut targets: Option<Vec<Principal>> = None;
+        for signed in self.signed_delegations()? {
+            if let Some(allowed) = signed.delegation.targets {
+                targets = Some(match targets {
+                    Some(previous) => previous
+                        .into_iter()
+                        .filter(|target| allowed.contains(target))
+                        .collect(),
+                    None => allowed,
+                });
+            }
+        }
+        Ok(targets)
+    }
+
+    pub fn signed_delegations(&self) -> Result<Vec<SignedDelegation>, DelegationChainError> {
+        if self.delegations.is_empty() {
+            return Err(EmptyChain());
+        }
+        self.delegations
+            .iter()
+            .map(|signed| {
+                let delegation = &signed.delegation;
+                let targets = match &delegation.targets {
+                    Some(targets) => Some(
+                        targets
+                            .iter()
+                            .map(|target| {
+                                let bytes = hex::decode(target).map_err(InvalidHex)?;
+                                Principal::try_from_slice(&bytes).map_err(InvalidTarget)
+                            })
+                            .collect::<Result<Vec<_>, _>>()?,
+                    ),
+                    None => None,
+                };
+                Ok(SignedDelegation {
+                    delegation: Delegation {
+                        pubkey: hex::decode(&delegation.pubkey).map_err(InvalidHex)?,
+                        expiration: u64::from_str_radix(&delegation.expiration, 16)
+                            .map_err(InvalidExpiration)?,
+                        targets,
+                    },
+                    signature: hex::decode(&signed.signature).map_err(InvalidHex)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_agent::identity::{DelegatedIdentity, Secp256k1Identity};
+    use ic_agent::Identity;
+
+    fn identity(seed: u8) -> Secp256k1Identity {
+        Secp256k1Identity::from_private_key(k256::SecretKey::from_be_bytes(&[seed; 32]).unwrap())
+    }
+
+    const EXPIRATION: u64 = 4_000_000_000_000_000_000;
+
+    #[test]
+    fn delegations_extend_the_chain_of_delegated_identities() {
+        let root = identity(1);
+        let session = identity(2);
+        let canister = Principal::from_slice(&[1, 2, 3]);
+        let chain = DelegationChain::delegate(
+            &root,
+            session.public_key().unwrap(),
+            EXPIRATION,
+            Some(vec![canister]),
+        )
+        .unwrap();
+        assert_eq!(chain.public_key().unwrap(), root.public_key().unwrap());
+        assert_eq!(
+            chain.session_public_key().unwrap(),
+            session.public_key().unwrap()
+        );
+
+        let json = serde_json::to_string(&chain).unwrap();
+        let chain: DelegationChain = serde_json::from_str(&json).unwrap();
+        assert_eq!(chain.expiration().unwrap(), EXPIRATION);
+        assert_eq!(chain.targets().unwrap(), Some(vec![canister]));
+
+        let delegated = DelegatedIdentity::new(
+            chain.public_key().unwrap(),
+            Box::new(session),
+            chain.signed_delegations().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(delegated.sender().unwrap(), root.sender().unwrap());
+
+        let second_session = identity(3);
+        let extended = DelegationChain::delegate(
+            &delegated,
+            second_session.public_key().unwrap(),
+            EXPIRATION + 1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(extended.signed_delegations().unwrap().len(), 2);
+        assert_eq!(extended.public_key().unwrap(), root.public_key().unwrap());
+        assert_eq!(extended.expiration().unwrap(), EXPIRATION);
+        assert_eq!(extended.targets().unwrap(), Some(vec![canister]));
+    }
+}
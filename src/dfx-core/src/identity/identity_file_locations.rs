@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 pub const IDENTITY_PEM: &str = "identity.pem";
 pub const IDENTITY_PEM_ENCRYPTED: &str = "identity.pem.encrypted";
+pub const DELEGATION_JSON: &str = "delegation.json";
 
 #[derive(Clone, Debug)]
 pub(crate) struct IdentityFileLocations {
@@ -42,6 +43,12 @@ impl IdentityFileLocations {
             .join(IDENTITY_PEM_ENCRYPTED)
     }
 
+    /// Determines the path of the delegation chain of a delegated identity.
+    pub fn get_delegation_chain_path(&self, identity_name: &str) -> PathBuf {
+        self.get_identity_dir_path(identity_name)
+            .join(DELEGATION_JSON)
+    }
+
     pub fn get_identity_dir_path(&self, identity: &str) -> PathBuf {
         self.root_dir.join(identity)
     }
@@ -1,10 +1,13 @@
 use super::pem_utils::validate_pem_file;
 use super::{keyring_mock, WALLET_CONFIG_FILENAME};
 use crate::config::directories::get_user_dfx_config_dir;
+use crate::config::model::dfinity::ConfigNetworkIdentities;
 use crate::error::encryption::EncryptionError;
 use crate::error::encryption::EncryptionError::{NonceGenerationFailed, SaltGenerationFailed};
 use crate::error::fs::ReadDirError;
 use crate::error::identity::{
+    ChangeIdentityPasswordError,
+    ChangeIdentityPasswordError::IdentityNotPasswordProtected,
     ConvertMnemonicToKeyError,
     ConvertMnemonicToKeyError::DeriveExtendedKeyFromPathFailed,
     CreateIdentityConfigError,
@@ -23,7 +26,7 @@ use crate::error::identity::{
     GetIdentityConfigOrDefaultError::LoadIdentityConfigurationFailed,
     GetLegacyCredentialsPemPathError,
     GetLegacyCredentialsPemPathError::GetLegacyPemPathFailed,
-    InitializeIdentityManagerError,
+    IdentityNotAllowedError, InitializeIdentityManagerError,
     InitializeIdentityManagerError::{
         CreateIdentityDirectoryFailed, GenerateKeyFailed, MigrateLegacyIdentityFailed,
         WritePemToFileFailed,
@@ -44,7 +47,9 @@ use crate::error::identity::{
     },
     RequireIdentityExistsError, SaveIdentityConfigurationError,
     SaveIdentityConfigurationError::EnsureIdentityConfigurationDirExistsFailed,
-    UseIdentityByNameError,
+    SetDelegationChainError, SetIdentityStorageModeError,
+    SetIdentityStorageModeError::StorageModeUnchanged,
+    SetInternetIdentityError, UseIdentityByNameError,
     UseIdentityByNameError::WriteDefaultIdentityFailed,
     WriteDefaultIdentityError,
     WriteDefaultIdentityError::SaveIdentityManagerConfigurationFailed,
@@ -52,6 +57,7 @@ use crate::error::identity::{
 use crate::error::structured_file::StructuredFileError;
 use crate::foundation::get_user_home;
 use crate::fs::composite::ensure_parent_dir_exists;
+use crate::identity::delegation::DelegationChain;
 use crate::identity::identity_file_locations::{IdentityFileLocations, IDENTITY_PEM};
 use crate::identity::identity_manager::IdentityStorageModeError::UnknownStorageMode;
 use crate::identity::{
@@ -70,6 +76,7 @@ use serde::{Deserialize, Serialize};
 use slog::{debug, trace, Logger};
 use std::boxed::Box;
 use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use thiserror::Error;
@@ -95,6 +102,24 @@ pub struct IdentityConfiguration {
 
     /// If the identity's PEM file is stored in the system's keyring, this field contains the identity's name WITHOUT the common prefix.
     pub keyring_identity_suffix: Option<String>,
+
+    /// If the identity's key is a device of an Internet Identity anchor, the anchor and the frontend origin it signs in to.
+    pub internet_identity: Option<InternetIdentityConfiguration>,
+}
+
+impl IdentityConfiguration {
+    /// The storage mode of the identity's PEM content, or None for identities stored in an HSM.
+    pub fn storage_mode(&self) -> Option<IdentityStorageMode> {
+        if self.hsm.is_some() {
+            None
+        } else if self.keyring_identity_suffix.is_some() {
+            Some(IdentityStorageMode::Keyring)
+        } else if self.encryption.is_some() {
+            Some(IdentityStorageMode::PasswordProtected)
+        } else {
+            Some(IdentityStorageMode::Plaintext)
+        }
+    }
 }
 
 /// The information necessary to de- and encrypt (except the password) the identity's .pem file
@@ -126,6 +151,15 @@ impl EncryptionConfiguration {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternetIdentityConfiguration {
+    /// The anchor (user number) of the Internet Identity.
+    pub anchor: u64,
+
+    /// The derivation origin of the frontend, which determines the principal of the user.
+    pub origin: String,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct HardwareIdentityConfiguration {
     #[cfg_attr(
@@ -168,6 +202,16 @@ impl FromStr for IdentityStorageMode {
     }
 }
 
+impl Display for IdentityStorageMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityStorageMode::Keyring => write!(f, "keyring"),
+            IdentityStorageMode::PasswordProtected => write!(f, "password-protected"),
+            IdentityStorageMode::Plaintext => write!(f, "plaintext"),
+        }
+    }
+}
+
 impl Default for IdentityStorageMode {
     fn default() -> Self {
         Self::Keyring
@@ -198,6 +242,7 @@ pub struct IdentityManager {
     configuration: Configuration,
     selected_identity: String,
     selected_identity_principal: Option<Principal>,
+    network_identities: Option<(String, ConfigNetworkIdentities)>,
 }
 
 #[derive(PartialEq)]
@@ -239,6 +284,7 @@ impl IdentityManager {
             configuration,
             selected_identity,
             selected_identity_principal: None,
+            network_identities: None,
         };
 
         if let Some(identity) = identity_override {
@@ -253,6 +299,53 @@ impl IdentityManager {
         self.selected_identity_principal
     }
 
+    /// Selects an identity for this invocation of dfx, like `--identity`, without changing the default identity.
+    pub fn select_identity(&mut self, name: &str) {
+        self.selected_identity = name.to_string();
+        self.selected_identity_principal = None;
+    }
+
+    /// Only allows the identities that the configuration of a network permits
+    /// to be instantiated or loaded from now on.
+    pub fn restrict_to_network(&mut self, network_name: &str, identities: ConfigNetworkIdentities) {
+        self.network_identities = Some((network_name.to_string(), identities));
+    }
+
+    /// The network that identities are restricted to, and its identity configuration.
+    pub fn get_network_identities(&self) -> Option<(&str, &ConfigNetworkIdentities)> {
+        self.network_identities
+            .as_ref()
+            .map(|(network_name, identities)| (network_name.as_str(), identities))
+    }
+
+    /// Checks that the network this manager is restricted to, if any, allows an identity.
+    pub fn check_identity_allowed(&self, name: &str) -> Result<(), IdentityNotAllowedError> {
+        let Some((network_name, identities)) = &self.network_identities else {
+            return Ok(());
+        };
+        if let Some(allowed) = &identities.allowed {
+            if !allowed.iter().any(|allowed| allowed == name) {
+                return Err(IdentityNotAllowedError::NotAllowed(
+                    name.to_string(),
+                    network_name.clone(),
+                    allowed.join(", "),
+                ));
+            }
+        }
+        if identities.allow_plaintext == Some(false) && name != ANONYMOUS_IDENTITY_NAME {
+            let config = self
+                .get_identity_config_or_default(name)
+                .map_err(IdentityNotAllowedError::GetIdentityConfigFailed)?;
+            if config.storage_mode() == Some(IdentityStorageMode::Plaintext) {
+                return Err(IdentityNotAllowedError::PlaintextNotAllowed(
+                    name.to_string(),
+                    network_name.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Create an Identity instance for use with an Agent
     pub fn instantiate_selected_identity(
         &mut self,
@@ -269,10 +362,14 @@ impl IdentityManager {
         log: &Logger,
     ) -> Result<Box<DfxIdentity>, InstantiateIdentityFromNameError> {
         let identity = match identity_name {
-            ANONYMOUS_IDENTITY_NAME => Box::new(DfxIdentity::anonymous()),
+            ANONYMOUS_IDENTITY_NAME => {
+                self.check_identity_allowed(identity_name)?;
+                Box::new(DfxIdentity::anonymous())
+            }
             identity_name => {
                 self.require_identity_exists(log, identity_name)
                     .map_err(InstantiateIdentityFromNameError::RequireIdentityExistsFailed)?;
+                self.check_identity_allowed(identity_name)?;
                 Box::new(
                     self.load_identity(identity_name, log)
                         .map_err(LoadIdentityFailed)?,
@@ -293,6 +390,80 @@ impl IdentityManager {
             .map_err(LoadIdentityError::NewIdentityFailed)
     }
 
+    /// Loads the key of an identity, without the delegation chain of a delegated identity.
+    pub fn load_identity_key(
+        &self,
+        name: &str,
+        log: &Logger,
+    ) -> Result<DfxIdentity, LoadIdentityError> {
+        self.check_identity_allowed(name)?;
+        self.load_key(name, log)
+    }
+
+    fn load_key(&self, name: &str, log: &Logger) -> Result<DfxIdentity, LoadIdentityError> {
+        let config = self
+            .get_identity_config_or_default(name)
+            .map_err(LoadIdentityError::GetIdentityConfigOrDefaultFailed)?;
+        DfxIdentity::new_key(name, config, self.file_locations(), log)
+            .map_err(LoadIdentityError::NewIdentityFailed)
+    }
+
+    /// The DER-encoded public key of an identity, without the delegation chain of a delegated identity.
+    pub fn get_identity_public_key(
+        &self,
+        log: &Logger,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, LoadIdentityError> {
+        use ic_agent::identity::Identity;
+        Ok(self.load_key(name, log)?.public_key())
+    }
+
+    /// Makes an identity delegated: its key signs calls as the identity that signed the chain,
+    /// until the chain expires.
+    pub fn set_delegation_chain(
+        &self,
+        log: &Logger,
+        name: &str,
+        chain: &DelegationChain,
+    ) -> Result<(), SetDelegationChainError> {
+        if name == ANONYMOUS_IDENTITY_NAME {
+            return Err(SetDelegationChainError::CannotDelegateToAnonymousIdentity());
+        }
+        self.require_identity_exists(log, name)
+            .map_err(SetDelegationChainError::IdentityDoesNotExist)?;
+        let public_key = self.get_identity_public_key(log, name).map_err(|e| {
+            SetDelegationChainError::LoadIdentityKeyFailed(name.to_string(), Box::new(e))
+        })?;
+        let session_public_key = chain
+            .session_public_key()
+            .map_err(SetDelegationChainError::InvalidDelegationChain)?;
+        if public_key != Some(session_public_key) {
+            return Err(SetDelegationChainError::SessionKeyMismatch(
+                name.to_string(),
+            ));
+        }
+        save_json_file(&self.file_locations.get_delegation_chain_path(name), chain)
+            .map_err(SetDelegationChainError::SaveDelegationChainFailed)
+    }
+
+    /// Records the Internet Identity anchor that the key of an identity is a device of.
+    pub fn set_internet_identity(
+        &self,
+        log: &Logger,
+        name: &str,
+        internet_identity: InternetIdentityConfiguration,
+    ) -> Result<(), SetInternetIdentityError> {
+        let config = self
+            .get_identity_config_or_default(name)
+            .map_err(SetInternetIdentityError::GetIdentityConfigFailed)?;
+        let config = IdentityConfiguration {
+            internet_identity: Some(internet_identity),
+            ..config
+        };
+        save_identity_configuration(log, &self.get_identity_json_path(name), &config)
+            .map_err(SetInternetIdentityError::SaveIdentityConfigurationFailed)
+    }
+
     /// Create a new identity (name -> generated key)
     ///
     /// `force`: If the identity already exists, remove and re-create it.
@@ -477,6 +648,11 @@ impl IdentityManager {
         &self.selected_identity
     }
 
+    /// Return the name of the identity that is used when no identity is specified
+    pub fn get_default_identity_name(&self) -> &str {
+        &self.configuration.default
+    }
+
     pub(crate) fn file_locations(&self) -> &IdentityFileLocations {
         &self.file_locations
     }
@@ -539,6 +715,7 @@ impl IdentityManager {
         remove_identity_file(&self.get_identity_json_path(name))?;
         remove_identity_file(&self.file_locations.get_plaintext_identity_pem_path(name))?;
         remove_identity_file(&self.file_locations.get_encrypted_identity_pem_path(name))?;
+        remove_identity_file(&self.file_locations.get_delegation_chain_path(name))?;
 
         let dir = self.get_identity_dir_path(name);
         if dir.exists() {
@@ -604,6 +781,106 @@ impl IdentityManager {
         }
     }
 
+    /// Move an identity's key to a different storage mode.
+    /// Prompts for the current password of password-protected identities,
+    /// and for a new password when moving to password-protected storage.
+    pub fn set_storage_mode(
+        &self,
+        log: &Logger,
+        name: &str,
+        mode: IdentityStorageMode,
+    ) -> Result<(), SetIdentityStorageModeError> {
+        if name == ANONYMOUS_IDENTITY_NAME {
+            return Err(SetIdentityStorageModeError::CannotChangeAnonymousIdentity());
+        }
+        self.require_identity_exists(log, name)
+            .map_err(SetIdentityStorageModeError::IdentityDoesNotExist)?;
+        let identity_config = self
+            .get_identity_config_or_default(name)
+            .map_err(SetIdentityStorageModeError::GetIdentityConfigFailed)?;
+        match identity_config.storage_mode() {
+            None => {
+                return Err(SetIdentityStorageModeError::CannotChangeHsmIdentity(
+                    name.to_string(),
+                ))
+            }
+            Some(current) if current == mode => {
+                return Err(StorageModeUnchanged(name.to_string(), mode));
+            }
+            Some(_) => {}
+        }
+
+        let new_config = match mode {
+            IdentityStorageMode::Keyring => {
+                if !keyring_mock::keyring_available(log) {
+                    return Err(SetIdentityStorageModeError::KeyringNotAvailable());
+                }
+                IdentityConfiguration {
+                    keyring_identity_suffix: Some(name.to_string()),
+                    ..Default::default()
+                }
+            }
+            IdentityStorageMode::PasswordProtected => IdentityConfiguration {
+                encryption: Some(EncryptionConfiguration::new().map_err(
+                    SetIdentityStorageModeError::GenerateFreshEncryptionConfigurationFailed,
+                )?),
+                ..Default::default()
+            },
+            IdentityStorageMode::Plaintext => IdentityConfiguration::default(),
+        };
+        let new_config = IdentityConfiguration {
+            internet_identity: identity_config.internet_identity.clone(),
+            ..new_config
+        };
+
+        let (pem, _) = pem_safekeeping::load_pem(log, &self.file_locations, name, &identity_config)
+            .map_err(SetIdentityStorageModeError::LoadPemFailed)?;
+        pem_safekeeping::replace_pem(
+            log,
+            &self.file_locations,
+            name,
+            &identity_config,
+            &new_config,
+            &pem,
+        )
+        .map_err(SetIdentityStorageModeError::ReplacePemFailed)
+    }
+
+    /// Re-encrypt a password-protected identity with a new password.
+    /// A fresh salt and nonce are generated for the new password.
+    pub fn change_password(
+        &self,
+        log: &Logger,
+        name: &str,
+    ) -> Result<(), ChangeIdentityPasswordError> {
+        self.require_identity_exists(log, name)
+            .map_err(ChangeIdentityPasswordError::IdentityDoesNotExist)?;
+        let identity_config = self
+            .get_identity_config_or_default(name)
+            .map_err(ChangeIdentityPasswordError::GetIdentityConfigFailed)?;
+        if identity_config.storage_mode() != Some(IdentityStorageMode::PasswordProtected) {
+            return Err(IdentityNotPasswordProtected(name.to_string()));
+        }
+
+        let (pem, _) = pem_safekeeping::load_pem(log, &self.file_locations, name, &identity_config)
+            .map_err(ChangeIdentityPasswordError::LoadPemFailed)?;
+        let new_config = IdentityConfiguration {
+            encryption: Some(EncryptionConfiguration::new().map_err(
+                ChangeIdentityPasswordError::GenerateFreshEncryptionConfigurationFailed,
+            )?),
+            ..identity_config.clone()
+        };
+        pem_safekeeping::replace_pem(
+            log,
+            &self.file_locations,
+            name,
+            &identity_config,
+            &new_config,
+            &pem,
+        )
+        .map_err(ChangeIdentityPasswordError::ReplacePemFailed)
+    }
+
     /// Select an identity by name to use by default
     pub fn use_identity_named(
         &mut self,
@@ -12,6 +12,11 @@ use crate::error::{
             ParsePrincipalFromIdFailedAndGetWalletCanisterIdFailed,
             ParsePrincipalFromIdFailedAndNoWallet,
         },
+        DelegationChainError, LoadDelegatedIdentityError,
+        LoadDelegatedIdentityError::{
+            DelegationExpired, InvalidDelegationChain, LoadDelegationChainFailed,
+            VerifyDelegationChainFailed,
+        },
         LoadPemIdentityError,
         LoadPemIdentityError::ReadIdentityFileFailed,
         MapWalletsToRenamedIdentityError,
@@ -24,13 +29,15 @@ use crate::error::{
     wallet_config::{WalletConfigError, WalletConfigError::LoadWalletConfigFailed},
 };
 use crate::fs::composite::ensure_parent_dir_exists;
+use crate::identity::delegation::DelegationChain;
 use crate::identity::identity_file_locations::IdentityFileLocations;
 use crate::identity::wallet::wallet_canister_id;
 use crate::json::{load_json_file, save_json_file};
 use candid::Principal;
 use ic_agent::agent::EnvelopeContent;
 use ic_agent::identity::{
-    AnonymousIdentity, BasicIdentity, Delegation, Secp256k1Identity, SignedDelegation,
+    AnonymousIdentity, BasicIdentity, DelegatedIdentity, Delegation, Secp256k1Identity,
+    SignedDelegation,
 };
 use ic_agent::Signature;
 use ic_identity_hsm::HardwareIdentity;
@@ -42,12 +49,17 @@ use serde::{Deserialize, Serialize};
 use slog::{info, Logger};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
 
+pub mod backup;
+pub mod delegation;
 mod identity_file_locations;
 pub mod identity_manager;
 pub mod keyring_mock;
 pub mod pem_safekeeping;
 pub mod pem_utils;
+pub mod signature;
 pub mod wallet;
 
 pub const ANONYMOUS_IDENTITY_NAME: &str = "anonymous";
@@ -148,11 +160,74 @@ impl Identity {
         })
     }
 
+    /// Wraps the key of the identity in the delegation chain from another identity,
+    /// so that it signs calls as that identity.
+    fn delegated(self, chain: DelegationChain) -> Result<Self, LoadDelegatedIdentityError> {
+        let name = self.name;
+        let (expiration, session_public_key, public_key, delegations) =
+            (|| -> Result<_, DelegationChainError> {
+                Ok((
+                    chain.expiration()?,
+                    chain.session_public_key()?,
+                    chain.public_key()?,
+                    chain.signed_delegations()?,
+                ))
+            })()
+            .map_err(|e| InvalidDelegationChain(name.clone(), e))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_nanos());
+        if u128::from(expiration) < now {
+            let expired_at = OffsetDateTime::from_unix_timestamp_nanos(i128::from(expiration))
+                .map_or_else(|_| expiration.to_string(), |time| time.to_string());
+            return Err(DelegationExpired(name, expired_at));
+        }
+        if Some(session_public_key) != ic_agent::Identity::public_key(self.inner.as_ref()) {
+            return Err(LoadDelegatedIdentityError::SessionKeyMismatch(name));
+        }
+        let inner = if signature::is_canister_signature_key(&public_key) {
+            // Delegations from Internet Identity are signed with canister signatures,
+            // which only the Internet Computer verifies when the identity makes calls.
+            DelegatedIdentity::new_unchecked(public_key, self.inner, delegations)
+        } else {
+            DelegatedIdentity::new(public_key, self.inner, delegations)
+                .map_err(|e| VerifyDelegationChainFailed(name.clone(), e.to_string()))?
+        };
+        Ok(Self {
+            name,
+            inner: Box::new(inner),
+            // Whoever can read the session key signs as the delegating identity until it expires.
+            insecure: self.insecure,
+            identity_type: IdentityType::Delegated,
+        })
+    }
+
     pub(crate) fn new(
         name: &str,
         config: IdentityConfiguration,
         locations: &IdentityFileLocations,
         log: &Logger,
+    ) -> Result<Self, NewIdentityError> {
+        let identity = Identity::new_key(name, config, locations, log)?;
+        let chain_path = locations.get_delegation_chain_path(name);
+        if chain_path.exists() {
+            let chain = load_json_file(&chain_path)
+                .map_err(|e| LoadDelegationChainFailed(name.to_string(), e))
+                .map_err(NewIdentityError::LoadDelegatedIdentityFailed)?;
+            identity
+                .delegated(chain)
+                .map_err(NewIdentityError::LoadDelegatedIdentityFailed)
+        } else {
+            Ok(identity)
+        }
+    }
+
+    /// Loads the key of the identity, without the delegation chain of a delegated identity.
+    pub(crate) fn new_key(
+        name: &str,
+        config: IdentityConfiguration,
+        locations: &IdentityFileLocations,
+        log: &Logger,
     ) -> Result<Self, NewIdentityError> {
         if let Some(hsm) = config.hsm {
             Identity::hardware(name, hsm).map_err(NewIdentityError::NewHardwareIdentityFailed)
@@ -321,6 +396,7 @@ pub enum IdentityType {
     EncryptedLocal,
     Hsm,
     Anonymous,
+    Delegated,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -1,6 +1,6 @@
-use super::identity_manager::EncryptionConfiguration;
-use super::{IdentityConfiguration, IdentityType};
-use crate::error::identity::WritePemContentError;
+use super::identity_manager::{save_identity_configuration, EncryptionConfiguration};
+use super::{IdentityConfiguration, IdentityType, IDENTITY_JSON};
+use crate::error::identity::{ReplacePemError, WritePemContentError};
 use crate::error::{
     encryption::{
         EncryptionError,
@@ -24,7 +24,7 @@ use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Key, Nonce};
 use argon2::{password_hash::PasswordHasher, Argon2};
 use slog::{debug, trace, Logger};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Loads an identity's PEM file content.
 pub(crate) fn load_pem(
@@ -75,6 +75,70 @@ pub(crate) fn save_pem(
     }
 }
 
+/// Stores an identity's PEM content according to a new identity configuration,
+/// and removes it from where the previous configuration stored it.
+///
+/// The new PEM file and identity.json are first written to temporary files and only moved into place
+/// once both were written successfully, so that a failure never leaves the identity without a usable key.
+pub(crate) fn replace_pem(
+    log: &Logger,
+    locations: &IdentityFileLocations,
+    name: &str,
+    previous_config: &IdentityConfiguration,
+    identity_config: &IdentityConfiguration,
+    pem_content: &[u8],
+) -> Result<(), ReplacePemError> {
+    trace!(
+        log,
+        "Replacing pem of identity '{name}' with identity config {:?}",
+        identity_config
+    );
+    let pem_path = locations.get_identity_pem_path(name, identity_config);
+    let temp_pem_path = temp_file_path(&pem_path);
+    if let Some(keyring_identity) = &identity_config.keyring_identity_suffix {
+        keyring_mock::write_pem_to_keyring(keyring_identity, pem_content)
+            .map_err(ReplacePemError::WritePemToKeyringFailed)?;
+    } else {
+        if temp_pem_path.exists() {
+            crate::fs::remove_file(&temp_pem_path)?;
+        }
+        write_pem_to_file(&temp_pem_path, Some(identity_config), pem_content)
+            .map_err(ReplacePemError::WritePemToFileFailed)?;
+    }
+
+    let config_path = locations.get_identity_dir_path(name).join(IDENTITY_JSON);
+    let temp_config_path = temp_file_path(&config_path);
+    save_identity_configuration(log, &temp_config_path, identity_config)
+        .map_err(ReplacePemError::SaveIdentityConfigurationFailed)?;
+
+    if identity_config.keyring_identity_suffix.is_none() {
+        crate::fs::rename(&temp_pem_path, &pem_path)?;
+    }
+    crate::fs::rename(&temp_config_path, &config_path)?;
+
+    if let Some(previous_keyring_identity) = &previous_config.keyring_identity_suffix {
+        if identity_config.keyring_identity_suffix.as_ref() != Some(previous_keyring_identity) {
+            keyring_mock::delete_pem_from_keyring(previous_keyring_identity)
+                .map_err(ReplacePemError::RemovePemFromKeyringFailed)?;
+        }
+    } else {
+        let previous_pem_path = locations.get_identity_pem_path(name, previous_config);
+        let overwritten =
+            identity_config.keyring_identity_suffix.is_none() && previous_pem_path == pem_path;
+        if !overwritten && previous_pem_path.exists() {
+            crate::fs::remove_file(&previous_pem_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The path a file is written to before it replaces the file at `path`.
+fn temp_file_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
 /// Loads a pem file, no matter if it is a plaintext pem file or if it is encrypted with a password.
 /// Transparently handles all complexities regarding pem file encryption, including prompting the user for the password.
 /// Returns the pem and whether the original was encrypted.
@@ -112,7 +176,10 @@ pub fn write_pem_to_file(
     write_pem_content(path, &pem_content).map_err(WritePemContentFailed)
 }
 
-fn write_pem_content(path: &Path, pem_content: &[u8]) -> Result<(), WritePemContentError> {
+pub(crate) fn write_pem_content(
+    path: &Path,
+    pem_content: &[u8],
+) -> Result<(), WritePemContentError> {
     let containing_folder = crate::fs::parent(path)?;
     crate::fs::create_dir_all(&containing_folder)?;
     crate::fs::write(path, pem_content)?;
@@ -205,7 +272,7 @@ fn get_argon_params() -> argon2::Params {
     argon2::Params::new(64000 /* in kb */, 3, 1, Some(32 /* in bytes */)).unwrap()
 }
 
-fn encrypt(
+pub(crate) fn encrypt(
     content: &[u8],
     config: &EncryptionConfiguration,
     password: &str,
@@ -229,7 +296,7 @@ fn encrypt(
     Ok(encrypted)
 }
 
-fn decrypt(
+pub(crate) fn decrypt(
     encrypted_content: &[u8],
     config: &EncryptionConfiguration,
     password: &str,
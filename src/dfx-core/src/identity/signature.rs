@@ -0,0 +1,137 @@
+//! Verification of the signatures of messages sent to the Internet Computer.
+use crate::error::identity::VerifySignatureError;
+use crate::error::identity::VerifySignatureError::{
+    InvalidPublicKey, InvalidSignature, UnsupportedKeyType,
+};
+use ic_agent::RequestId;
+use k256::ecdsa::signature::Verifier;
+
+/// The DER prefix of Ed25519 public keys, followed by the 32-byte key.
+const ED25519_DER_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+/// The DER prefix of ECDSA P-256 public keys, followed by the 65-byte uncompressed point.
+const P256_DER_PREFIX: &[u8] = &[
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+/// The DER prefix of ECDSA secp256k1 public keys, followed by the 65-byte uncompressed point.
+const SECP256K1_DER_PREFIX: &[u8] = &[
+    0x30, 0x56, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b,
+    0x81, 0x04, 0x00, 0x0a, 0x03, 0x42, 0x00,
+];
+/// The object identifier of canister signatures, which need a certificate of the network to verify.
+const CANISTER_SIGNATURE_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xb8, 0x43, 0x01, 0x02];
+
+/// The algorithm of a DER-encoded public key.
+pub fn key_type(public_key: &[u8]) -> &'static str {
+    if public_key.starts_with(ED25519_DER_PREFIX) {
+        "Ed25519"
+    } else if public_key.starts_with(P256_DER_PREFIX) {
+        "ECDSA P-256"
+    } else if public_key.starts_with(SECP256K1_DER_PREFIX) {
+        "ECDSA secp256k1"
+    } else if is_canister_signature_key(public_key) {
+        "canister signature"
+    } else {
+        "unknown"
+    }
+}
+
+/// Whether a DER-encoded public key is that of a canister signature, as used by Internet Identity.
+pub fn is_canister_signature_key(public_key: &[u8]) -> bool {
+    public_key
+        .windows(CANISTER_SIGNATURE_OID.len())
+        .any(|window| window == CANISTER_SIGNATURE_OID)
+}
+
+/// Verifies the signature of a message by the owner of a DER-encoded public key.
+pub fn verify_signature(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), VerifySignatureError> {
+    let key_type = key_type(public_key);
+    if let Some(key) = public_key.strip_prefix(ED25519_DER_PREFIX) {
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+            .verify(message, signature)
+            .map_err(|_| InvalidSignature(key_type))
+    } else if let Some(key) = public_key.strip_prefix(P256_DER_PREFIX) {
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ECDSA_P256_SHA256_FIXED, key)
+            .verify(message, signature)
+            .map_err(|_| InvalidSignature(key_type))
+    } else if let Some(key) = public_key.strip_prefix(SECP256K1_DER_PREFIX) {
+        let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+            .map_err(|_| InvalidPublicKey(key_type))?;
+        let signature =
+            k256::ecdsa::Signature::try_from(signature).map_err(|_| InvalidSignature(key_type))?;
+        key.verify(message, &signature)
+            .map_err(|_| InvalidSignature(key_type))
+    } else {
+        Err(UnsupportedKeyType(key_type.to_string()))
+    }
+}
+
+/// Verifies the signature of a request, which signs its request id.
+pub fn verify_request_signature(
+    public_key: &[u8],
+    request_id: &RequestId,
+    signature: &[u8],
+) -> Result<(), VerifySignatureError> {
+    let mut message = b"\x0Aic-request".to_vec();
+    message.extend_from_slice(request_id.as_slice());
+    verify_signature(public_key, &message, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_agent::identity::{BasicIdentity, Secp256k1Identity};
+    use ic_agent::Identity;
+    use ring::signature::Ed25519KeyPair;
+
+    fn check(identity: &dyn Identity, expected_key_type: &str) {
+        let signature = identity.sign_arbitrary(b"hello").unwrap();
+        let public_key = signature.public_key.unwrap();
+        let signature = signature.signature.unwrap();
+        assert_eq!(key_type(&public_key), expected_key_type);
+        assert!(verify_signature(&public_key, b"hello", &signature).is_ok());
+        assert!(matches!(
+            verify_signature(&public_key, b"other", &signature),
+            Err(InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn verifies_ed25519_signatures() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        check(&BasicIdentity::from_key_pair(key_pair), "Ed25519");
+    }
+
+    #[test]
+    fn verifies_secp256k1_signatures() {
+        let key = k256::SecretKey::from_be_bytes(&[7; 32]).unwrap();
+        check(&Secp256k1Identity::from_private_key(key), "ECDSA secp256k1");
+    }
+
+    #[test]
+    fn recognizes_canister_signature_keys() {
+        let mut public_key = vec![0x30, 0x20, 0x30, 0x0c, 0x06, 0x0a];
+        public_key.extend_from_slice(CANISTER_SIGNATURE_OID);
+        public_key.extend_from_slice(&[0x03, 0x10, 0x00, 0x0a]);
+        public_key.extend_from_slice(&[1; 10]);
+        public_key.extend_from_slice(b"seed");
+        assert!(is_canister_signature_key(&public_key));
+        assert_eq!(key_type(&public_key), "canister signature");
+        assert!(!is_canister_signature_key(ED25519_DER_PREFIX));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(matches!(
+            verify_signature(&[0x30, 0x00], b"hello", &[]),
+            Err(UnsupportedKeyType(_))
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::Value;
+use std::fmt;
+
+/// The order of the keys of the objects in a JSON document, as written in its text.
+///
+/// `serde_json::Value` sorts the keys of objects, so an edited document is written out
+/// in the order of the original with [`KeyOrder::apply`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyOrder {
+    /// The keys of an object, each with the order of its value.
+    keys: Vec<(String, KeyOrder)>,
+    /// The order of each element of an array.
+    elements: Vec<KeyOrder>,
+}
+
+impl KeyOrder {
+    /// Serializes `value` with the keys of its objects in this order.
+    /// Keys that this order does not know follow the known ones, sorted.
+    pub fn apply<'a>(&'a self, value: &'a Value) -> impl Serialize + 'a {
+        Ordered { value, order: self }
+    }
+}
+
+struct Ordered<'a> {
+    value: &'a Value,
+    order: &'a KeyOrder,
+}
+
+impl Serialize for Ordered<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value {
+            Value::Object(map) => {
+                let mut out = serializer.serialize_map(Some(map.len()))?;
+                for (key, order) in &self.order.keys {
+                    if let Some(value) = map.get(key) {
+                        out.serialize_entry(key, &Ordered { value, order })?;
+                    }
+                }
+                let default = KeyOrder::default();
+                for (key, value) in map {
+                    if !self.order.keys.iter().any(|(known, _)| known == key) {
+                        out.serialize_entry(
+                            key,
+                            &Ordered {
+                                value,
+                                order: &default,
+                            },
+                        )?;
+                    }
+                }
+                out.end()
+            }
+            Value::Array(array) => {
+                let mut out = serializer.serialize_seq(Some(array.len()))?;
+                let default = KeyOrder::default();
+                for (index, value) in array.iter().enumerate() {
+                    let order = self.order.elements.get(index).unwrap_or(&default);
+                    out.serialize_element(&Ordered { value, order })?;
+                }
+                out.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(KeyOrderVisitor)
+    }
+}
+
+struct KeyOrderVisitor;
+
+impl<'de> Visitor<'de> for KeyOrderVisitor {
+    type Value = KeyOrder;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<KeyOrder, A::Error> {
+        let mut keys: Vec<(String, KeyOrder)> = vec![];
+        while let Some((key, order)) = map.next_entry::<String, KeyOrder>()? {
+            // Like `serde_json::Value`, the last of duplicate keys wins.
+            keys.retain(|(known, _)| *known != key);
+            keys.push((key, order));
+        }
+        Ok(KeyOrder {
+            keys,
+            elements: vec![],
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<KeyOrder, A::Error> {
+        let mut elements = vec![];
+        while let Some(order) = seq.next_element()? {
+            elements.push(order);
+        }
+        Ok(KeyOrder {
+            keys: vec![],
+            elements,
+        })
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+
+    fn visit_unit<E>(self) -> Result<KeyOrder, E> {
+        Ok(KeyOrder::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_the_order_of_the_original() {
+        let original = r#"{"version": 1, "canisters": {"b": {"type": "motoko", "main": "b.mo"}, "a": {}}, "dfx": "0.1", "list": [{"z": 1, "y": 2}]}"#;
+        let order: KeyOrder = serde_json::from_str(original).unwrap();
+
+        let mut value: Value = serde_json::from_str(original).unwrap();
+        value["canisters"]["b"]["gzip"] = json!(true);
+        value["canisters"]["a"]["type"] = json!("rust");
+        value["list"][0]["x"] = json!(3);
+        value.as_object_mut().unwrap().remove("dfx");
+
+        assert_eq!(
+            serde_json::to_string(&order.apply(&value)).unwrap(),
+            r#"{"version":1,"canisters":{"b":{"type":"motoko","main":"b.mo","gzip":true},"a":{"type":"rust"}},"list":[{"z":1,"y":2,"x":3}]}"#
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod key_order;
+pub mod path;
+pub mod position;
 pub mod structure;
 use crate::error::structured_file::StructuredFileError;
 use crate::error::structured_file::StructuredFileError::ReadJsonFileFailed;
@@ -5,6 +8,7 @@ use crate::error::structured_file::StructuredFileError::{
     DeserializeJsonFileFailed, SerializeJsonFileFailed,
 };
 use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
 use std::path::Path;
 
 pub fn load_json_file<T: for<'a> serde::de::Deserialize<'a>>(
@@ -22,3 +26,86 @@ pub fn save_json_file<T: Serialize>(path: &Path, value: &T) -> Result<(), Struct
     crate::fs::write(path, content)?;
     Ok(())
 }
+
+/// Indentation and trailing newline of an existing JSON file,
+/// so that it can be rewritten without reformatting it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonFormat {
+    indent: String,
+    trailing_newline: bool,
+}
+
+impl Default for JsonFormat {
+    fn default() -> Self {
+        Self {
+            indent: "  ".to_string(),
+            trailing_newline: false,
+        }
+    }
+}
+
+impl JsonFormat {
+    /// Detects the format of a JSON document from the indentation of its first indented line.
+    pub fn detect(content: &[u8]) -> Self {
+        let content = String::from_utf8_lossy(content);
+        let indent = content
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let trimmed = line.trim_start_matches([' ', '\t']);
+                &line[..line.len() - trimmed.len()]
+            })
+            .find(|indent| !indent.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| Self::default().indent);
+        Self {
+            indent,
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    pub fn to_string<T: Serialize>(&self, value: &T) -> Result<String, serde_json::Error> {
+        let mut out = Vec::new();
+        let formatter = PrettyFormatter::with_indent(self.indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+        value.serialize(&mut serializer)?;
+        if self.trailing_newline {
+            out.push(b'\n');
+        }
+        // serde_json only emits valid UTF-8
+        Ok(String::from_utf8(out).unwrap())
+    }
+}
+
+pub fn save_json_file_with_format<T: Serialize>(
+    path: &Path,
+    value: &T,
+    format: &JsonFormat,
+) -> Result<(), StructuredFileError> {
+    let content = format
+        .to_string(value)
+        .map_err(|err| SerializeJsonFileFailed(Box::new(path.to_path_buf()), err))?;
+    crate::fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detect_format() {
+        let format = JsonFormat::detect(b"{\n    \"a\": 1\n}\n");
+        assert_eq!(
+            format.to_string(&json!({"a": {"b": 1}})).unwrap(),
+            "{\n    \"a\": {\n        \"b\": 1\n    }\n}\n"
+        );
+        let format = JsonFormat::detect(b"{\n\t\"a\": 1\n}");
+        assert_eq!(
+            format.to_string(&json!({"a": 1})).unwrap(),
+            "{\n\t\"a\": 1\n}"
+        );
+        assert_eq!(JsonFormat::detect(b"{}"), JsonFormat::default());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::error::json_path::JsonPathError;
+use serde_json::{Map, Value};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A path to a value inside a JSON document.
+///
+/// Can be written either as a JSON pointer (`/canisters/backend/main`)
+/// or as a dot-separated path (`canisters.backend.main`).
+/// Array elements are addressed by their index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<String>,
+}
+
+impl JsonPath {
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Formats the path as a JSON pointer, as accepted by `Value::pointer`.
+    pub fn to_pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        value.pointer(&self.to_pointer())
+    }
+
+    /// Sets the value at this path, creating intermediate objects as needed.
+    ///
+    /// An array index equal to the length of the array appends to it.
+    pub fn set(&self, root: &mut Value, new_value: Value) -> Result<(), JsonPathError> {
+        let Some((last, parents)) = self.segments.split_last() else {
+            *root = new_value;
+            return Ok(());
+        };
+        let mut current = root;
+        for (depth, segment) in parents.iter().enumerate() {
+            if current.is_null() {
+                *current = Value::Object(Map::new());
+            }
+            current = match current {
+                Value::Object(map) => map
+                    .entry(segment.clone())
+                    .or_insert_with(|| Value::Object(Map::new())),
+                Value::Array(array) => {
+                    let index = self.parse_index(segment, array.len(), depth)?;
+                    &mut array[index]
+                }
+                _ => return Err(self.not_a_container(depth)),
+            };
+        }
+        if current.is_null() {
+            *current = Value::Object(Map::new());
+        }
+        match current {
+            Value::Object(map) => {
+                map.insert(last.clone(), new_value);
+            }
+            Value::Array(array) => {
+                if last == "-" || last.parse::<usize>().ok() == Some(array.len()) {
+                    array.push(new_value);
+                } else {
+                    let index = self.parse_index(last, array.len(), parents.len())?;
+                    array[index] = new_value;
+                }
+            }
+            _ => return Err(self.not_a_container(parents.len())),
+        }
+        Ok(())
+    }
+
+    /// Removes the value at this path, returning it if it was present.
+    pub fn unset(&self, root: &mut Value) -> Result<Option<Value>, JsonPathError> {
+        let Some((last, parents)) = self.segments.split_last() else {
+            return Err(JsonPathError::CannotRemoveRoot);
+        };
+        let parent_pointer = JsonPath {
+            segments: parents.to_vec(),
+        }
+        .to_pointer();
+        let removed = match root.pointer_mut(&parent_pointer) {
+            Some(Value::Object(map)) => map.shift_remove(last),
+            Some(Value::Array(array)) => match last.parse::<usize>() {
+                Ok(index) if index < array.len() => Some(array.remove(index)),
+                _ => None,
+            },
+            Some(_) => return Err(self.not_a_container(parents.len())),
+            None => None,
+        };
+        Ok(removed)
+    }
+
+    fn parse_index(&self, segment: &str, len: usize, depth: usize) -> Result<usize, JsonPathError> {
+        match segment.parse::<usize>() {
+            Ok(index) if index < len => Ok(index),
+            _ => Err(JsonPathError::InvalidArrayIndex {
+                path: self.prefix(depth + 1),
+                len,
+            }),
+        }
+    }
+
+    fn not_a_container(&self, depth: usize) -> JsonPathError {
+        JsonPathError::NotAContainer(self.prefix(depth))
+    }
+
+    fn prefix(&self, len: usize) -> String {
+        JsonPath {
+            segments: self.segments[..len].to_vec(),
+        }
+        .to_string()
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments: Vec<String> = if s.is_empty() || s == "." {
+            vec![]
+        } else if let Some(pointer) = s.strip_prefix('/') {
+            pointer
+                .split('/')
+                .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+                .collect()
+        } else {
+            s.split('.').map(String::from).collect()
+        };
+        if segments.iter().any(|segment| segment.is_empty()) && !s.starts_with('/') {
+            return Err(JsonPathError::EmptySegment(s.to_string()));
+        }
+        Ok(JsonPath { segments })
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self
+            .segments
+            .iter()
+            .any(|s| s.is_empty() || s.contains('.'))
+        {
+            f.write_str(&self.to_pointer())
+        } else if self.segments.is_empty() {
+            f.write_str(".")
+        } else {
+            f.write_str(&self.segments.join("."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(s: &str) -> JsonPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_dotted_and_pointer() {
+        assert_eq!(path("canisters.app.main"), path("/canisters/app/main"));
+        assert_eq!(path("/a~1b/c~0d").segments(), ["a/b", "c~d"]);
+        assert_eq!(path("/a~1b/c~0d").to_pointer(), "/a~1b/c~0d");
+        assert!(path(".").segments().is_empty());
+        assert!("a..b".parse::<JsonPath>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        assert_eq!(path("a.b.0").to_string(), "a.b.0");
+        assert_eq!(path("/a.b/c").to_string(), "/a.b/c");
+        assert_eq!(path("/a.b/c"), path(&path("/a.b/c").to_string()));
+    }
+
+    #[test]
+    fn get_values() {
+        let value = json!({"canisters": {"app": {"dependencies": ["a", "b"]}}});
+        assert_eq!(
+            path("canisters.app.dependencies.1").get(&value),
+            Some(&json!("b"))
+        );
+        assert_eq!(path("canisters.other").get(&value), None);
+    }
+
+    #[test]
+    fn set_creates_intermediate_objects() {
+        let mut value = json!({"version": 1});
+        path("canisters.app.main")
+            .set(&mut value, json!("src/main.mo"))
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({"version": 1, "canisters": {"app": {"main": "src/main.mo"}}})
+        );
+
+        let mut value = Value::Null;
+        path("local.bind")
+            .set(&mut value, json!("127.0.0.1:8000"))
+            .unwrap();
+        assert_eq!(value, json!({"local": {"bind": "127.0.0.1:8000"}}));
+    }
+
+    #[test]
+    fn set_array_elements() {
+        let mut value = json!({"deps": ["a"]});
+        path("deps.0").set(&mut value, json!("b")).unwrap();
+        path("deps.1").set(&mut value, json!("c")).unwrap();
+        path("deps.-").set(&mut value, json!("d")).unwrap();
+        assert_eq!(value, json!({"deps": ["b", "c", "d"]}));
+        assert!(matches!(
+            path("deps.7").set(&mut value, json!("e")),
+            Err(JsonPathError::InvalidArrayIndex { len: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn set_through_scalar_fails() {
+        let mut value = json!({"version": 1});
+        assert!(matches!(
+            path("version.major").set(&mut value, json!(1)),
+            Err(JsonPathError::NotAContainer(p)) if p == "version"
+        ));
+    }
+
+    #[test]
+    fn set_preserves_key_order() {
+        let mut value: Value = serde_json::from_str(r#"{"z": 1, "a": 2, "m": 3}"#).unwrap();
+        path("a").set(&mut value, json!(4)).unwrap();
+        path("b").set(&mut value, json!(5)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"z":1,"a":4,"m":3,"b":5}"#
+        );
+    }
+
+    #[test]
+    fn unset_values() {
+        let mut value = json!({"canisters": {"app": {"main": "x", "deps": ["a", "b"]}}});
+        assert_eq!(
+            path("canisters.app.deps.0").unset(&mut value).unwrap(),
+            Some(json!("a"))
+        );
+        assert_eq!(
+            path("canisters.app.main").unset(&mut value).unwrap(),
+            Some(json!("x"))
+        );
+        assert_eq!(
+            path("canisters.other.main").unset(&mut value).unwrap(),
+            None
+        );
+        assert_eq!(value, json!({"canisters": {"app": {"deps": ["b"]}}}));
+        assert!(path(".").unset(&mut value).is_err());
+    }
+}
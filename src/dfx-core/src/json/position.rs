@@ -0,0 +1,233 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// A 1-based line and column in a text file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+/// The positions of all values of a JSON document, indexed by JSON pointer.
+///
+/// Object members are located at their key, array elements at their value.
+/// Parsing is best-effort: on a syntax error, only the positions up to the error are recorded.
+#[derive(Debug, Default)]
+pub struct JsonPositions {
+    positions: HashMap<String, Position>,
+}
+
+impl JsonPositions {
+    pub fn parse(content: &str) -> Self {
+        let mut scanner = Scanner {
+            content,
+            chars: content.char_indices().peekable(),
+            line: 1,
+            column: 1,
+            positions: HashMap::new(),
+        };
+        scanner.skip_whitespace();
+        let start = scanner.position();
+        scanner.positions.insert(String::new(), start);
+        let _ = scanner.value(String::new());
+        Self {
+            positions: scanner.positions,
+        }
+    }
+
+    pub fn get(&self, pointer: &str) -> Option<Position> {
+        self.positions.get(pointer).copied()
+    }
+
+    /// Returns the position of the value at `pointer`, or of its nearest ancestor that exists.
+    pub fn find(&self, pointer: &str) -> Position {
+        let mut pointer = pointer;
+        loop {
+            if let Some(position) = self.get(pointer) {
+                return position;
+            }
+            match pointer.rfind('/') {
+                Some(index) => pointer = &pointer[..index],
+                None => return Position::default(),
+            }
+        }
+    }
+}
+
+struct Scanner<'a> {
+    content: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    line: usize,
+    column: usize,
+    positions: HashMap<String, Position>,
+}
+
+impl Scanner<'_> {
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        let next = self.chars.next();
+        match next {
+            Some((_, '\n')) => {
+                self.line += 1;
+                self.column = 1;
+            }
+            Some(_) => self.column += 1,
+            None => {}
+        }
+        next
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        (self.next()?.1 == expected).then_some(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn value(&mut self, pointer: String) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '{' => self.object(pointer),
+            '[' => self.array(pointer),
+            '"' => self.string().map(|_| ()),
+            _ => {
+                while matches!(self.peek(), Some(c) if c.is_alphanumeric() || "+-.".contains(c)) {
+                    self.next();
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn object(&mut self, pointer: String) -> Option<()> {
+        self.expect('{')?;
+        self.skip_whitespace();
+        if self.peek()? == '}' {
+            self.next();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let position = self.position();
+            let key = self.string()?;
+            let child = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+            self.positions.insert(child.clone(), position);
+            self.expect(':')?;
+            self.value(child)?;
+            self.skip_whitespace();
+            match self.next()?.1 {
+                ',' => continue,
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: String) -> Option<()> {
+        self.expect('[')?;
+        self.skip_whitespace();
+        if self.peek()? == ']' {
+            self.next();
+            return Some(());
+        }
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            let child = format!("{pointer}/{index}");
+            self.positions.insert(child.clone(), self.position());
+            self.value(child)?;
+            index += 1;
+            self.skip_whitespace();
+            match self.next()?.1 {
+                ',' => continue,
+                ']' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let (start, quote) = self.next()?;
+        if quote != '"' {
+            return None;
+        }
+        loop {
+            match self.next()? {
+                (_, '\\') => {
+                    self.next()?;
+                }
+                (end, '"') => {
+                    return serde_json::from_str(&self.content[start..=end]).ok();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize) -> Option<Position> {
+        Some(Position { line, column })
+    }
+
+    #[test]
+    fn locate_members_and_elements() {
+        let content = r#"{
+  "canisters": {
+    "app": {
+      "main": "src/main.mo",
+      "dependencies": [ "a", "b" ]
+    },
+    "a/b~c": {}
+  },
+  "version": 1
+}"#;
+        let positions = JsonPositions::parse(content);
+        assert_eq!(positions.get(""), at(1, 1));
+        assert_eq!(positions.get("/canisters"), at(2, 3));
+        assert_eq!(positions.get("/canisters/app/main"), at(4, 7));
+        assert_eq!(positions.get("/canisters/app/dependencies/1"), at(5, 30));
+        assert_eq!(positions.get("/canisters/a~1b~0c"), at(7, 5));
+        assert_eq!(positions.get("/version"), at(9, 3));
+    }
+
+    #[test]
+    fn find_falls_back_to_ancestor() {
+        let positions = JsonPositions::parse("{\n  \"a\": {\"b\": 1}\n}");
+        assert_eq!(positions.find("/a/b"), Position { line: 2, column: 9 });
+        assert_eq!(positions.find("/a/c/d"), Position { line: 2, column: 3 });
+        assert_eq!(positions.find("/x"), Position { line: 1, column: 1 });
+    }
+
+    #[test]
+    fn escaped_keys_and_invalid_input() {
+        let positions = JsonPositions::parse(r#"{"a\"b": [1, {"c": true}], "d": "#);
+        assert_eq!(positions.get("/a\"b/1/c"), at(1, 15));
+        assert_eq!(positions.get("/d"), at(1, 28));
+    }
+}
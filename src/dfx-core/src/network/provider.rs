@@ -3,7 +3,7 @@ use crate::config::directories::{
 };
 use crate::config::model::dfinity::{
     Config, ConfigDefaults, ConfigLocalProvider, ConfigNetwork, NetworkType, NetworksConfig,
-    DEFAULT_PROJECT_LOCAL_BIND, DEFAULT_SHARED_LOCAL_BIND,
+    DEFAULT_NAMED_LOCAL_BIND, DEFAULT_PROJECT_LOCAL_BIND, DEFAULT_SHARED_LOCAL_BIND,
 };
 use crate::config::model::local_server_descriptor::{
     LocalNetworkScopeDescriptor, LocalServerDescriptor,
@@ -110,6 +110,7 @@ fn config_network_to_network_descriptor(
                 .or_else(|| project_defaults.and_then(|x| x.replica.clone()))
                 .unwrap_or_default();
             let playground = local_provider.playground.clone();
+            let system_canisters = local_provider.system_canisters.clone().unwrap_or_default();
 
             let network_type = NetworkTypeDescriptor::new(
                 local_provider.r#type,
@@ -131,6 +132,7 @@ fn config_network_to_network_descriptor(
                 canister_http,
                 proxy,
                 replica,
+                system_canisters,
                 local_scope,
                 legacy_pid_path,
             )?;
@@ -249,6 +251,8 @@ fn create_shared_network_descriptor(
                 replica: None,
                 playground: None,
                 proxy: None,
+                system_canisters: None,
+                identities: None,
             }))
         }
         (network_name, None) => {
@@ -301,7 +305,7 @@ fn create_shared_network_descriptor(
             local_scope,
             ephemeral_wallet_config_path,
             local_bind_determination,
-            DEFAULT_SHARED_LOCAL_BIND,
+            default_local_bind(network_name, DEFAULT_SHARED_LOCAL_BIND),
             None,
         )
     })
@@ -367,7 +371,8 @@ fn create_project_network_descriptor(
                 }
             };
             let data_directory = temp_path.join("network").join(network_name);
-            let legacy_pid_path = Some(temp_path.join("pid"));
+            // dfx <= 0.11.x only ran the `local` network.
+            let legacy_pid_path = (network_name == "local").then(|| temp_path.join("pid"));
             let ephemeral_wallet_config_path = temp_path.join("local").join(WALLET_CONFIG_FILENAME);
             Some(config_network_to_network_descriptor(
                 network_name,
@@ -377,7 +382,7 @@ fn create_project_network_descriptor(
                 LocalNetworkScopeDescriptor::Project,
                 Some(ephemeral_wallet_config_path),
                 local_bind_determination,
-                DEFAULT_PROJECT_LOCAL_BIND,
+                default_local_bind(network_name, DEFAULT_PROJECT_LOCAL_BIND),
                 legacy_pid_path,
             ))
         } else {
@@ -412,6 +417,16 @@ fn create_default_network_from_name(
     }
 }
 
+/// The bind address of a local network that doesn't configure one.
+/// Only `local` has a fixed port, so that other local networks can run at the same time.
+fn default_local_bind<'a>(network_name: &str, default_local_bind: &'a str) -> &'a str {
+    if network_name == "local" {
+        default_local_bind
+    } else {
+        DEFAULT_NAMED_LOCAL_BIND
+    }
+}
+
 fn get_local_bind_address(
     local_provider: &ConfigLocalProvider,
     local_bind_determination: &LocalBindDetermination,
@@ -486,7 +501,7 @@ mod tests {
     use crate::config::model::dfinity::ReplicaSubnetType::{System, VerifiedApplication};
     use crate::config::model::dfinity::{
         to_socket_addr, ConfigDefaultsBitcoin, ConfigDefaultsCanisterHttp, ConfigDefaultsReplica,
-        ReplicaLogLevel,
+        ReplicaLogLevel, ReplicaSubnetsConfig,
     };
     use std::fs;
     use std::net::SocketAddr;
@@ -653,6 +668,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn named_local_network_binds_to_free_port() {
+        let config = Arc::new(
+            Config::from_str(
+                r#"{
+            "networks": {
+                "local": {},
+                "shard1": {}
+            }
+        }"#,
+            )
+            .unwrap(),
+        );
+
+        let descriptor = |network: &str| {
+            create_network_descriptor(
+                Some(config.clone()),
+                Arc::new(NetworksConfig::new().unwrap()),
+                Some(network.to_string()),
+                None,
+                LocalBindDetermination::AsConfigured,
+            )
+            .unwrap()
+            .local_server_descriptor
+            .unwrap()
+        };
+        let local = descriptor("local");
+        let shard1 = descriptor("shard1");
+
+        assert_eq!(
+            local.bind_address,
+            to_socket_addr(DEFAULT_PROJECT_LOCAL_BIND).unwrap()
+        );
+        assert_eq!(shard1.bind_address, to_socket_addr("127.0.0.1:0").unwrap());
+        assert_ne!(local.data_directory, shard1.data_directory);
+        assert_ne!(local.dfx_pid_path(), shard1.dfx_pid_path());
+    }
+
     #[test]
     fn config_with_invalid_local_bind_addr() {
         let config = Config::from_str(
@@ -935,7 +988,8 @@ mod tests {
             &ConfigDefaultsReplica {
                 subnet_type: Some(VerifiedApplication),
                 port: Some(17001),
-                log_level: Some(ReplicaLogLevel::Trace)
+                log_level: Some(ReplicaLogLevel::Trace),
+                subnets: None,
             }
         );
     }
@@ -982,11 +1036,54 @@ mod tests {
             &ConfigDefaultsReplica {
                 subnet_type: Some(System),
                 port: None,
-                log_level: None
+                log_level: None,
+                subnets: None,
             }
         );
     }
 
+    #[test]
+    fn replica_subnets_on_local_network() {
+        let config = Config::from_str(
+            r#"{
+              "networks": {
+                "local": {
+                  "bind": "127.0.0.1:8000",
+                  "replica": {
+                    "subnets": {
+                      "application": 2,
+                      "system": 1
+                    }
+                  }
+                }
+              }
+        }"#,
+        )
+        .unwrap();
+
+        let network_descriptor = create_network_descriptor(
+            Some(Arc::new(config)),
+            Arc::new(NetworksConfig::new().unwrap()),
+            None,
+            None,
+            LocalBindDetermination::AsConfigured,
+        )
+        .unwrap();
+        let replica_config = &network_descriptor
+            .local_server_descriptor()
+            .unwrap()
+            .replica;
+
+        assert_eq!(
+            replica_config.subnets,
+            Some(ReplicaSubnetsConfig {
+                application: 2,
+                system: 1,
+                verified_application: 0,
+            })
+        );
+    }
+
     #[test]
     fn canister_http_config_on_local_network() {
         let config = Config::from_str(
@@ -1021,7 +1118,8 @@ mod tests {
             canister_http_config,
             &ConfigDefaultsCanisterHttp {
                 enabled: true,
-                log_level: HttpAdapterLogLevel::Debug
+                log_level: HttpAdapterLogLevel::Debug,
+                mock: None,
             }
         );
     }
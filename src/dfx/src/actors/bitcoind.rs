@@ -0,0 +1,217 @@
+use crate::actors::shutdown::{wait_for_child_or_receiver, ChildOrReceiver};
+use crate::actors::shutdown_controller::signals::outbound::Shutdown;
+use crate::actors::shutdown_controller::signals::ShutdownSubscribe;
+use crate::actors::shutdown_controller::ShutdownController;
+use crate::lib::error::{DfxError, DfxResult};
+use actix::{
+    Actor, ActorContext, ActorFutureExt, Addr, AsyncContext, Context, Handler, ResponseActFuture,
+    Running, WrapFuture,
+};
+use anyhow::Context as _;
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use slog::{debug, error, info, warn, Logger};
+use std::path::PathBuf;
+use std::process::Child;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessExt, Signal, System, SystemExt};
+
+#[derive(Clone)]
+pub struct Config {
+    pub bitcoind_path: PathBuf,
+
+    pub data_dir: PathBuf,
+    pub port: u16,
+    pub rpc_port: u16,
+    pub rpc_cookie_path: PathBuf,
+    pub shutdown_controller: Addr<ShutdownController>,
+    pub bitcoind_pid_file_path: PathBuf,
+
+    pub logger: Option<Logger>,
+}
+
+/// An actor for a bitcoind process in regtest mode, which the bitcoin adapter connects to.
+/// Restarts bitcoind if it exits, and stops it when the local network shuts down.
+pub struct Bitcoind {
+    config: Config,
+
+    stop_sender: Option<Sender<()>>,
+    thread_join: Option<JoinHandle<()>>,
+
+    logger: Logger,
+}
+
+impl Bitcoind {
+    pub fn new(config: Config) -> Self {
+        let logger =
+            (config.logger.clone()).unwrap_or_else(|| Logger::root(slog::Discard, slog::o!()));
+        Bitcoind {
+            config,
+            stop_sender: None,
+            thread_join: None,
+            logger,
+        }
+    }
+
+    fn start_bitcoind(&mut self) -> DfxResult {
+        let logger = self.logger.clone();
+
+        let (sender, receiver) = unbounded();
+
+        let handle = bitcoind_start_thread(logger, self.config.clone(), receiver)
+            .context("Failed to start bitcoind thread.")?;
+
+        self.thread_join = Some(handle);
+        self.stop_sender = Some(sender);
+        Ok(())
+    }
+}
+
+impl Actor for Bitcoind {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        if let Err(e) = self.start_bitcoind() {
+            error!(self.logger, "Could not start bitcoind: {e:#}");
+            ctx.stop();
+            return;
+        }
+
+        self.config
+            .shutdown_controller
+            .do_send(ShutdownSubscribe(ctx.address().recipient::<Shutdown>()));
+    }
+
+    fn stopping(&mut self, _ctx: &mut Self::Context) -> Running {
+        info!(self.logger, "Stopping bitcoind...");
+        if let Some(sender) = self.stop_sender.take() {
+            let _ = sender.send(());
+        }
+
+        if let Some(join) = self.thread_join.take() {
+            let _ = join.join();
+        }
+
+        info!(self.logger, "Stopped.");
+        Running::Stop
+    }
+}
+
+impl Handler<Shutdown> for Bitcoind {
+    type Result = ResponseActFuture<Self, Result<(), ()>>;
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut Self::Context) -> Self::Result {
+        Box::pin(
+            async {}
+                .into_actor(self) // converts future to ActorFuture
+                .map(|_, _act, ctx| {
+                    ctx.stop();
+                    Ok(())
+                }),
+        )
+    }
+}
+
+/// Lets bitcoind flush its blocks and wallets to disk before it exits.
+/// Killing it outright could corrupt the chain state, which would then have to be cleaned.
+fn stop_gracefully(child: &mut Child) {
+    let pid = Pid::from(child.id() as usize);
+    let mut system = System::new();
+    system.refresh_process(pid);
+    let terminated = system
+        .process(pid)
+        .and_then(|process| process.kill_with(Signal::Term))
+        .unwrap_or(false);
+    if !terminated {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
+
+fn bitcoind_start_thread(
+    logger: Logger,
+    config: Config,
+    receiver: Receiver<()>,
+) -> DfxResult<std::thread::JoinHandle<()>> {
+    std::fs::create_dir_all(&config.data_dir).with_context(|| {
+        format!(
+            "Could not create bitcoind data directory {}.",
+            config.data_dir.display()
+        )
+    })?;
+
+    let thread_handler = move || {
+        let mut cmd = std::process::Command::new(&config.bitcoind_path);
+        cmd.args([
+            "-regtest",
+            "-server",
+            "-txindex",
+            "-printtoconsole=0",
+            "-fallbackfee=0.00001",
+            "-bind=127.0.0.1",
+            "-rpcbind=127.0.0.1",
+            "-rpcallowip=127.0.0.1",
+        ]);
+        cmd.arg(format!("-datadir={}", config.data_dir.display()));
+        cmd.arg(format!("-port={}", config.port));
+        cmd.arg(format!("-rpcport={}", config.rpc_port));
+        cmd.arg(format!(
+            "-rpccookiefile={}",
+            config.rpc_cookie_path.display()
+        ));
+
+        cmd.stdout(std::process::Stdio::inherit());
+        cmd.stderr(std::process::Stdio::inherit());
+
+        loop {
+            let last_start = std::time::Instant::now();
+            debug!(logger, "Starting bitcoind...");
+            let mut child = match cmd.spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    error!(
+                        logger,
+                        "Could not start bitcoind at {}: {e}",
+                        config.bitcoind_path.display()
+                    );
+                    break;
+                }
+            };
+
+            if let Err(e) = std::fs::write(&config.bitcoind_pid_file_path, child.id().to_string()) {
+                warn!(
+                    logger,
+                    "Failed to write bitcoind PID to {}: {e}",
+                    config.bitcoind_pid_file_path.display()
+                );
+            }
+
+            // This waits for the child to stop, or the receiver to receive a message.
+            match wait_for_child_or_receiver(&mut child, &receiver) {
+                ChildOrReceiver::Receiver => {
+                    debug!(logger, "Got signal to stop. Stopping bitcoind process...");
+                    stop_gracefully(&mut child);
+                    break;
+                }
+                ChildOrReceiver::Child => {
+                    debug!(logger, "bitcoind process failed.");
+                    // If it took less than two seconds to exit, wait a bit before trying again.
+                    if std::time::Instant::now().duration_since(last_start) < Duration::from_secs(2)
+                    {
+                        std::thread::sleep(Duration::from_secs(2));
+                    } else {
+                        debug!(
+                            logger,
+                            "Last bitcoind seemed to have been healthy, not waiting..."
+                        );
+                    }
+                }
+            }
+        }
+    };
+
+    std::thread::Builder::new()
+        .name("bitcoind-actor".to_owned())
+        .spawn(thread_handler)
+        .map_err(DfxError::from)
+}
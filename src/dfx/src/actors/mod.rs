@@ -1,4 +1,5 @@
 use self::pocketic::PocketIc;
+use crate::actors::bitcoind::Bitcoind;
 use crate::actors::btc_adapter::signals::BtcAdapterReadySubscribe;
 use crate::actors::btc_adapter::BtcAdapter;
 use crate::actors::canister_http_adapter::signals::CanisterHttpAdapterReadySubscribe;
@@ -7,9 +8,10 @@ use crate::actors::replica::{BitcoinIntegrationConfig, Replica};
 use crate::actors::shutdown_controller::ShutdownController;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::integrations::system_canisters::get_system_canisters;
 use crate::lib::progress_bar::ProgressBar;
 use actix::{Actor, Addr, Recipient};
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dfx_core::config::model::local_server_descriptor::LocalServerDescriptor;
 use dfx_core::config::model::replica_config::ReplicaConfig;
 use fn_error_context::context;
@@ -17,8 +19,9 @@ use pocketic_proxy::signals::PortReadySubscribe;
 use pocketic_proxy::{PocketIcProxy, PocketIcProxyConfig};
 use post_start::PostStart;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+pub mod bitcoind;
 pub mod btc_adapter;
 pub mod canister_http_adapter;
 pub mod pocketic;
@@ -61,6 +64,48 @@ pub fn start_btc_adapter_actor(
     Ok(BtcAdapter::new(actor_config).start().recipient())
 }
 
+#[context("Failed to start bitcoind.")]
+pub fn start_bitcoind_actor(
+    env: &dyn Environment,
+    local_server_descriptor: &LocalServerDescriptor,
+    shutdown_controller: Addr<ShutdownController>,
+) -> DfxResult<Option<Addr<Bitcoind>>> {
+    let Some(bitcoind) = &local_server_descriptor.bitcoin.bitcoind else {
+        return Ok(None);
+    };
+    let actor_config = bitcoind::Config {
+        bitcoind_path: bitcoind_path(env, bitcoind.path.as_deref())?,
+
+        data_dir: local_server_descriptor.bitcoind_data_dir(),
+        port: bitcoind.port,
+        rpc_port: bitcoind.rpc_port,
+        rpc_cookie_path: local_server_descriptor.bitcoind_rpc_cookie_path(),
+        shutdown_controller,
+        bitcoind_pid_file_path: local_server_descriptor.bitcoind_pid_path(),
+        logger: Some(env.get_logger().clone()),
+    };
+    Ok(Some(Bitcoind::new(actor_config).start()))
+}
+
+/// The bitcoind binary: the configured path (relative to the project root),
+/// otherwise the one in the dfx cache, otherwise the one on the PATH.
+fn bitcoind_path(env: &dyn Environment, configured: Option<&Path>) -> DfxResult<PathBuf> {
+    if let Some(path) = configured {
+        return Ok(match env.get_config()? {
+            Some(config) if path.is_relative() => config.get_project_root().join(path),
+            _ => path.to_path_buf(),
+        });
+    }
+    let cached = env.get_cache().get_binary_command_path(env, "bitcoind")?;
+    if cached.exists() {
+        return Ok(cached);
+    }
+    match which::which("bitcoind") {
+        Ok(path) => Ok(path),
+        Err(_) => bail!("Cannot find bitcoind. Install it, or set `bitcoin.bitcoind.path` in the network configuration."),
+    }
+}
+
 #[context("Failed to start canister http adapter actor.")]
 pub fn start_canister_http_adapter_actor(
     env: &dyn Environment,
@@ -188,6 +233,7 @@ pub fn start_pocketic_proxy_actor(
 pub fn start_pocketic_actor(
     env: &dyn Environment,
     replica_config: ReplicaConfig,
+    network_name: &str,
     local_server_descriptor: &LocalServerDescriptor,
     shutdown_controller: Addr<ShutdownController>,
     pocketic_port_path: PathBuf,
@@ -212,12 +258,22 @@ pub fn start_pocketic_actor(
     } else {
         None
     };
+    let system_canisters =
+        get_system_canisters(env, network_name, &local_server_descriptor.system_canisters)?;
+    let http_mock = http_mock_rules_path(env, local_server_descriptor)?.map(|rules_path| {
+        pocketic::HttpMockConfig {
+            rules_path,
+            log_path: local_server_descriptor.canister_http_mock_log_path(),
+        }
+    });
     let actor_config = pocketic::Config {
         pocketic_path,
         effective_config_path: local_server_descriptor.effective_config_path(),
         replica_config,
         bitcoind_addr: local_server_descriptor.bitcoin.nodes.clone(),
         bitcoin_integration_config,
+        system_canisters,
+        http_mock,
         port: local_server_descriptor.replica.port,
         port_file: pocketic_port_path,
         pid_file: local_server_descriptor.pocketic_pid_path(),
@@ -228,6 +284,21 @@ pub fn start_pocketic_actor(
     Ok(pocketic::PocketIc::new(actor_config).start())
 }
 
+/// The mock rules file of `canister_http.mock`. Relative paths are relative to the project root.
+pub fn http_mock_rules_path(
+    env: &dyn Environment,
+    local_server_descriptor: &LocalServerDescriptor,
+) -> DfxResult<Option<PathBuf>> {
+    let Some(mock) = &local_server_descriptor.canister_http.mock else {
+        return Ok(None);
+    };
+    let path = match env.get_config()? {
+        Some(config) if mock.is_relative() => config.get_project_root().join(mock),
+        _ => mock.clone(),
+    };
+    Ok(Some(path))
+}
+
 #[context("Failed to start PostStart actor.")]
 pub fn start_post_start_actor(
     env: &dyn Environment,
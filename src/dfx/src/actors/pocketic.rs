@@ -11,6 +11,10 @@ use crate::lib::info::replica_rev;
 use crate::lib::integrations::bitcoin::initialize_bitcoin_canister;
 #[cfg(unix)]
 use crate::lib::integrations::create_integrations_agent;
+#[cfg(unix)]
+use crate::lib::integrations::system_canisters::initialize_system_canisters;
+use crate::lib::integrations::system_canisters::SystemCanister;
+use crate::lib::replica::http_mock::HttpMock;
 use actix::{
     Actor, ActorContext, ActorFutureExt, Addr, AsyncContext, Context, Handler, Recipient,
     ResponseActFuture, Running, WrapFuture,
@@ -18,11 +22,13 @@ use actix::{
 use anyhow::{anyhow, bail};
 #[cfg(unix)]
 use candid::Principal;
-use crossbeam::channel::{unbounded, Receiver, Sender};
-#[cfg(unix)]
-use dfx_core::config::model::replica_config::CachedConfig;
+use crossbeam::channel::{unbounded, Receiver, RecvTimeoutError, Sender};
 use dfx_core::config::model::replica_config::ReplicaConfig;
 #[cfg(unix)]
+use dfx_core::config::model::replica_config::{
+    CachedConfig, CanisterIdRange, LocalSubnet, LocalSubnetKind,
+};
+#[cfg(unix)]
 use dfx_core::json::save_json_file;
 use slog::{debug, error, warn, Logger};
 use std::net::SocketAddr;
@@ -51,6 +57,8 @@ pub struct Config {
     pub replica_config: ReplicaConfig,
     pub bitcoind_addr: Option<Vec<SocketAddr>>,
     pub bitcoin_integration_config: Option<BitcoinIntegrationConfig>,
+    pub system_canisters: Vec<SystemCanister>,
+    pub http_mock: Option<HttpMockConfig>,
     pub port: Option<u16>,
     pub port_file: PathBuf,
     pub pid_file: PathBuf,
@@ -59,6 +67,14 @@ pub struct Config {
     pub verbose: bool,
 }
 
+/// Answers HTTPS outcalls from mock rules instead of sending them to the internet.
+/// dfx then executes rounds itself, instead of letting PocketIC progress automatically.
+#[derive(Clone)]
+pub struct HttpMockConfig {
+    pub rules_path: PathBuf,
+    pub log_path: PathBuf,
+}
+
 /// A PocketIC actor. Starts the server, can subscribe to a Ready signal and a
 /// Killed signal.
 /// This starts a thread that monitors the process and send signals to any subscriber
@@ -280,6 +296,8 @@ fn pocketic_start_thread(
                 &config.effective_config_path,
                 &config.bitcoind_addr,
                 &config.bitcoin_integration_config,
+                &config.system_canisters,
+                config.http_mock.is_some(),
                 &config.replica_config,
                 logger.clone(),
             ) {
@@ -298,9 +316,23 @@ fn pocketic_start_thread(
                 Ok(i) => i,
             };
             addr.do_send(signals::PocketIcRestarted { port });
+            let http_mock = config.http_mock.clone().map(|http_mock| {
+                start_http_mock_thread(
+                    port,
+                    instance,
+                    http_mock,
+                    config.replica_config.artificial_delay,
+                    logger.clone(),
+                )
+            });
             // This waits for the child to stop, or the receiver to receive a message.
             // We don't restart the server if done = true.
-            match wait_for_child_or_receiver(&mut child, &receiver) {
+            let result = wait_for_child_or_receiver(&mut child, &receiver);
+            if let Some((stop, handle)) = http_mock {
+                let _ = stop.send(());
+                let _ = handle.join();
+            }
+            match result {
                 ChildOrReceiver::Receiver => {
                     debug!(logger, "Got signal to stop. Killing PocketIC process...");
                     if let Err(e) = shutdown_pocketic(port, instance, logger.clone()) {
@@ -339,6 +371,8 @@ async fn initialize_pocketic(
     effective_config_path: &Path,
     bitcoind_addr: &Option<Vec<SocketAddr>>,
     bitcoin_integration_config: &Option<BitcoinIntegrationConfig>,
+    system_canisters: &[SystemCanister],
+    http_mock: bool,
     replica_config: &ReplicaConfig,
     logger: Logger,
 ) -> DfxResult<usize> {
@@ -361,11 +395,22 @@ async fn initialize_pocketic(
         verified_application: vec![],
         application: vec![],
     };
-    match replica_config.subnet_type {
-        ReplicaSubnetType::Application => subnet_config_set.application.push(<_>::default()),
-        ReplicaSubnetType::System => subnet_config_set.system.push(<_>::default()),
-        ReplicaSubnetType::VerifiedApplication => {
-            subnet_config_set.verified_application.push(<_>::default())
+    if let Some(subnets) = replica_config.subnets {
+        if subnets.application == 0 && subnets.system == 0 && subnets.verified_application == 0 {
+            bail!("`replica.subnets` must configure at least one subnet.");
+        }
+        let specs =
+            |count: u8| -> Vec<SubnetSpec> { (0..count).map(|_| SubnetSpec::default()).collect() };
+        subnet_config_set.application = specs(subnets.application);
+        subnet_config_set.system = specs(subnets.system);
+        subnet_config_set.verified_application = specs(subnets.verified_application);
+    } else {
+        match replica_config.subnet_type {
+            ReplicaSubnetType::Application => subnet_config_set.application.push(<_>::default()),
+            ReplicaSubnetType::System => subnet_config_set.system.push(<_>::default()),
+            ReplicaSubnetType::VerifiedApplication => {
+                subnet_config_set.verified_application.push(<_>::default())
+            }
         }
     }
     let resp = init_client
@@ -391,12 +436,14 @@ async fn initialize_pocketic(
             topology,
         } => {
             let default_effective_canister_id: Principal =
-                topology.default_effective_canister_id.into();
+                topology.default_effective_canister_id.clone().into();
             let effective_config = CachedConfig::pocketic(
                 replica_config,
                 replica_rev().into(),
                 Some(default_effective_canister_id),
-            );
+            )
+            .with_instance_id(instance_id)
+            .with_subnets(local_subnets(&topology));
             save_json_file(effective_config_path, &effective_config)?;
             instance_id
         }
@@ -434,23 +481,136 @@ async fn initialize_pocketic(
         let agent = create_integrations_agent(&agent_url, &logger).await?;
         initialize_bitcoin_canister(&agent, &logger, bitcoin_integration_config.clone()).await?;
     }
+    if !system_canisters.is_empty() {
+        let agent = create_integrations_agent(&agent_url, &logger).await?;
+        initialize_system_canisters(&agent, &logger, system_canisters).await?;
+    }
+    if http_mock {
+        // From now on, the HTTP mock thread executes rounds, so that it can answer outcalls in between.
+        init_client
+            .post(format!(
+                "http://localhost:{port}/instances/{instance}/stop_progress"
+            ))
+            .send()
+            .await?
+            .error_for_status()?;
+    }
 
     debug!(logger, "Initialized PocketIC.");
     Ok(instance)
 }
 
+/// The subnets of the topology, ordered by kind and canister ID range, so that indices are stable.
+#[cfg(unix)]
+fn local_subnets(topology: &pocket_ic::common::rest::Topology) -> Vec<LocalSubnet> {
+    use pocket_ic::common::rest::SubnetKind;
+    let mut subnets: Vec<(LocalSubnetKind, Principal, Vec<CanisterIdRange>)> = topology
+        .subnet_configs
+        .iter()
+        .map(|(subnet_id, config)| {
+            let kind = match config.subnet_kind {
+                SubnetKind::Application => LocalSubnetKind::Application,
+                SubnetKind::System => LocalSubnetKind::System,
+                SubnetKind::VerifiedApplication => LocalSubnetKind::VerifiedApplication,
+                SubnetKind::NNS => LocalSubnetKind::Nns,
+                SubnetKind::SNS => LocalSubnetKind::Sns,
+                SubnetKind::II => LocalSubnetKind::Ii,
+                SubnetKind::Fiduciary => LocalSubnetKind::Fiduciary,
+                SubnetKind::Bitcoin => LocalSubnetKind::Bitcoin,
+            };
+            let canister_ranges = config
+                .canister_ranges
+                .iter()
+                .map(|range| CanisterIdRange {
+                    start: range.start.clone().into(),
+                    end: range.end.clone().into(),
+                })
+                .collect();
+            (kind, Principal::from(*subnet_id), canister_ranges)
+        })
+        .collect();
+    subnets.sort_by_key(|(kind, _, ranges)| (*kind, ranges.first().map(|range| range.start)));
+
+    let mut result: Vec<LocalSubnet> = vec![];
+    for (kind, subnet_id, canister_ranges) in subnets {
+        let index = result.iter().filter(|subnet| subnet.kind == kind).count();
+        result.push(LocalSubnet {
+            kind,
+            index,
+            subnet_id,
+            canister_ranges,
+        });
+    }
+    result
+}
+
 #[cfg(not(unix))]
 fn initialize_pocketic(
     _: u16,
     _: &Path,
     _: &Option<Vec<SocketAddr>>,
     _: &Option<BitcoinIntegrationConfig>,
+    _: &[SystemCanister],
+    _: bool,
     _: &ReplicaConfig,
     _: Logger,
 ) -> DfxResult<usize> {
     bail!("PocketIC not supported on this platform")
 }
 
+/// Executes rounds and advances time like PocketIC does automatically,
+/// and answers HTTPS outcalls from the mock rules in between, until it is told to stop.
+fn start_http_mock_thread(
+    port: u16,
+    instance: usize,
+    config: HttpMockConfig,
+    artificial_delay: u32,
+    logger: Logger,
+) -> (Sender<()>, JoinHandle<()>) {
+    let (stop, stopped) = unbounded();
+    let handle = std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Unable to create a runtime");
+        let server_url = format!("http://localhost:{port}/")
+            .parse()
+            .expect("Invalid PocketIC server url");
+        let pocketic = pocket_ic::nonblocking::PocketIc::new_from_existing_instance(
+            server_url, instance, None,
+        );
+        let mut http_mock = match HttpMock::new(config.rules_path, config.log_path) {
+            Ok(http_mock) => http_mock,
+            Err(e) => {
+                error!(logger, "Failed to mock HTTPS outcalls: {e:#}");
+                return;
+            }
+        };
+        let interval = Duration::from_millis(artificial_delay.max(1) as u64);
+        let mut last_round = Instant::now();
+        loop {
+            let result: DfxResult = runtime.block_on(async {
+                pocketic.tick().await;
+                http_mock.answer_outcalls(&pocketic).await?;
+                let now = Instant::now();
+                pocketic.advance_time(now - last_round).await;
+                last_round = now;
+                Ok(())
+            });
+            if let Err(e) = result {
+                error!(logger, "Failed to mock HTTPS outcalls: {e:#}");
+            }
+            if !matches!(
+                stopped.recv_timeout(interval),
+                Err(RecvTimeoutError::Timeout)
+            ) {
+                break;
+            }
+        }
+    });
+    (stop, handle)
+}
+
 #[cfg(unix)]
 #[tokio::main(flavor = "current_thread")]
 async fn shutdown_pocketic(port: u16, instance: usize, logger: Logger) -> DfxResult {
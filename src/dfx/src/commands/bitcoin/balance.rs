@@ -0,0 +1,24 @@
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::bitcoind::{format_btc, BitcoindRpc};
+use clap::Parser;
+
+/// Prints the confirmed balance of an address, or of the wallet of the node.
+#[derive(Parser)]
+pub struct BalanceOpts {
+    /// The address to get the balance of.
+    address: Option<String>,
+
+    /// Prints the balance in satoshis instead of BTC.
+    #[arg(long)]
+    satoshis: bool,
+}
+
+pub async fn exec(bitcoind: &BitcoindRpc, opts: BalanceOpts) -> DfxResult {
+    let balance = bitcoind.balance(opts.address.as_deref()).await?;
+    if opts.satoshis {
+        println!("{balance}");
+    } else {
+        println!("{} BTC", format_btc(balance));
+    }
+    Ok(())
+}
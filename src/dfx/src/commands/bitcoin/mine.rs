@@ -0,0 +1,25 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::bitcoind::BitcoindRpc;
+use clap::Parser;
+use slog::info;
+
+/// Mines blocks. Block rewards can be spent after 100 more blocks are mined.
+#[derive(Parser)]
+pub struct MineOpts {
+    /// The number of blocks to mine.
+    blocks: u64,
+
+    /// The address that receives the block rewards. Defaults to the wallet of the node,
+    /// which funds `dfx bitcoin send`.
+    address: Option<String>,
+}
+
+pub async fn exec(env: &dyn Environment, bitcoind: &BitcoindRpc, opts: MineOpts) -> DfxResult {
+    let hashes = bitcoind.mine(opts.blocks, opts.address).await?;
+    info!(env.get_logger(), "Mined {} blocks.", hashes.len());
+    for hash in hashes {
+        println!("{hash}");
+    }
+    Ok(())
+}
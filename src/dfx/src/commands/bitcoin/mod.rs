@@ -0,0 +1,48 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::bitcoind::BitcoindRpc;
+use crate::lib::network::network_opt::NetworkOpt;
+use clap::Parser;
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+use tokio::runtime::Runtime;
+
+mod balance;
+mod mine;
+mod send;
+
+/// Commands to use the bitcoind node that `dfx start --bitcoind` runs in regtest mode.
+#[derive(Parser)]
+#[command(name = "bitcoin")]
+pub struct BitcoinOpts {
+    #[command(flatten)]
+    network: NetworkOpt,
+
+    #[command(subcommand)]
+    subcmd: SubCommand,
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    Balance(balance::BalanceOpts),
+    Mine(mine::MineOpts),
+    Send(send::SendOpts),
+}
+
+pub fn exec(env: &dyn Environment, opts: BitcoinOpts) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        opts.network.to_network_name(),
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let bitcoind = BitcoindRpc::connect(&network_descriptor)?;
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    runtime.block_on(async {
+        match opts.subcmd {
+            SubCommand::Balance(v) => balance::exec(&bitcoind, v).await,
+            SubCommand::Mine(v) => mine::exec(env, &bitcoind, v).await,
+            SubCommand::Send(v) => send::exec(&bitcoind, v).await,
+        }
+    })
+}
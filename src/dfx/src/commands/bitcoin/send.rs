@@ -0,0 +1,21 @@
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::bitcoind::{parse_btc, BitcoindRpc};
+use clap::Parser;
+
+/// Sends BTC from the wallet of the node and prints the transaction id.
+/// The transaction is confirmed when the next block is mined.
+#[derive(Parser)]
+pub struct SendOpts {
+    /// The address to send to.
+    address: String,
+
+    /// The amount of BTC, with up to 8 decimals.
+    #[arg(value_parser = parse_btc)]
+    amount: u64,
+}
+
+pub async fn exec(bitcoind: &BitcoindRpc, opts: SendOpts) -> DfxResult {
+    let txid = bitcoind.send(&opts.address, opts.amount).await?;
+    println!("{txid}");
+    Ok(())
+}
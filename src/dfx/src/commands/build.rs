@@ -6,7 +6,9 @@ use crate::lib::error::DfxResult;
 use crate::lib::models::canister::CanisterPool;
 use crate::lib::network::network_opt::NetworkOpt;
 use crate::lib::operations::canister::add_canisters_with_ids;
+use crate::lib::watch::watch_canisters;
 use clap::Parser;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use std::path::PathBuf;
 use tokio::runtime::Runtime;
 
@@ -14,6 +16,7 @@ use tokio::runtime::Runtime;
 #[derive(Parser)]
 pub struct CanisterBuildOpts {
     /// Specifies the name of the canister to build.
+    /// A glob pattern such as `svc_*` selects several canisters.
     /// You must specify either a canister name or the --all option.
     canister_name: Option<String>,
 
@@ -21,6 +24,11 @@ pub struct CanisterBuildOpts {
     #[arg(long, conflicts_with("canister_name"))]
     all: bool,
 
+    /// Only builds the canisters with this tag in dfx.json, and their dependencies.
+    /// Can be specified more than once.
+    #[arg(long, conflicts_with("all"))]
+    tag: Vec<String>,
+
     /// Build canisters without creating them. This can be used to check that canisters build ok.
     #[arg(long)]
     check: bool,
@@ -29,6 +37,10 @@ pub struct CanisterBuildOpts {
     #[arg(long)]
     output_env_file: Option<PathBuf>,
 
+    /// Keep running, and rebuild canisters whenever their sources change.
+    #[arg(long)]
+    watch: bool,
+
     #[command(flatten)]
     network: NetworkOpt,
 }
@@ -48,13 +60,14 @@ pub fn exec(env: &dyn Environment, opts: CanisterBuildOpts) -> DfxResult {
 
     let build_mode_check = opts.check;
 
-    // Option can be None in which case --all was specified
+    // Without a canister name or tag, all canisters are built.
+    let selector = CanisterSelector::new(opts.canister_name.as_deref(), &opts.tag);
     let required_canisters = config
         .get_config()
-        .get_canister_names_with_dependencies(opts.canister_name.as_deref())?;
+        .get_selected_canister_names_with_dependencies(&selector)?;
     let canisters_to_load = add_canisters_with_ids(&required_canisters, &env, &config);
 
-    let canisters_to_build = required_canisters
+    let canisters_to_build: Vec<String> = required_canisters
         .into_iter()
         .filter(|canister_name| {
             !config
@@ -84,11 +97,32 @@ pub fn exec(env: &dyn Environment, opts: CanisterBuildOpts) -> DfxResult {
 
     let runtime = Runtime::new().expect("Unable to create a runtime");
     let build_config = BuildConfig::from_config(&config)?
-        .with_canisters_to_build(canisters_to_build)
-        .with_env_file(env_file);
-    runtime.block_on(canister_pool.build_or_fail(&env, logger, &build_config))?;
-
-    slog::info!(logger, "Finished building canisters.");
+        .with_canisters_to_build(canisters_to_build.clone())
+        .with_env_file(env_file.clone());
+    // In watch mode, a failed build is retried with the next change.
+    let failed = match runtime.block_on(canister_pool.build_or_fail(&env, logger, &build_config)) {
+        Ok(()) => {
+            slog::info!(logger, "Finished building canisters.");
+            vec![]
+        }
+        Err(err) if opts.watch => {
+            slog::error!(logger, "{:#}", err);
+            canisters_to_build.clone()
+        }
+        Err(err) => return Err(err),
+    };
+
+    if opts.watch {
+        watch_canisters(logger, &config, &canisters_to_build, &failed, |affected| {
+            let canister_pool = CanisterPool::load(&env, build_mode_check, &canisters_to_load)?;
+            let build_config = BuildConfig::from_config(&config)?
+                .with_canisters_to_build(affected.to_vec())
+                .with_env_file(env_file.clone());
+            runtime.block_on(canister_pool.build_or_fail(&env, logger, &build_config))?;
+            slog::info!(logger, "Finished building canisters.");
+            Ok(())
+        })?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,68 @@
+use crate::commands::canister::call::get_effective_canister_id_of_call;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::sign::approval_bundle::ApprovalBundleV1;
+use crate::lib::sign::signed_message::SignedMessageV1;
+use anyhow::{anyhow, bail, Context};
+use candid::Principal;
+use clap::Parser;
+use slog::info;
+use std::path::PathBuf;
+
+/// Approve a proposed canister call by signing it with the current identity.
+/// Review the call with `dfx canister review` first.
+#[derive(Parser)]
+pub struct CanisterApproveOpts {
+    /// Specifies the file name of the proposal. The approval is added to it.
+    file_name: PathBuf,
+
+    /// Specifies how long the approval will be valid, default to be 5 minutes.
+    /// The Internet Computer only accepts messages that expire within 5 minutes.
+    #[arg(long, default_value = "5m")]
+    expire_after: String,
+}
+
+pub async fn exec(env: &dyn Environment, opts: CanisterApproveOpts) -> DfxResult {
+    let log = env.get_logger();
+    let mut bundle = ApprovalBundleV1::load(&opts.file_name)?;
+    let proposal = &bundle.proposal;
+
+    let network = env
+        .get_network_descriptor()
+        .providers
+        .first()
+        .expect("Cannot get network provider (url).")
+        .to_string();
+    if network != proposal.network {
+        bail!(
+            "The call is proposed on {}, but the selected network is {}.",
+            proposal.network,
+            network
+        );
+    }
+
+    let timeout = humantime::parse_duration(&opts.expire_after)
+        .map_err(|_| anyhow!("Cannot parse expire_after as a duration (e.g. `1h`, `1h 30m`)"))?;
+    let canister_id = Principal::from_text(&proposal.canister_id)
+        .with_context(|| format!("Failed to parse canister id {:?}.", proposal.canister_id))?;
+    let effective_canister_id =
+        get_effective_canister_id_of_call(canister_id, &proposal.method_name, &proposal.arg)?;
+    let approval = SignedMessageV1::sign_update(
+        env,
+        canister_id,
+        effective_canister_id,
+        &proposal.method_name,
+        proposal.arg.clone(),
+        timeout,
+    )?;
+    bundle.add_approval(approval)?;
+    bundle.save(&opts.file_name)?;
+    info!(
+        log,
+        "Approved [{}]: {} of {} approvals.",
+        opts.file_name.display(),
+        bundle.approvals.len(),
+        bundle.threshold
+    );
+    Ok(())
+}
@@ -213,6 +213,25 @@ pub fn get_effective_canister_id(
     }
 }
 
+/// The canister whose subnet executes a call: the canister itself, or for management canister
+/// methods the canister in the arguments.
+pub fn get_effective_canister_id_of_call(
+    canister_id: CanisterId,
+    method_name: &str,
+    arg_value: &[u8],
+) -> DfxResult<CanisterId> {
+    if canister_id != CanisterId::management_canister() {
+        return Ok(canister_id);
+    }
+    let management_method = MgmtMethod::from_str(method_name).map_err(|_| {
+        anyhow!(
+            "Attempted to call an unsupported management canister method: {}",
+            method_name
+        )
+    })?;
+    get_effective_canister_id(&management_method, arg_value)
+}
+
 pub async fn exec(
     env: &dyn Environment,
     opts: CanisterCallOpts,
@@ -0,0 +1,155 @@
+use crate::lib::cycles_history::{CanisterCyclesHistory, CyclesHistory, CyclesSnapshot};
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::canister::{get_canister_status, selected_project_canisters};
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::table;
+use crate::util::format_as_tc;
+use anyhow::{anyhow, Context};
+use candid::Principal;
+use clap::Parser;
+use dfx_core::config::model::canister_selector::CanisterSelector;
+use dfx_core::identity::CallSender;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
+
+/// Records the cycles balance, memory size and query statistics of canisters in a local history,
+/// and reports their burn rate and the date at which they are projected to freeze.
+/// Running it regularly, for example from a scheduled job, makes the estimates more accurate.
+#[derive(Parser)]
+pub struct CyclesReportOpts {
+    /// Specifies the name or id of the canister to report on.
+    /// You must specify either a canister name/id or the --all flag.
+    canister: Option<String>,
+
+    /// Reports on all of the canisters configured in the dfx.json file.
+    #[arg(long, required_unless_present_any(["canister", "tag"]))]
+    all: bool,
+
+    /// Only report on the canisters with this tag in dfx.json. Can be specified more than once.
+    #[arg(long, conflicts_with("all"))]
+    tag: Vec<String>,
+
+    /// Prints the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+pub async fn exec(
+    env: &dyn Environment,
+    opts: CyclesReportOpts,
+    call_sender: &CallSender,
+) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let selector = CanisterSelector::new(opts.canister.as_deref(), &opts.tag);
+    let canisters = match selector.single() {
+        Some(canister) => vec![canister.to_string()],
+        None => selected_project_canisters(env, &selector)?,
+    };
+    let project_temp_dir = env
+        .get_project_temp_dir()?
+        .ok_or_else(|| anyhow!("dfx canister cycles-report must be run in a project."))?;
+    let path = CyclesHistory::path(&project_temp_dir, &env.get_network_descriptor().name);
+    let mut history = CyclesHistory::load(&path)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let canister_id_store = env.get_canister_id_store()?;
+    let mut canister_ids = vec![];
+    for canister in canisters {
+        let canister_id =
+            Principal::from_text(&canister).or_else(|_| canister_id_store.get(&canister))?;
+        let status = get_canister_status(env, canister_id, call_sender)
+            .await
+            .with_context(|| format!("Failed to get canister status for '{canister}'."))?;
+        history.record(
+            &canister_id.to_text(),
+            &canister,
+            CyclesSnapshot::from_status(now, &status),
+        );
+        canister_ids.push(canister_id.to_text());
+    }
+    history.save(&path)?;
+
+    let mut reported: Vec<&CanisterCyclesHistory> = canister_ids
+        .iter()
+        .map(|canister_id| &history.canisters[canister_id])
+        .collect();
+    // The top consumers come first.
+    reported.sort_by_key(|canister| {
+        std::cmp::Reverse(canister.burn_rate().map_or(0, |rate| rate.per_day))
+    });
+
+    if opts.json {
+        let report: Vec<_> = reported
+            .iter()
+            .map(|canister| {
+                let latest = canister.latest().unwrap();
+                let rate = canister.burn_rate().unwrap();
+                json!({
+                    "canister": canister.name,
+                    "balance": latest.balance.to_string(),
+                    "memory_size": latest.memory_size.to_string(),
+                    "burn_rate_per_day": rate.per_day.to_string(),
+                    "burn_rate_is_idle_only": rate.idle_only,
+                    "freezing_threshold_cycles": latest.freezing_threshold_cycles().to_string(),
+                    "projected_freeze": canister.projected_freeze(),
+                    "query_calls": canister.query_calls().to_string(),
+                    "snapshots": canister.snapshots.len(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    print_table(&reported);
+    let total: u128 = reported
+        .iter()
+        .filter_map(|canister| canister.burn_rate())
+        .map(|rate| rate.per_day)
+        .sum();
+    println!("Total burn rate: {} per day", format_as_tc(total));
+    Ok(())
+}
+
+fn format_date(timestamp: u64) -> String {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+        .map_or_else(|| "never".to_string(), |date| date.date().to_string())
+}
+
+fn print_table(reported: &[&CanisterCyclesHistory]) {
+    let rows: Vec<[String; 6]> = reported
+        .iter()
+        .map(|canister| {
+            let latest = canister.latest().unwrap();
+            let rate = canister.burn_rate().unwrap();
+            [
+                canister.name.clone(),
+                format_as_tc(latest.balance),
+                format!("{:.2} MiB", latest.memory_size as f64 / (1 << 20) as f64),
+                if rate.idle_only {
+                    format!("{} (idle)", format_as_tc(rate.per_day))
+                } else {
+                    format_as_tc(rate.per_day)
+                },
+                canister.query_calls().to_string(),
+                canister
+                    .projected_freeze()
+                    .map_or_else(|| "never".to_string(), format_date),
+            ]
+        })
+        .collect();
+    table::print_table(
+        [
+            "CANISTER", "BALANCE", "MEMORY", "BURN/DAY", "QUERIES", "FREEZES",
+        ],
+        &rows,
+    );
+}
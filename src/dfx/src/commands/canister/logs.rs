@@ -1,9 +1,11 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::operations::canister;
+use crate::lib::operations::canister::selected_project_canisters;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use candid::Principal;
 use clap::Parser;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::identity::CallSender;
 use ic_utils::interfaces::management_canister::FetchCanisterLogsResponse;
 use time::format_description::well_known::Rfc3339;
@@ -13,7 +15,17 @@ use time::OffsetDateTime;
 #[derive(Parser)]
 pub struct LogsOpts {
     /// Specifies the name or id of the canister to get its canister information.
-    canister: String,
+    /// A glob pattern such as `svc_*` selects several canisters.
+    /// You must specify either a canister name/id, the --all flag or the --tag option.
+    canister: Option<String>,
+
+    /// Gets the logs of all of the canisters configured in the dfx.json file.
+    #[arg(long, required_unless_present_any(["canister", "tag"]))]
+    all: bool,
+
+    /// Only get the logs of the canisters with this tag in dfx.json. Can be specified more than once.
+    #[arg(long, conflicts_with("all"))]
+    tag: Vec<String>,
 }
 
 fn format_bytes(bytes: &[u8]) -> String {
@@ -74,18 +86,34 @@ fn test_format_canister_logs() {
     );
 }
 
-pub async fn exec(env: &dyn Environment, opts: LogsOpts, call_sender: &CallSender) -> DfxResult {
-    let callee_canister = opts.canister.as_str();
+async fn canister_logs(
+    env: &dyn Environment,
+    canister: &str,
+    call_sender: &CallSender,
+) -> DfxResult<Vec<String>> {
     let canister_id_store = env.get_canister_id_store()?;
+    let canister_id =
+        Principal::from_text(canister).or_else(|_| canister_id_store.get(canister))?;
 
-    let canister_id = Principal::from_text(callee_canister)
-        .or_else(|_| canister_id_store.get(callee_canister))?;
+    let logs = canister::get_canister_logs(env, canister_id, call_sender).await?;
+    Ok(format_canister_logs(logs))
+}
 
+pub async fn exec(env: &dyn Environment, opts: LogsOpts, call_sender: &CallSender) -> DfxResult {
     fetch_root_key_if_needed(env).await?;
 
-    let logs = canister::get_canister_logs(env, canister_id, call_sender).await?;
-
-    println!("{}", format_canister_logs(logs).join("\n"));
+    let selector = CanisterSelector::new(opts.canister.as_deref(), &opts.tag);
+    if let Some(canister) = selector.single() {
+        let logs = canister_logs(env, canister, call_sender).await?;
+        println!("{}", logs.join("\n"));
+    } else {
+        // Prefix each line with the canister name, so that the output can be filtered.
+        for canister in selected_project_canisters(env, &selector)? {
+            for line in canister_logs(env, &canister, call_sender).await? {
+                println!("{canister} {line}");
+            }
+        }
+    }
 
     Ok(())
 }
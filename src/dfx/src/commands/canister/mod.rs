@@ -6,8 +6,10 @@ use clap::{Parser, Subcommand};
 use dfx_core::identity::CallSender;
 use tokio::runtime::Runtime;
 
-mod call;
+mod approve;
+pub mod call;
 mod create;
+mod cycles_report;
 mod delete;
 mod deposit_cycles;
 mod id;
@@ -15,7 +17,9 @@ mod info;
 mod install;
 mod logs;
 mod metadata;
+mod propose;
 mod request_status;
+mod review;
 mod send;
 mod set_id;
 mod sign;
@@ -45,15 +49,19 @@ pub struct CanisterOpts {
 
 #[derive(Subcommand)]
 pub enum SubCommand {
+    Approve(approve::CanisterApproveOpts),
     Call(call::CanisterCallOpts),
     Create(create::CanisterCreateOpts),
+    CyclesReport(cycles_report::CyclesReportOpts),
     Delete(delete::CanisterDeleteOpts),
     DepositCycles(deposit_cycles::DepositCyclesOpts),
     Id(id::CanisterIdOpts),
     Info(info::InfoOpts),
     Install(install::CanisterInstallOpts),
     Metadata(metadata::CanisterMetadataOpts),
+    Propose(propose::CanisterProposeOpts),
     RequestStatus(request_status::RequestStatusOpts),
+    Review(review::CanisterReviewOpts),
     Send(send::CanisterSendOpts),
     SetId(set_id::CanisterSetIdOpts),
     Sign(sign::CanisterSignOpts),
@@ -80,15 +88,19 @@ pub fn exec(env: &dyn Environment, opts: CanisterOpts) -> DfxResult {
     runtime.block_on(async {
         let call_sender = || CallSender::from(&opts.wallet, env.get_network_descriptor());
         match opts.subcmd {
+            SubCommand::Approve(v) => approve::exec(env, v).await,
             SubCommand::Call(v) => call::exec(env, v, &call_sender()?).await,
             SubCommand::Create(v) => create::exec(env, v, &call_sender()?).await,
+            SubCommand::CyclesReport(v) => cycles_report::exec(env, v, &call_sender()?).await,
             SubCommand::Delete(v) => delete::exec(env, v, &call_sender()?).await,
             SubCommand::DepositCycles(v) => deposit_cycles::exec(env, v, &call_sender()?).await,
             SubCommand::Id(v) => id::exec(env, v).await,
             SubCommand::Install(v) => install::exec(env, v, &call_sender()?).await,
             SubCommand::Info(v) => info::exec(env, v).await,
             SubCommand::Metadata(v) => metadata::exec(env, v).await,
+            SubCommand::Propose(v) => propose::exec(env, v).await,
             SubCommand::RequestStatus(v) => request_status::exec(env, v).await,
+            SubCommand::Review(v) => review::exec(env, v).await,
             SubCommand::Send(v) => send::exec(env, v, &call_sender()?).await,
             SubCommand::SetId(v) => set_id::exec(env, v).await,
             SubCommand::Sign(v) => sign::exec(env, v, &call_sender()?).await,
@@ -0,0 +1,141 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::canister::get_canister_id_and_candid_path;
+use crate::lib::sign::approval_bundle::{ApprovalBundleV1, ProposedCall, SubmitMode};
+use crate::util::assets::management_idl;
+use crate::util::clap::argument_from_cli::ArgumentFromCliPositionalOpt;
+use crate::util::{blob_from_arguments, get_candid_type};
+use anyhow::{bail, Context};
+use candid::Principal;
+use candid_parser::utils::CandidSource;
+use clap::Parser;
+use slog::{info, warn};
+use std::path::PathBuf;
+
+/// Propose a canister call that several identities must approve before it is sent.
+/// The proposal is written to a file, which approvers review with `dfx canister review`
+/// and sign with `dfx canister approve`.
+#[derive(Parser)]
+pub struct CanisterProposeOpts {
+    /// Specifies the name/id of the canister to call.
+    canister_name: String,
+
+    /// Specifies the method name to call on the canister.
+    method_name: String,
+
+    #[command(flatten)]
+    argument_from_cli: ArgumentFromCliPositionalOpt,
+
+    /// The number of approvals needed to send the call.
+    #[arg(long)]
+    threshold: usize,
+
+    /// A principal that may approve the call. Can be specified more than once.
+    /// Anyone may approve the call if no approver is specified.
+    #[arg(long = "approver")]
+    approvers: Vec<Principal>,
+
+    /// Send only the first approval instead of every one. The threshold is then only checked
+    /// by dfx, and any single approval can be sent directly without the others.
+    #[arg(long)]
+    unsafe_submit_first: bool,
+
+    /// Describes the call to the approvers.
+    #[arg(long)]
+    description: Option<String>,
+
+    /// The Candid interface of the canister, to encode the argument and to show it decoded to the approvers.
+    /// Defaults to the interface of the canister in the project.
+    #[arg(long)]
+    candid: Option<PathBuf>,
+
+    /// Specifies the output file name.
+    #[arg(long, default_value = "proposal.json")]
+    file: PathBuf,
+}
+
+pub async fn exec(env: &dyn Environment, opts: CanisterProposeOpts) -> DfxResult {
+    let log = env.get_logger();
+    let method_name = opts.method_name.as_str();
+    if opts.file.exists() {
+        bail!(
+            "[{}] already exists, please specify a different output file name.",
+            opts.file.display(),
+        );
+    }
+
+    let (canister_id, maybe_candid_path) =
+        get_canister_id_and_candid_path(env, opts.canister_name.as_str())?;
+    let candid = if let Some(path) = &opts.candid {
+        Some(dfx_core::fs::read_to_string(path)?)
+    } else if canister_id == Principal::management_canister() {
+        Some(management_idl()?)
+    } else {
+        match maybe_candid_path {
+            Some(path) if path.exists() => Some(dfx_core::fs::read_to_string(&path)?),
+            _ => None,
+        }
+    };
+    let method_type = candid
+        .as_ref()
+        .and_then(|did| get_candid_type(CandidSource::Text(did), method_name));
+    if let Some((_, func)) = &method_type {
+        if func.is_query() {
+            bail!(
+                "{} is a query method, approvals are only needed for update calls.",
+                method_name
+            );
+        }
+    }
+
+    let (argument_from_cli, argument_type) = opts.argument_from_cli.get_argument_and_type()?;
+    let arg_value = blob_from_arguments(
+        Some(env),
+        argument_from_cli.as_deref(),
+        None,
+        argument_type.as_deref(),
+        &method_type,
+        false,
+        false,
+    )
+    .context("Failed to encode the argument of the proposed call.")?;
+
+    let network_descriptor = env.get_network_descriptor();
+    let network = network_descriptor
+        .providers
+        .first()
+        .expect("Cannot get network provider (url).")
+        .to_string();
+    let proposal = ProposedCall {
+        description: opts
+            .description
+            .unwrap_or_else(|| format!("update call to {canister_id}.{method_name}")),
+        network,
+        is_ic: network_descriptor.is_ic,
+        canister_id: canister_id.to_text(),
+        method_name: method_name.to_string(),
+        arg: arg_value,
+        candid,
+    };
+    let submit = if opts.unsafe_submit_first {
+        warn!(
+            log,
+            "Only the first approval will be sent. The threshold is not enforced: any approver can send their approval on its own."
+        );
+        SubmitMode::UnsafeFirst
+    } else {
+        SubmitMode::All
+    };
+    let approvers = opts.approvers.iter().map(Principal::to_text).collect();
+    let bundle = ApprovalBundleV1::new(proposal, opts.threshold, approvers, submit);
+    bundle.validate()?;
+    bundle.save(&opts.file)?;
+    info!(
+        log,
+        "Proposal written to [{}]. It needs {} approvals with `dfx canister approve {}`.",
+        opts.file.display(),
+        bundle.threshold,
+        opts.file.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::sign::approval_bundle::{ApprovalBundleV1, SubmitMode};
+use crate::util::get_candid_type;
+use candid_parser::utils::CandidSource;
+use clap::Parser;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+/// Show a proposed canister call with its decoded arguments and its approvals.
+#[derive(Parser)]
+pub struct CanisterReviewOpts {
+    /// Specifies the file name of the proposal.
+    file_name: PathBuf,
+
+    /// Decode the arguments with this Candid interface instead of the one in the proposal.
+    #[arg(long)]
+    candid: Option<PathBuf>,
+}
+
+pub async fn exec(_env: &dyn Environment, opts: CanisterReviewOpts) -> DfxResult {
+    let bundle = ApprovalBundleV1::load(&opts.file_name)?;
+    let proposal = &bundle.proposal;
+
+    let method_type = match &opts.candid {
+        Some(path) => get_candid_type(CandidSource::File(path), &proposal.method_name),
+        None => proposal
+            .candid
+            .as_ref()
+            .and_then(|did| get_candid_type(CandidSource::Text(did), &proposal.method_name)),
+    };
+    let args = bundle.decoded_args(&method_type)?;
+
+    println!("Description: {}", proposal.description);
+    println!("Network:     {}", proposal.network);
+    println!("Canister id: {}", proposal.canister_id);
+    println!("Method name: {}", proposal.method_name);
+    println!("Arguments:   {args}");
+    println!("Arg sha256:  {}", bundle.arg_hash());
+    if method_type.is_none() {
+        println!("             (decoded without the Candid interface, field names are hashed)");
+    }
+    if bundle.approvers.is_empty() {
+        println!("Threshold:   {} approvals by anyone", bundle.threshold);
+    } else {
+        println!(
+            "Threshold:   {} of {} approvers",
+            bundle.threshold,
+            bundle.approvers.len()
+        );
+        for approver in &bundle.approvers {
+            println!("  {approver}");
+        }
+    }
+    if bundle.submit == SubmitMode::UnsafeFirst {
+        println!("Submission:  only the first approval is sent, so the threshold is not enforced");
+    }
+
+    println!("Approvals:   {}", bundle.approvals.len());
+    let now = OffsetDateTime::now_utc();
+    for approval in &bundle.approvals {
+        let status = if approval.expiration < now {
+            "expired"
+        } else {
+            "expires"
+        };
+        println!(
+            "  {} ({status} at {})",
+            approval.sender, approval.expiration
+        );
+    }
+    if bundle.is_approved() {
+        println!(
+            "Ready to send with `dfx canister send {}`.",
+            opts.file_name.display()
+        );
+    } else {
+        println!(
+            "Needs {} more approvals.",
+            bundle.threshold - bundle.approvals.len()
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,8 @@
+use crate::commands::canister::call::get_effective_canister_id_of_call;
+use crate::commands::send::send_message;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::sign::approval_bundle::{ApprovalBundleV1, SubmitMode};
 use crate::lib::sign::signed_message::SignedMessageV1;
 use anyhow::{bail, Context};
 use candid::{IDLArgs, Principal};
@@ -9,12 +12,12 @@ use dfx_core::json::load_json_file;
 use ic_agent::agent::{CallResponse, RequestStatusResponse};
 use ic_agent::Agent;
 use ic_agent::RequestId;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// Send a previously-signed message.
+/// Send a previously-signed message, or a proposed call once it has enough approvals.
 #[derive(Parser)]
 pub struct CanisterSendOpts {
-    /// Specifies the file name of the message
+    /// Specifies the file name of the message or of the proposal
     file_name: PathBuf,
 
     /// Send the signed request-status call in the message
@@ -31,6 +34,12 @@ pub async fn exec(
         bail!("`send` currently doesn't support proxying through the wallet canister, please use `dfx canister send --no-wallet ...`.");
     }
     let file_name = opts.file_name;
+    if ApprovalBundleV1::is_approval_bundle(&file_name)? {
+        if opts.status {
+            bail!("--status is not supported for proposals, their result is printed when they are sent.");
+        }
+        return send_approved(&file_name).await;
+    }
     let message: SignedMessageV1 = load_json_file(&file_name)?;
     message.validate()?;
 
@@ -42,6 +51,8 @@ pub async fn exec(
     let content = hex::decode(&message.content).context("Failed to decode message content.")?;
     let canister_id = Principal::from_text(&message.canister_id)
         .with_context(|| format!("Failed to parse canister id {:?}.", message.canister_id))?;
+    let effective_canister_id =
+        get_effective_canister_id_of_call(canister_id, &message.method_name, &message.arg)?;
 
     if opts.status {
         if message.call_type != "update" {
@@ -58,7 +69,7 @@ pub async fn exec(
             .parse::<RequestId>()
             .context("Failed to decode request ID.")?;
         let (response, _cert) = agent
-            .request_status_signed(&request_id, canister_id, envelope)
+            .request_status_signed(&request_id, effective_canister_id, envelope)
             .await
             .with_context(|| format!("Failed to read canister state of {}.", canister_id))?;
         eprint!("Response: ");
@@ -115,7 +126,7 @@ pub async fn exec(
     match message.call_type.as_str() {
         "query" => {
             let response = agent
-                .query_signed(canister_id, content)
+                .query_signed(effective_canister_id, content)
                 .await
                 .with_context(|| format!("Query call to {} failed.", canister_id))?;
             eprint!("Response: ");
@@ -127,7 +138,7 @@ pub async fn exec(
         }
         "update" => {
             let call_response = agent
-                .update_signed(canister_id, content)
+                .update_signed(effective_canister_id, content)
                 .await
                 .with_context(|| format!("Update call to {} failed.", canister_id))?;
             match call_response {
@@ -157,3 +168,63 @@ pub async fn exec(
     }
     Ok(())
 }
+
+/// Sends a proposed call that was approved by enough approvers.
+async fn send_approved(file_name: &Path) -> DfxResult {
+    let bundle = ApprovalBundleV1::load(file_name)?;
+    if !bundle.is_approved() {
+        bail!(
+            "[{}] has {} of the {} approvals it needs.",
+            file_name.display(),
+            bundle.approvals.len(),
+            bundle.threshold
+        );
+    }
+    // Loading the bundle verified the signatures of the approvals.
+    let proposal = &bundle.proposal;
+
+    eprintln!("Will send proposed call:");
+    eprintln!("  Description: {}", proposal.description);
+    eprintln!("  Network:     {}", proposal.network);
+    eprintln!("  Canister id: {}", proposal.canister_id);
+    eprintln!("  Method name: {}", proposal.method_name);
+    eprintln!("  Arg sha256:  {}", bundle.arg_hash());
+    eprintln!("  Approved by:");
+    for approval in &bundle.approvals {
+        eprintln!("    {}", approval.sender);
+    }
+    if bundle.submit == SubmitMode::UnsafeFirst {
+        eprintln!(
+            "  Only the first approval is sent, so the canister cannot enforce the threshold."
+        );
+    }
+
+    // Not using dialoguer because it doesn't support non terminal env like bats e2e
+    eprintln!("\nOkay? [y/N]");
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read stdin.")?;
+    if !["y", "yes"].contains(&input.to_lowercase().trim()) {
+        return Ok(());
+    }
+
+    let agent = Agent::builder().with_url(&proposal.network).build()?;
+    if !proposal.is_ic {
+        agent.fetch_root_key().await?;
+    }
+    let to_send = match bundle.submit {
+        SubmitMode::All => &bundle.approvals[..],
+        SubmitMode::UnsafeFirst => &bundle.approvals[..1],
+    };
+    for approval in to_send {
+        let reply = send_message(&agent, approval).await?;
+        eprint!("Response: ");
+        if let Ok(idl) = IDLArgs::from_bytes(&reply) {
+            println!("{idl}");
+        } else {
+            println!("{}", hex::encode(&reply));
+        }
+    }
+    Ok(())
+}
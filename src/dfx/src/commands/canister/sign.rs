@@ -1,21 +1,23 @@
-use crate::commands::canister::call::get_effective_canister_id;
+use crate::commands::canister::call::get_effective_canister_id_of_call;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::operations::canister::get_canister_id_and_candid_path;
-use crate::lib::sign::signed_message::SignedMessageV1;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::sign::approval_bundle::summarize;
+use crate::lib::sign::signed_message::{verify_envelope, SignedMessageV1};
+use crate::util::assets::management_idl;
 use crate::util::clap::argument_from_cli::ArgumentFromCliPositionalOpt;
-use crate::util::{blob_from_arguments, get_candid_type};
-use anyhow::{anyhow, bail};
-use candid::Principal;
+use crate::util::{blob_from_arguments, fetch_remote_did_file, get_candid_type};
+use anyhow::{anyhow, bail, Context};
+use candid::{IDLArgs, Principal};
 use candid_parser::utils::CandidSource;
 use clap::Parser;
 use dfx_core::identity::CallSender;
-use dfx_core::json::save_json_file;
-use ic_utils::interfaces::management_canister::MgmtMethod;
+use dfx_core::json::{load_json_file, save_json_file};
+use ic_agent::agent::EnvelopeContent;
 use slog::info;
 use std::convert::TryInto;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
 use std::time::SystemTime;
 use time::OffsetDateTime;
 
@@ -23,10 +25,12 @@ use time::OffsetDateTime;
 #[derive(Parser)]
 pub struct CanisterSignOpts {
     /// Specifies the name/id of the canister to call.
-    canister_name: String,
+    #[arg(required_unless_present("inspect"))]
+    canister_name: Option<String>,
 
     /// Specifies the method name to call on the canister.
-    method_name: String,
+    #[arg(required_unless_present("inspect"))]
+    method_name: Option<String>,
 
     #[command(flatten)]
     argument_from_cli: ArgumentFromCliPositionalOpt,
@@ -64,6 +68,10 @@ pub struct CanisterSignOpts {
         conflicts_with("random")
     )]
     always_assist: bool,
+
+    /// Instead of signing a call, show what the signed message in this file does and check its signature.
+    #[arg(long, conflicts_with("canister_name"))]
+    inspect: Option<PathBuf>,
 }
 
 pub async fn exec(
@@ -76,10 +84,13 @@ pub async fn exec(
         bail!("`sign` currently doesn't support proxying through the wallet canister, please use `dfx canister sign --no-wallet ...`.");
     }
 
-    let method_name = opts.method_name.as_str();
+    if let Some(file_name) = &opts.inspect {
+        return inspect(env, file_name).await;
+    }
+    let canister_name = opts.canister_name.as_deref().unwrap();
+    let method_name = opts.method_name.as_deref().unwrap();
 
-    let (canister_id, maybe_candid_path) =
-        get_canister_id_and_candid_path(env, opts.canister_name.as_str())?;
+    let (canister_id, maybe_candid_path) = get_canister_id_and_candid_path(env, canister_name)?;
 
     let method_type =
         maybe_candid_path.and_then(|path| get_candid_type(CandidSource::File(&path), method_name));
@@ -155,17 +166,8 @@ pub async fn exec(
         );
     }
 
-    let effective_canister_id = if canister_id == Principal::management_canister() {
-        let management_method = MgmtMethod::from_str(method_name).map_err(|_| {
-            anyhow!(
-                "Attempted to call an unsupported management canister method: {}",
-                method_name
-            )
-        })?;
-        get_effective_canister_id(&management_method, &arg_value)?
-    } else {
-        canister_id
-    };
+    let effective_canister_id =
+        get_effective_canister_id_of_call(canister_id, method_name, &arg_value)?;
 
     if is_query {
         let signed_query = agent
@@ -206,3 +208,119 @@ pub async fn exec(
         Ok(())
     }
 }
+
+/// Shows what a signed message does and checks its signature.
+/// Everything shown is decoded from the signed envelope, not from the other fields of the file.
+async fn inspect(env: &dyn Environment, file_name: &Path) -> DfxResult {
+    let message: SignedMessageV1 = load_json_file(file_name)?;
+    let envelope = message.envelope()?;
+    let (call_type, sender, canister_id, method_name, arg, ingress_expiry) =
+        match envelope.content.as_ref() {
+            EnvelopeContent::Call {
+                sender,
+                canister_id,
+                method_name,
+                arg,
+                ingress_expiry,
+                ..
+            } => (
+                "update",
+                sender,
+                canister_id,
+                method_name,
+                arg,
+                ingress_expiry,
+            ),
+            EnvelopeContent::Query {
+                sender,
+                canister_id,
+                method_name,
+                arg,
+                ingress_expiry,
+                ..
+            } => (
+                "query",
+                sender,
+                canister_id,
+                method_name,
+                arg,
+                ingress_expiry,
+            ),
+            EnvelopeContent::ReadState { .. } => {
+                bail!("[{}] does not hold a canister call.", file_name.display())
+            }
+        };
+
+    let canister_name = env
+        .get_canister_id_store()
+        .ok()
+        .and_then(|store| store.get_name(&canister_id.to_text()));
+    let method_type = if *canister_id == Principal::management_canister() {
+        get_candid_type(CandidSource::Text(&management_idl()?), method_name)
+    } else {
+        match get_canister_id_and_candid_path(env, &canister_id.to_text()) {
+            Ok((_, Some(path))) if path.exists() => {
+                get_candid_type(CandidSource::File(&path), method_name)
+            }
+            // The interface is fetched from the canister if the network can be reached.
+            _ if fetch_root_key_if_needed(env).await.is_ok() => {
+                fetch_remote_did_file(env.get_agent(), *canister_id)
+                    .await
+                    .and_then(|did| get_candid_type(CandidSource::Text(&did), method_name))
+            }
+            _ => None,
+        }
+    };
+    let args = match &method_type {
+        Some((type_env, func)) => IDLArgs::from_bytes_with_types(arg, type_env, &func.args),
+        None => IDLArgs::from_bytes(arg),
+    }
+    .context("Failed to decode the arguments of the call.")?;
+    let args = IDLArgs::new(&args.args.into_iter().map(summarize).collect::<Vec<_>>());
+
+    let expiry = OffsetDateTime::from_unix_timestamp_nanos(i128::from(*ingress_expiry))
+        .context("Invalid ingress expiry.")?;
+    let expired = if expiry < OffsetDateTime::now_utc() {
+        " (expired)"
+    } else {
+        ""
+    };
+
+    println!("Network:        {}", message.network);
+    println!("Call type:      {call_type}");
+    println!("Sender:         {sender}");
+    match canister_name {
+        Some(name) => println!("Canister:       {name} ({canister_id})"),
+        None => println!("Canister:       {canister_id}"),
+    }
+    println!("Method name:    {method_name}");
+    println!("Arguments:      {args}");
+    if method_type.is_none() {
+        println!("                (decoded without the Candid interface, field names are hashed)");
+    }
+    println!("Ingress expiry: {expiry}{expired}");
+    if call_type == "update" {
+        println!(
+            "Request id:     0x{}",
+            String::from(envelope.content.to_request_id())
+        );
+    }
+    let signature = verify_envelope(&envelope);
+    match &signature {
+        Ok(key_type) => println!("Signature:      valid ({key_type})"),
+        Err(_) => println!("Signature:      invalid"),
+    }
+    if message.sender != sender.to_text()
+        || message.canister_id != canister_id.to_text()
+        || message.method_name != *method_name
+        || message.arg != *arg
+    {
+        bail!(
+            "The fields of [{}] do not match the signed message.",
+            file_name.display()
+        );
+    }
+    signature
+        .map(|_| ())
+        .with_context(|| format!("The signature in [{}] is not valid.", file_name.display()))
+}
@@ -5,6 +5,7 @@ use std::{
 
 use anyhow::{bail, Context};
 use clap::{Parser, Subcommand};
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::identity::CallSender;
 use ic_utils::interfaces::management_canister::CanisterStatus;
 use indicatif::HumanBytes;
@@ -17,7 +18,7 @@ use crate::lib::{
     error::{DfxError, DfxResult},
     operations::canister::{
         delete_canister_snapshot, get_canister_status, list_canister_snapshots,
-        load_canister_snapshot, take_canister_snapshot,
+        load_canister_snapshot, selected_project_canisters, take_canister_snapshot,
     },
     root_key::fetch_root_key_if_needed,
 };
@@ -34,10 +35,17 @@ enum SnapshotSubcommand {
     /// Creates a new snapshot of a canister. The canister must be stopped.
     Create {
         /// The canister to snapshot.
-        canister: String,
+        /// A glob pattern such as `svc_*` selects several canisters.
+        canister: Option<String>,
         /// If a snapshot ID is specified, this snapshot will replace it and reuse the ID.
-        #[arg(long)]
+        #[arg(long, conflicts_with_all(["all", "tag"]))]
         replace: Option<SnapshotId>,
+        /// Snapshots all of the canisters configured in the dfx.json file.
+        #[arg(long, required_unless_present_any(["canister", "tag"]))]
+        all: bool,
+        /// Only snapshot the canisters with this tag in dfx.json. Can be specified more than once.
+        #[arg(long, conflicts_with("all"))]
+        tag: Vec<String>,
     },
     /// Loads a canister snapshot, overwriting its execution state. All data since that snapshot will be lost. The canister must be stopped.
     Load {
@@ -49,7 +57,14 @@ enum SnapshotSubcommand {
     /// Lists a canister's existing snapshots.
     List {
         /// The canister to list snapshots from.
-        canister: String,
+        /// A glob pattern such as `svc_*` selects several canisters.
+        canister: Option<String>,
+        /// Lists the snapshots of all of the canisters configured in the dfx.json file.
+        #[arg(long, required_unless_present_any(["canister", "tag"]))]
+        all: bool,
+        /// Only list the snapshots of the canisters with this tag in dfx.json. Can be specified more than once.
+        #[arg(long, conflicts_with("all"))]
+        tag: Vec<String>,
     },
     /// Deletes a snapshot from a canister.
     Delete {
@@ -83,8 +98,20 @@ pub async fn exec(
 ) -> DfxResult {
     fetch_root_key_if_needed(env).await?;
     match opts.subcmd {
-        SnapshotSubcommand::Create { canister, replace } => {
-            create(env, canister, replace, call_sender).await?
+        SnapshotSubcommand::Create {
+            canister,
+            replace,
+            all: _,
+            tag,
+        } => {
+            let selector = CanisterSelector::new(canister.as_deref(), &tag);
+            if let Some(canister) = selector.single() {
+                create(env, canister.to_string(), replace, call_sender).await?
+            } else {
+                for canister in selected_project_canisters(env, &selector)? {
+                    create(env, canister, None, call_sender).await?
+                }
+            }
         }
         SnapshotSubcommand::Load { canister, snapshot } => {
             load(env, canister, snapshot, call_sender).await?
@@ -92,7 +119,21 @@ pub async fn exec(
         SnapshotSubcommand::Delete { canister, snapshot } => {
             delete(env, canister, snapshot, call_sender).await?
         }
-        SnapshotSubcommand::List { canister } => list(env, canister, call_sender).await?,
+        SnapshotSubcommand::List {
+            canister,
+            all: _,
+            tag,
+        } => {
+            let selector = CanisterSelector::new(canister.as_deref(), &tag);
+            if let Some(canister) = selector.single() {
+                list(env, canister.to_string(), call_sender).await?
+            } else {
+                for canister in selected_project_canisters(env, &selector)? {
+                    info!(env.get_logger(), "Canister {canister}:");
+                    list(env, canister, call_sender).await?
+                }
+            }
+        }
     }
     Ok(())
 }
@@ -1,10 +1,11 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::operations::canister;
-use crate::lib::operations::canister::skip_remote_canister;
+use crate::lib::operations::canister::selected_project_canisters;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use candid::Principal;
 use clap::Parser;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::identity::CallSender;
 use slog::info;
 
@@ -16,8 +17,12 @@ pub struct CanisterStopOpts {
     canister: Option<String>,
 
     /// Stops all of the canisters configured in the dfx.json file.
-    #[arg(long, required_unless_present("canister"))]
+    #[arg(long, required_unless_present_any(["canister", "tag"]))]
     all: bool,
+
+    /// Only stop the canisters with this tag in dfx.json. Can be specified more than once.
+    #[arg(long, conflicts_with("all"))]
+    tag: Vec<String>,
 }
 
 async fn stop_canister(
@@ -49,22 +54,13 @@ pub async fn exec(
 ) -> DfxResult {
     fetch_root_key_if_needed(env).await?;
 
-    if let Some(canister) = opts.canister.as_deref() {
+    let selector = CanisterSelector::new(opts.canister.as_deref(), &opts.tag);
+    if let Some(canister) = selector.single() {
         stop_canister(env, canister, call_sender).await
-    } else if opts.all {
-        let config = env.get_config_or_anyhow()?;
-
-        if let Some(canisters) = &config.get_config().canisters {
-            for canister in canisters.keys() {
-                if skip_remote_canister(env, canister)? {
-                    continue;
-                }
-
-                stop_canister(env, canister, call_sender).await?;
-            }
+    } else {
+        for canister in selected_project_canisters(env, &selector)? {
+            stop_canister(env, &canister, call_sender).await?;
         }
         Ok(())
-    } else {
-        unreachable!()
     }
 }
@@ -7,7 +7,7 @@ use crate::lib::ic_attributes::{
     get_reserved_cycles_limit, get_wasm_memory_limit, get_wasm_memory_threshold, CanisterSettings,
 };
 use crate::lib::operations::canister::{
-    get_canister_status, skip_remote_canister, update_settings,
+    get_canister_status, selected_project_canisters, update_settings,
 };
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::ask_for_consent;
@@ -15,11 +15,12 @@ use crate::util::clap::parsers::{
     compute_allocation_parser, freezing_threshold_parser, memory_allocation_parser,
     reserved_cycles_limit_parser, wasm_memory_limit_parser,
 };
-use anyhow::{bail, Context};
+use anyhow::Context;
 use byte_unit::Byte;
 use candid::Principal as CanisterId;
 use candid::Principal;
 use clap::{ArgAction, Parser};
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::error::identity::InstantiateIdentityFromNameError::GetIdentityPrincipalFailed;
 use dfx_core::identity::CallSender;
 use fn_error_context::context;
@@ -33,9 +34,13 @@ pub struct UpdateSettingsOpts {
     canister: Option<String>,
 
     /// Updates the settings of all canisters configured in the project dfx.json files.
-    #[arg(long, required_unless_present("canister"))]
+    #[arg(long, required_unless_present_any(["canister", "tag"]))]
     all: bool,
 
+    /// Only update the canisters with this tag in dfx.json. Can be specified more than once.
+    #[arg(long, conflicts_with("all"))]
+    tag: Vec<String>,
+
     /// Specifies the identity name or the principal of the new controller.
     /// Can be specified more than once, indicating the canister will have multiple controllers.
     /// If any controllers are set with this parameter, any other controllers will be removed.
@@ -162,7 +167,8 @@ pub async fn exec(
 
     let canister_id_store = env.get_canister_id_store()?;
 
-    if let Some(canister_name_or_id) = opts.canister.as_deref() {
+    let selector = CanisterSelector::new(opts.canister.as_deref(), &opts.tag);
+    if let Some(canister_name_or_id) = selector.single() {
         let config = env.get_config()?;
         let config_interface = config.as_ref().map(|config| config.get_config());
         let mut controllers = controllers;
@@ -245,130 +251,115 @@ pub async fn exec(
         };
         update_settings(env, canister_id, settings, call_sender).await?;
         display_controller_update(&opts, canister_name_or_id);
-    } else if opts.all {
-        // Update all canister settings.
+    } else {
+        // Update the settings of the selected canisters.
         let config = env.get_config_or_anyhow()?;
         let config_interface = config.get_config();
 
-        if let Some(canisters) = &config_interface.canisters {
-            for canister_name in canisters.keys() {
-                if skip_remote_canister(env, canister_name)? {
-                    continue;
+        for canister_name in &selected_project_canisters(env, &selector)? {
+            let mut controllers = controllers.clone();
+            let canister_id = canister_id_store.get(canister_name)?;
+            let compute_allocation = get_compute_allocation(
+                opts.compute_allocation,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get compute allocation for {canister_name}."))?;
+            let memory_allocation = get_memory_allocation(
+                opts.memory_allocation,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get memory allocation for {canister_name}."))?;
+            let freezing_threshold = get_freezing_threshold(
+                opts.freezing_threshold,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get freezing threshold for {canister_name}."))?;
+            let reserved_cycles_limit = get_reserved_cycles_limit(
+                opts.reserved_cycles_limit,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get reserved cycles limit for {canister_name}."))?;
+            let wasm_memory_limit = get_wasm_memory_limit(
+                opts.wasm_memory_limit,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get Wasm memory limit for {canister_name}."))?;
+            let wasm_memory_threshold = get_wasm_memory_threshold(
+                opts.wasm_memory_threshold,
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get Wasm memory threshold for {canister_name}."))?;
+            let mut current_status: Option<StatusCallResult> = None;
+            if let Some(log_visibility) = &opts.log_visibility_opt {
+                if log_visibility.require_current_settings() {
+                    current_status =
+                        Some(get_canister_status(env, canister_id, call_sender).await?);
                 }
-                let mut controllers = controllers.clone();
-                let canister_id = canister_id_store.get(canister_name)?;
-                let compute_allocation = get_compute_allocation(
-                    opts.compute_allocation,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| {
-                    format!("Failed to get compute allocation for {canister_name}.")
-                })?;
-                let memory_allocation = get_memory_allocation(
-                    opts.memory_allocation,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| format!("Failed to get memory allocation for {canister_name}."))?;
-                let freezing_threshold = get_freezing_threshold(
-                    opts.freezing_threshold,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| {
-                    format!("Failed to get freezing threshold for {canister_name}.")
-                })?;
-                let reserved_cycles_limit = get_reserved_cycles_limit(
-                    opts.reserved_cycles_limit,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| {
-                    format!("Failed to get reserved cycles limit for {canister_name}.")
-                })?;
-                let wasm_memory_limit = get_wasm_memory_limit(
-                    opts.wasm_memory_limit,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| format!("Failed to get Wasm memory limit for {canister_name}."))?;
-                let wasm_memory_threshold = get_wasm_memory_threshold(
-                    opts.wasm_memory_threshold,
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| {
-                    format!("Failed to get Wasm memory threshold for {canister_name}.")
-                })?;
-                let mut current_status: Option<StatusCallResult> = None;
-                if let Some(log_visibility) = &opts.log_visibility_opt {
-                    if log_visibility.require_current_settings() {
-                        current_status =
-                            Some(get_canister_status(env, canister_id, call_sender).await?);
-                    }
+            }
+            let log_visibility = get_log_visibility(
+                env,
+                opts.log_visibility_opt.as_ref(),
+                current_status.as_ref(),
+                Some(config_interface),
+                Some(canister_name),
+            )
+            .with_context(|| format!("Failed to get log visibility for {canister_name}."))?;
+            if let Some(added) = &opts.add_controller {
+                if current_status.is_none() {
+                    current_status =
+                        Some(get_canister_status(env, canister_id, call_sender).await?);
+                }
+                let mut existing_controllers = current_status
+                    .as_ref()
+                    .unwrap()
+                    .settings
+                    .controllers
+                    .clone();
+                for s in added {
+                    existing_controllers.push(controller_to_principal(env, s)?);
                 }
-                let log_visibility = get_log_visibility(
-                    env,
-                    opts.log_visibility_opt.as_ref(),
-                    current_status.as_ref(),
-                    Some(config_interface),
-                    Some(canister_name),
-                )
-                .with_context(|| format!("Failed to get log visibility for {canister_name}."))?;
-                if let Some(added) = &opts.add_controller {
+                controllers = Some(existing_controllers);
+            }
+            if let Some(removed) = &opts.remove_controller {
+                let controllers = if opts.add_controller.is_some() {
+                    controllers.as_mut().unwrap()
+                } else {
                     if current_status.is_none() {
                         current_status =
                             Some(get_canister_status(env, canister_id, call_sender).await?);
                     }
-                    let mut existing_controllers = current_status
-                        .as_ref()
-                        .unwrap()
-                        .settings
-                        .controllers
-                        .clone();
-                    for s in added {
-                        existing_controllers.push(controller_to_principal(env, s)?);
-                    }
-                    controllers = Some(existing_controllers);
-                }
-                if let Some(removed) = &opts.remove_controller {
-                    let controllers = if opts.add_controller.is_some() {
-                        controllers.as_mut().unwrap()
-                    } else {
-                        if current_status.is_none() {
-                            current_status =
-                                Some(get_canister_status(env, canister_id, call_sender).await?);
-                        }
-                        controllers.get_or_insert(current_status.unwrap().settings.controllers)
-                    };
-                    let removed = removed
-                        .iter()
-                        .map(|r| controller_to_principal(env, r))
-                        .collect::<DfxResult<Vec<_>>>()
-                        .context("Failed to determine all controllers to remove.")?;
-                    for s in removed {
-                        if let Some(idx) = controllers.iter().position(|x| *x == s) {
-                            controllers.swap_remove(idx);
-                        }
+                    controllers.get_or_insert(current_status.unwrap().settings.controllers)
+                };
+                let removed = removed
+                    .iter()
+                    .map(|r| controller_to_principal(env, r))
+                    .collect::<DfxResult<Vec<_>>>()
+                    .context("Failed to determine all controllers to remove.")?;
+                for s in removed {
+                    if let Some(idx) = controllers.iter().position(|x| *x == s) {
+                        controllers.swap_remove(idx);
                     }
                 }
-                let settings = CanisterSettings {
-                    controllers,
-                    compute_allocation,
-                    memory_allocation,
-                    freezing_threshold,
-                    reserved_cycles_limit,
-                    wasm_memory_limit,
-                    wasm_memory_threshold,
-                    log_visibility,
-                };
-                update_settings(env, canister_id, settings, call_sender).await?;
-                display_controller_update(&opts, canister_name);
             }
+            let settings = CanisterSettings {
+                controllers,
+                compute_allocation,
+                memory_allocation,
+                freezing_threshold,
+                reserved_cycles_limit,
+                wasm_memory_limit,
+                wasm_memory_threshold,
+                log_visibility,
+            };
+            update_settings(env, canister_id, settings, call_sender).await?;
+            display_controller_update(&opts, canister_name);
         }
-    } else {
-        bail!("Cannot find canister name.")
     }
 
     Ok(())
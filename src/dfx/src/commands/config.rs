@@ -1,10 +1,21 @@
-use clap::{Parser, Subcommand};
-use dfx_core::config::model::dfinity::{TelemetryState, ToolConfigInterface};
+use anyhow::{anyhow, bail, Context};
+use clap::{Args, Parser, Subcommand};
+use dfx_core::config::model::dfinity::{Config, TelemetryState, ToolConfigInterface};
+use dfx_core::json::key_order::KeyOrder;
+use dfx_core::json::path::JsonPath;
+use dfx_core::json::{save_json_file_with_format, JsonFormat};
+use serde_json::Value;
 use slog::{info, warn};
+use std::collections::BTreeSet;
+use std::path::Path;
 
+use crate::lib::config_lint::{
+    dfx_json_schema_violations, lint_dfx_json, lint_networks_json, networks_json_schema_violations,
+    Severity,
+};
 use crate::lib::{environment::Environment, error::DfxResult};
 
-/// Changes settings in dfx's configuration.
+/// Changes settings in dfx's configuration, or edits dfx.json.
 #[derive(Parser)]
 #[command(arg_required_else_help = true)]
 pub struct ConfigOpts {
@@ -18,6 +29,68 @@ enum ConfigOption {
     ///
     /// `local` collects telemetry but does not store it.
     Telemetry { telemetry: Option<TelemetryState> },
+
+    /// Prints the value at a path in dfx.json.
+    ///
+    /// Strings are printed as-is; all other values are printed as JSON.
+    Get {
+        /// A dot-separated path (`canisters.backend.main`) or a JSON pointer (`/canisters/backend/main`).
+        path: JsonPath,
+
+        #[command(flatten)]
+        file: ConfigFileOpts,
+    },
+
+    /// Sets the value at a path in dfx.json.
+    ///
+    /// The file is only written if the result is still a valid configuration.
+    Set {
+        /// A dot-separated path (`canisters.backend.main`) or a JSON pointer (`/canisters/backend/main`).
+        path: JsonPath,
+
+        /// The new value. Parsed as JSON if possible, otherwise stored as a string.
+        value: String,
+
+        /// Always store the value as a string, even if it is valid JSON.
+        #[arg(long)]
+        string: bool,
+
+        #[command(flatten)]
+        file: ConfigFileOpts,
+    },
+
+    /// Removes the value at a path in dfx.json.
+    ///
+    /// The file is only written if the result is still a valid configuration.
+    Unset {
+        /// A dot-separated path (`canisters.backend.main`) or a JSON pointer (`/canisters/backend/main`).
+        path: JsonPath,
+
+        #[command(flatten)]
+        file: ConfigFileOpts,
+    },
+
+    /// Checks dfx.json for errors and likely mistakes.
+    ///
+    /// Besides checking the file against the schema printed by `dfx schema`, this reports
+    /// unknown or circular dependencies, missing source files, duplicate specified ids,
+    /// remote ids for unknown networks, deprecated fields, and canister types
+    /// that no installed extension provides.
+    Validate {
+        /// Print the diagnostics as JSON, for use by editors and other tools.
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        file: ConfigFileOpts,
+    },
+}
+
+#[derive(Args)]
+struct ConfigFileOpts {
+    /// Operate on the shared networks.json instead of the project's dfx.json.
+    #[arg(long)]
+    networks: bool,
 }
 
 pub fn exec(env: &dyn Environment, opts: ConfigOpts) -> DfxResult {
@@ -33,6 +106,37 @@ pub fn exec(env: &dyn Environment, opts: ConfigOpts) -> DfxResult {
                 println!("{}", env.telemetry_mode());
             }
         }
+        ConfigOption::Get { path, file } => {
+            let json = read_json(env, &file)?;
+            let value = path
+                .get(&json)
+                .ok_or_else(|| anyhow!("No value at '{path}'."))?;
+            match value {
+                Value::String(s) => println!("{s}"),
+                _ => println!("{}", serde_json::to_string_pretty(value)?),
+            }
+        }
+        ConfigOption::Set {
+            path,
+            value,
+            string,
+            file,
+        } => {
+            let value = if string {
+                Value::String(value)
+            } else {
+                serde_json::from_str(&value).unwrap_or(Value::String(value))
+            };
+            edit_json(env, &file, |json| Ok(path.set(json, value)?))?;
+        }
+        ConfigOption::Unset { path, file } => {
+            edit_json(env, &file, |json| {
+                path.unset(json)?
+                    .map(|_| ())
+                    .ok_or_else(|| anyhow!("No value at '{path}'."))
+            })?;
+        }
+        ConfigOption::Validate { json, file } => validate(env, json, &file)?,
     }
     Ok(())
 }
@@ -48,3 +152,134 @@ fn update_config<T>(
     cfg.save()?;
     Ok(res)
 }
+
+fn read_json(env: &dyn Environment, file: &ConfigFileOpts) -> DfxResult<Value> {
+    if file.networks {
+        Ok(env.get_networks_config().get_json().clone())
+    } else {
+        Ok(env.get_config_or_anyhow()?.get_json().clone())
+    }
+}
+
+/// Applies `f` to the JSON of dfx.json (or networks.json),
+/// and saves the file only if the result is still a valid configuration.
+///
+/// The result is checked against the JSON schema of the file, which also rejects unknown fields.
+/// Only problems introduced by the change are reported.
+/// The file keeps its key order and indentation.
+fn edit_json(
+    env: &dyn Environment,
+    file: &ConfigFileOpts,
+    f: impl FnOnce(&mut Value) -> DfxResult,
+) -> DfxResult {
+    let schema_violations = |json: &Value| {
+        if file.networks {
+            networks_json_schema_violations(json)
+        } else {
+            dfx_json_schema_violations(json, env.get_extension_manager())
+        }
+    };
+    let mut json = read_json(env, file)?;
+    let existing_violations = schema_violations(&json);
+    f(&mut json)?;
+    let new_violations: Vec<String> = schema_violations(&json)
+        .into_iter()
+        .filter(|violation| !existing_violations.contains(violation))
+        .map(|violation| format!("  {}: {}", violation.pointer, violation.message))
+        .collect();
+    if !new_violations.is_empty() {
+        bail!(
+            "The change would make {} invalid.\n{}",
+            if file.networks {
+                "networks.json"
+            } else {
+                "dfx.json"
+            },
+            new_violations.join("\n")
+        );
+    }
+    let path = if file.networks {
+        let mut networks_config = env.get_networks_config().as_ref().clone();
+        networks_config
+            .update_json(json.clone())
+            .context("The change would make networks.json invalid.")?;
+        networks_config.get_path().clone()
+    } else {
+        let mut config = env.get_config_or_anyhow()?.as_ref().clone();
+        config
+            .update_json(json.clone(), Some(env.get_extension_manager()))
+            .context("The change would make dfx.json invalid.")?;
+        config.get_path().clone()
+    };
+    save_json_in_original_order(&path, &json)
+}
+
+/// Writes an edited JSON file with the key order and indentation it had before the edit.
+fn save_json_in_original_order(path: &Path, json: &Value) -> DfxResult {
+    let (format, order) = if path.exists() {
+        let content = dfx_core::fs::read(path)?;
+        let order: KeyOrder = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}.", path.display()))?;
+        (JsonFormat::detect(&content), order)
+    } else {
+        (JsonFormat::default(), KeyOrder::default())
+    };
+    save_json_file_with_format(path, &order.apply(json), &format)?;
+    Ok(())
+}
+
+fn validate(env: &dyn Environment, json: bool, file: &ConfigFileOpts) -> DfxResult {
+    let networks_config = env.get_networks_config();
+    let (path, diagnostics) = if file.networks {
+        let path = networks_config.get_path().clone();
+        if !path.exists() {
+            bail!("{} does not exist.", path.display());
+        }
+        let content = dfx_core::fs::read_to_string(&path)?;
+        let diagnostics = lint_networks_json(&path, &content);
+        (path, diagnostics)
+    } else {
+        // Don't load the config through the environment: it may not be valid.
+        let path = Config::path_from_current_dir()?.ok_or_else(|| anyhow!(
+            "Cannot find dfx configuration file in the current working directory. Did you forget to create one?"
+        ))?;
+        let content = dfx_core::fs::read_to_string(&path)?;
+        let known_networks: BTreeSet<String> = networks_config
+            .get_interface()
+            .networks
+            .keys()
+            .cloned()
+            .collect();
+        let diagnostics = lint_dfx_json(
+            &path,
+            &content,
+            env.get_extension_manager(),
+            &known_networks,
+        );
+        (path, diagnostics)
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+    } else {
+        for diagnostic in &diagnostics {
+            println!("{diagnostic}");
+        }
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        bail!("Found {errors} error(s) in {}.", path.display());
+    }
+    if !json {
+        info!(
+            env.get_logger(),
+            "{} is valid ({} warning(s)).",
+            path.display(),
+            diagnostics.len()
+        );
+    }
+    Ok(())
+}
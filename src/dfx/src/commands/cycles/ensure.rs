@@ -0,0 +1,250 @@
+use crate::commands::ledger::top_up::MEMO_TOP_UP_CANISTER;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::identity::wallet::get_or_create_wallet_canister;
+use crate::lib::ledger_types::Memo;
+use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
+use crate::lib::operations::canister::{deposit_cycles, get_canister_status, skip_remote_canister};
+use crate::lib::operations::cmc::{notify_top_up, transfer_cmc};
+use crate::lib::operations::cycles_ledger;
+use crate::lib::operations::ledger::xdr_permyriad_per_icp;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::table;
+use crate::util::format_as_tc;
+use anyhow::{anyhow, bail, Context};
+use candid::Principal;
+use clap::Parser;
+use dfx_core::config::model::dfinity::{ConfigCanistersCanisterCycles, CyclesSource};
+use dfx_core::identity::CallSender;
+use num_traits::ToPrimitive;
+use slog::info;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tops up canisters whose cycles balance is below the minimum of their `cycles` policy in dfx.json.
+/// Each canister is topped up to the target of its policy, from the funding source of the policy.
+#[derive(Parser)]
+pub struct EnsureOpts {
+    /// Specifies the name of the canister to top up.
+    /// You must specify either a canister name or the --all option.
+    canister: Option<String>,
+
+    /// Top up all canisters of the project that have a cycles policy.
+    #[arg(long, required_unless_present("canister"))]
+    all: bool,
+
+    /// Print the balances and the top-ups that would be made, without making them.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Specify a wallet canister id to read the balances with, as with `dfx canister status`.
+    /// If none specified, the selected identity reads them.
+    #[arg(long)]
+    wallet: Option<String>,
+}
+
+/// The state of a canister with a cycles policy.
+struct CanisterCycles {
+    name: String,
+    canister_id: Principal,
+    policy: ConfigCanistersCanisterCycles,
+    balance: u128,
+}
+
+impl CanisterCycles {
+    /// The number of cycles needed to reach the target, if the balance is below the minimum.
+    fn deficit(&self) -> u128 {
+        if self.balance < self.policy.minimum {
+            self.policy.target.saturating_sub(self.balance)
+        } else {
+            0
+        }
+    }
+}
+
+pub async fn exec(env: &dyn Environment, opts: EnsureOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let config = env.get_config_or_anyhow()?;
+    let canisters = config.get_config().canisters.clone().unwrap_or_default();
+    let names: Vec<String> = match &opts.canister {
+        Some(canister) => vec![canister.clone()],
+        None => canisters
+            .iter()
+            .filter(|(_, canister)| canister.cycles.is_some())
+            .map(|(name, _)| name.clone())
+            .collect(),
+    };
+
+    let call_sender = CallSender::from(&opts.wallet, env.get_network_descriptor())
+        .map_err(|e| anyhow!("Failed to determine call sender: {}", e))?;
+    let mut wallet = None;
+    let mut states = vec![];
+    for name in names {
+        let policy = canisters
+            .get(&name)
+            .ok_or_else(|| anyhow!("Canister '{name}' is not defined in dfx.json."))?
+            .cycles
+            .clone()
+            .ok_or_else(|| anyhow!("Canister '{name}' has no cycles policy in dfx.json."))?;
+        if policy.target < policy.minimum {
+            bail!(
+                "The cycles policy of canister '{name}' has a target of {} below its minimum of {}.",
+                policy.target,
+                policy.minimum
+            );
+        }
+        if skip_remote_canister(env, &name)? {
+            continue;
+        }
+        let canister_id = env.get_canister_id_store()?.get(&name)?;
+        let status = get_canister_status(env, canister_id, &call_sender)
+            .await
+            .with_context(|| format!("Failed to get the cycles balance of canister '{name}'."))?;
+        states.push(CanisterCycles {
+            name,
+            canister_id,
+            policy,
+            balance: status.cycles.0.to_u128().unwrap_or(u128::MAX),
+        });
+    }
+
+    print_table(&states);
+
+    if opts.dry_run {
+        return Ok(());
+    }
+    for state in states.iter().filter(|state| state.deficit() > 0) {
+        top_up(env, state, &mut wallet)
+            .await
+            .with_context(|| format!("Failed to top up canister '{}'.", state.name))?;
+    }
+    Ok(())
+}
+
+async fn top_up(
+    env: &dyn Environment,
+    state: &CanisterCycles,
+    wallet: &mut Option<Principal>,
+) -> DfxResult {
+    let agent = env.get_agent();
+    let logger = env.get_logger();
+    let cycles = state.deficit();
+    match state.policy.source {
+        CyclesSource::CyclesLedger => {
+            let created_at_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64;
+            cycles_ledger::withdraw(
+                agent,
+                logger,
+                state.canister_id,
+                cycles,
+                created_at_time,
+                None,
+            )
+            .await?;
+        }
+        CyclesSource::Wallet => {
+            let wallet_id = wallet_id(env, wallet).await?;
+            deposit_cycles(
+                env,
+                state.canister_id,
+                &CallSender::Wallet(wallet_id),
+                cycles,
+            )
+            .await?;
+        }
+        CyclesSource::Icp => {
+            // One e8 of ICP is worth as many cycles as the rate has XDR permyriad per ICP.
+            let cycles_per_e8s = u128::from(xdr_permyriad_per_icp(agent).await?);
+            let e8s = u64::try_from(cycles.div_ceil(cycles_per_e8s))?;
+            let height = transfer_cmc(
+                agent,
+                logger,
+                Memo(MEMO_TOP_UP_CANISTER),
+                ICPTs::from_e8s(e8s),
+                TRANSACTION_FEE,
+                None,
+                state.canister_id,
+                None,
+            )
+            .await?;
+            notify_top_up(agent, state.canister_id, height).await?;
+        }
+    }
+    info!(
+        logger,
+        "Topped up canister '{}' with {} cycles from the {}.",
+        state.name,
+        cycles,
+        state.policy.source
+    );
+    Ok(())
+}
+
+/// The cycles wallet of the selected identity, looked up once.
+async fn wallet_id(env: &dyn Environment, wallet: &mut Option<Principal>) -> DfxResult<Principal> {
+    if let Some(wallet) = wallet {
+        return Ok(*wallet);
+    }
+    let identity = env
+        .get_selected_identity()
+        .context("The cycles wallet needs a selected identity.")?;
+    let canister =
+        get_or_create_wallet_canister(env, env.get_network_descriptor(), identity).await?;
+    let wallet_id = *canister.canister_id_();
+    *wallet = Some(wallet_id);
+    Ok(wallet_id)
+}
+
+fn print_table(states: &[CanisterCycles]) {
+    let rows: Vec<[String; 6]> = states
+        .iter()
+        .map(|state| {
+            [
+                state.name.clone(),
+                format_as_tc(state.balance),
+                format_as_tc(state.policy.minimum),
+                format_as_tc(state.policy.target),
+                match state.deficit() {
+                    0 => "-".to_string(),
+                    deficit => format_as_tc(deficit),
+                },
+                state.policy.source.to_string(),
+            ]
+        })
+        .collect();
+    table::print_table(
+        [
+            "CANISTER", "BALANCE", "MINIMUM", "TARGET", "TOP-UP", "SOURCE",
+        ],
+        &rows,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(balance: u128) -> CanisterCycles {
+        CanisterCycles {
+            name: "backend".to_string(),
+            canister_id: Principal::anonymous(),
+            policy: ConfigCanistersCanisterCycles {
+                minimum: 1_000,
+                target: 5_000,
+                source: CyclesSource::CyclesLedger,
+            },
+            balance,
+        }
+    }
+
+    #[test]
+    fn deficit_tops_up_to_target_below_minimum() {
+        assert_eq!(state(999).deficit(), 4_001);
+        assert_eq!(state(0).deficit(), 5_000);
+        assert_eq!(state(1_000).deficit(), 0);
+        assert_eq!(state(10_000).deficit(), 0);
+    }
+}
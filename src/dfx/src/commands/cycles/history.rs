@@ -0,0 +1,22 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::system_canisters::MAINNET_CYCLES_LEDGER_CANISTER_ID;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::util::clap::history_opt::HistoryOpt;
+use clap::Parser;
+
+/// Prints past transactions of the cycles ledger, by default those of the current identity.
+/// Reads the ICRC-3 blocks of the cycles ledger, or the transactions of an index canister.
+#[derive(Parser)]
+pub struct HistoryOpts {
+    #[command(flatten)]
+    history: HistoryOpt,
+}
+
+pub async fn exec(env: &dyn Environment, opts: HistoryOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    opts.history
+        .print_transactions(env, MAINNET_CYCLES_LEDGER_CANISTER_ID)
+        .await
+}
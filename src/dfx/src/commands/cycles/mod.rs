@@ -9,6 +9,8 @@ use tokio::runtime::Runtime;
 mod approve;
 mod balance;
 mod convert;
+mod ensure;
+mod history;
 mod redeem_faucet_coupon;
 pub mod top_up;
 mod transfer;
@@ -29,6 +31,8 @@ enum SubCommand {
     Approve(approve::ApproveOpts),
     Balance(balance::CyclesBalanceOpts),
     Convert(convert::ConvertOpts),
+    Ensure(ensure::EnsureOpts),
+    History(history::HistoryOpts),
     TopUp(top_up::TopUpOpts),
     Transfer(transfer::TransferOpts),
     RedeemFaucetCoupon(redeem_faucet_coupon::RedeemFaucetCouponOpts),
@@ -43,6 +47,8 @@ pub fn exec(env: &dyn Environment, opts: CyclesOpts) -> DfxResult {
             SubCommand::Approve(v) => approve::exec(&agent_env, v).await,
             SubCommand::Balance(v) => balance::exec(&agent_env, v).await,
             SubCommand::Convert(v) => convert::exec(&agent_env, v).await,
+            SubCommand::Ensure(v) => ensure::exec(&agent_env, v).await,
+            SubCommand::History(v) => history::exec(&agent_env, v).await,
             SubCommand::TopUp(v) => top_up::exec(&agent_env, v).await,
             SubCommand::Transfer(v) => transfer::exec(&agent_env, v).await,
             SubCommand::RedeemFaucetCoupon(v) => redeem_faucet_coupon::exec(&agent_env, v).await,
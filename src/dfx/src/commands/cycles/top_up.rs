@@ -1,8 +1,10 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::integrations::system_canisters::MAINNET_CYCLES_LEDGER_CANISTER_ID;
 use crate::lib::operations::cycles_ledger;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::{cycle_amount_parser, icrc_subaccount_parser};
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use candid::Principal;
 use clap::Parser;
 use icrc_ledger_types::icrc1::account::Subaccount;
@@ -27,6 +29,9 @@ pub struct TopUpOpts {
     /// https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
     #[arg(long)]
     created_at_time: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
@@ -34,8 +39,6 @@ pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
 
     let amount = opts.amount;
 
-    fetch_root_key_if_needed(env).await?;
-
     let created_at_time = opts.created_at_time.unwrap_or(
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -44,6 +47,24 @@ pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
     );
 
     let to = get_canister_id(env, &opts.to)?;
+
+    if opts.sign_only.is_sign_only() {
+        let (method_name, arg) =
+            cycles_ledger::withdraw_call(to, amount, created_at_time, opts.from_subaccount)?;
+        let description = format!(
+            "Top up canister {to} with {amount} cycles from the cycles ledger, created at time {created_at_time}."
+        );
+        return opts.sign_only.sign_update(
+            env,
+            description,
+            MAINNET_CYCLES_LEDGER_CANISTER_ID,
+            method_name,
+            arg,
+            None,
+        );
+    }
+
+    fetch_root_key_if_needed(env).await?;
     let result = cycles_ledger::withdraw(
         agent,
         env.get_logger(),
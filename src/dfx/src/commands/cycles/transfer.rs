@@ -1,9 +1,13 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::integrations::system_canisters::MAINNET_CYCLES_LEDGER_CANISTER_ID;
 use crate::lib::operations::cycles_ledger;
+use crate::lib::operations::ledger::icrc1_transfer_call;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::{cycle_amount_parser, icrc_subaccount_parser};
-use candid::Principal;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
+use anyhow::bail;
+use candid::{Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1::{self, account::Subaccount};
 use slog::warn;
@@ -43,6 +47,9 @@ pub struct TransferOpts {
     /// Memo.
     #[arg(long)]
     memo: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
@@ -50,8 +57,6 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
 
     let amount = opts.amount;
 
-    fetch_root_key_if_needed(env).await?;
-
     let created_at_time = opts.created_at_time.unwrap_or(
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -59,6 +64,37 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
             .as_nanos() as u64,
     );
 
+    if opts.sign_only.is_sign_only() {
+        if opts.from.is_some() {
+            bail!("--sign-only does not support transfers with --from.");
+        }
+        let to = icrc1::account::Account {
+            owner: opts.to,
+            subaccount: opts.to_subaccount,
+        };
+        let (method_name, arg) = icrc1_transfer_call(
+            opts.from_subaccount,
+            to,
+            Nat::from(amount),
+            None,
+            opts.memo,
+            created_at_time,
+        )?;
+        let description = format!(
+            "Transfer {amount} cycles to {to} on the cycles ledger, created at time {created_at_time}."
+        );
+        return opts.sign_only.sign_update(
+            env,
+            description,
+            MAINNET_CYCLES_LEDGER_CANISTER_ID,
+            method_name,
+            arg,
+            None,
+        );
+    }
+
+    fetch_root_key_if_needed(env).await?;
+
     let result = if let Some(from_owner) = opts.from {
         let from = icrc1::account::Account {
             owner: from_owner,
@@ -5,9 +5,10 @@ use crate::lib::error::DfxResult;
 use crate::lib::network::network_opt::NetworkOpt;
 use crate::lib::operations::canister::deploy_canisters::deploy_canisters;
 use crate::lib::operations::canister::deploy_canisters::DeployMode::{
-    ComputeEvidence, ForceReinstallSingleCanister, NormalDeploy, PrepareForProposal,
+    ComputeEvidence, ForceReinstallSingleCanister, NormalDeploy, PrepareForProposal, RedeployOnly,
 };
 use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::watch::watch_canisters;
 use crate::util::clap::argument_from_cli::ArgumentFromCliLongOpt;
 use crate::util::clap::install_mode::{InstallModeHint, InstallModeOpt};
 use crate::util::clap::parsers::{cycle_amount_parser, icrc_subaccount_parser};
@@ -17,10 +18,11 @@ use anyhow::{anyhow, bail};
 use candid::Principal;
 use clap::Parser;
 use console::Style;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::config::model::network_descriptor::NetworkDescriptor;
 use dfx_core::identity::CallSender;
 use icrc_ledger_types::icrc1::account::Subaccount;
-use slog::info;
+use slog::{error, info};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tokio::runtime::Runtime;
@@ -30,9 +32,15 @@ use url::Url;
 #[derive(Parser)]
 pub struct DeployOpts {
     /// Specifies the name of the canister you want to deploy.
+    /// A glob pattern such as `svc_*` selects several canisters.
     /// If you don’t specify a canister name, all canisters defined in the dfx.json file are deployed.
     canister_name: Option<String>,
 
+    /// Only deploys the canisters with this tag in dfx.json, and their dependencies.
+    /// Can be specified more than once.
+    #[arg(long)]
+    tag: Vec<String>,
+
     #[command(flatten)]
     argument_from_cli: ArgumentFromCliLongOpt,
 
@@ -111,17 +119,36 @@ pub struct DeployOpts {
         conflicts_with("yes")
     )]
     always_assist: bool,
+
+    /// Keep running, and rebuild and upgrade canisters whenever their sources change.
+    /// Assets are synchronized and declarations are regenerated as part of each deployment.
+    #[arg(
+        long,
+        conflicts_with("by_proposal"),
+        conflicts_with("compute_evidence"),
+        conflicts_with("always_assist")
+    )]
+    watch: bool,
+
+    /// With --watch, also pass the init argument to each upgrade.
+    /// By default it is only used for the first deployment.
+    #[arg(long, requires("watch"))]
+    watch_with_argument: bool,
 }
 
 pub fn exec(env: &dyn Environment, opts: DeployOpts) -> DfxResult {
     let env = create_agent_environment(env, opts.network.to_network_name())?;
     let runtime = Runtime::new().expect("Unable to create a runtime");
 
-    let canister_name = opts.canister_name.as_deref();
+    let canisters = CanisterSelector::new(opts.canister_name.as_deref(), &opts.tag);
+    let canister_name = canisters.single();
     let (argument_from_cli, argument_type) = opts.argument_from_cli.get_argument_and_type()?;
     if argument_from_cli.is_some() && canister_name.is_none() {
         bail!("The init argument can only be set when deploying a single canister.");
     }
+    if opts.specified_id.is_some() && canister_name.is_none() {
+        bail!("The --specified-id option can only be used when deploying a single canister.");
+    }
     let mode_hint = opts.install_mode.mode_for_deploy()?;
     let config = env.get_config_or_anyhow()?;
     let env_file = config.get_output_env_file(opts.output_env_file)?;
@@ -164,9 +191,9 @@ pub fn exec(env: &dyn Environment, opts: DeployOpts) -> DfxResult {
 
     runtime.block_on(fetch_root_key_if_needed(&env))?;
 
-    runtime.block_on(deploy_canisters(
+    let deployed = runtime.block_on(deploy_canisters(
         &env,
-        canister_name,
+        &canisters,
         argument_from_cli.as_deref(),
         argument_type.as_deref(),
         &deploy_mode,
@@ -179,15 +206,76 @@ pub fn exec(env: &dyn Environment, opts: DeployOpts) -> DfxResult {
         opts.from_subaccount,
         opts.no_wallet,
         opts.yes,
-        env_file,
+        env_file.clone(),
         opts.no_asset_upgrade,
         &mut subnet_selection,
         opts.always_assist,
-    ))?;
+    ));
+    // In watch mode, a failed deployment is retried with the next change.
+    let failed = match deployed {
+        Ok(()) => false,
+        Err(err) if opts.watch => {
+            error!(env.get_logger(), "{:#}", err);
+            true
+        }
+        Err(err) => return Err(err),
+    };
 
-    if matches!(deploy_mode, NormalDeploy | ForceReinstallSingleCanister(_)) {
+    if !failed && matches!(deploy_mode, NormalDeploy | ForceReinstallSingleCanister(_)) {
         display_urls(&env)?;
     }
+
+    if opts.watch {
+        let network = &env.get_network_descriptor().name;
+        let mut watched = config
+            .get_config()
+            .get_selected_canister_names_with_dependencies(&canisters)?;
+        watched.retain(|name| {
+            !config
+                .get_config()
+                .is_remote_canister(name, network)
+                .unwrap_or(false)
+        });
+        watched.sort();
+        let failed_canisters = if failed { watched.clone() } else { vec![] };
+        // The init argument is for the first deployment, which may only succeed in watch mode.
+        let mut first_deployment = failed;
+        watch_canisters(
+            env.get_logger(),
+            &config,
+            &watched,
+            &failed_canisters,
+            |affected| {
+                let (argument, argument_type) = if first_deployment || opts.watch_with_argument {
+                    (argument_from_cli.as_deref(), argument_type.as_deref())
+                } else {
+                    (None, None)
+                };
+                runtime.block_on(deploy_canisters(
+                    &env,
+                    &CanisterSelector::Names(affected.to_vec()),
+                    argument,
+                    argument_type,
+                    &RedeployOnly(affected.to_vec()),
+                    &InstallModeHint::Auto(None),
+                    false,
+                    with_cycles,
+                    None,
+                    None,
+                    &call_sender,
+                    opts.from_subaccount,
+                    opts.no_wallet,
+                    opts.yes,
+                    env_file.clone(),
+                    opts.no_asset_upgrade,
+                    &mut subnet_selection,
+                    false,
+                ))?;
+                first_deployment = false;
+                Ok(())
+            },
+        )?;
+    }
     Ok(())
 }
 
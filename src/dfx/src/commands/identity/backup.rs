@@ -0,0 +1,42 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use clap::Parser;
+use dialoguer::Password;
+use slog::info;
+use std::path::PathBuf;
+
+/// Writes all identities, their configuration and their wallets to a single password-encrypted backup file.
+/// Password-protected identities keep their original password.
+#[derive(Parser)]
+pub struct BackupOpts {
+    /// The file to write the backup to.
+    file: PathBuf,
+}
+
+pub fn exec(env: &dyn Environment, opts: BackupOpts) -> DfxResult {
+    let log = env.get_logger();
+    let identity_manager = env.new_identity_manager()?;
+
+    let password = Password::new()
+        .with_prompt("Please enter a passphrase for the backup")
+        .with_confirmation("Please confirm the passphrase", "Passphrases do not match.")
+        .validate_with(|password: &String| -> Result<(), &str> {
+            if password.chars().count() > 8 {
+                Ok(())
+            } else {
+                Err("Password must be longer than 8 characters.")
+            }
+        })
+        .interact()?;
+
+    let names = identity_manager.backup_identities(log, &opts.file, &password)?;
+    info!(
+        log,
+        "Backed up {} identities to {}: {}",
+        names.len(),
+        opts.file.display(),
+        names.join(", ")
+    );
+
+    Ok(())
+}
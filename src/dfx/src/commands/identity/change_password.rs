@@ -0,0 +1,24 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use clap::Parser;
+use slog::info;
+
+/// Changes the password of a password-protected identity.
+#[derive(Parser)]
+pub struct ChangePasswordOpts {
+    /// The identity whose password to change. Defaults to the identity currently in use.
+    identity: Option<String>,
+}
+
+pub fn exec(env: &dyn Environment, opts: ChangePasswordOpts) -> DfxResult {
+    let log = env.get_logger();
+    let identity_manager = env.new_identity_manager()?;
+    let name = opts
+        .identity
+        .unwrap_or_else(|| identity_manager.get_selected_identity_name().clone());
+
+    identity_manager.change_password(log, &name)?;
+    info!(log, r#"Changed the password of identity "{}"."#, name);
+
+    Ok(())
+}
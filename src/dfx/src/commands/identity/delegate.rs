@@ -0,0 +1,90 @@
+use crate::lib::agent::create_network_identity_manager;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use anyhow::{anyhow, bail, Context};
+use candid::Principal;
+use clap::Parser;
+use dfx_core::identity::delegation::DelegationChain;
+use dfx_core::json::save_json_file;
+use slog::info;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Delegates from the current identity to a session key, so that the session key can make calls
+/// as the current identity until the delegation expires, for example in a CI job.
+#[derive(Parser)]
+pub struct DelegateOpts {
+    /// The identity that holds the session key, or the DER-encoded public key of the session key in hex.
+    /// The delegation is added to the identity, which then makes calls as the current identity.
+    /// For a public key, the delegation chain is printed, or written to the --output file.
+    #[arg(long)]
+    to: String,
+
+    /// How long the delegation is valid (e.g. `30m`, `1h`, `7d`).
+    #[arg(long, default_value = "1h")]
+    expiry: String,
+
+    /// The canisters the session key may call. By default, it may call any canister.
+    #[arg(long, value_delimiter = ',')]
+    targets: Vec<Principal>,
+
+    /// Write the delegation chain to this file, when delegating to a public key.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+pub fn exec(env: &dyn Environment, opts: DelegateOpts, network: NetworkOpt) -> DfxResult {
+    let log = env.get_logger();
+    let mut identity_manager = create_network_identity_manager(env, network.to_network_name())?;
+    let identity = identity_manager.instantiate_selected_identity(log)?;
+    let from_name = identity.name().to_string();
+
+    let to_identity = identity_manager
+        .require_identity_exists(log, &opts.to)
+        .is_ok();
+    let to_public_key = if to_identity {
+        if opts.to == from_name {
+            bail!("An identity cannot delegate to itself.");
+        }
+        if opts.output.is_some() {
+            bail!("--output is only used when delegating to a public key.");
+        }
+        identity_manager
+            .get_identity_public_key(log, &opts.to)?
+            .ok_or_else(|| anyhow!("Identity '{}' has no key to delegate to.", opts.to))?
+    } else {
+        hex::decode(&opts.to).with_context(|| {
+            format!(
+                "'{}' is neither an identity nor a public key in hex.",
+                opts.to
+            )
+        })?
+    };
+
+    let expiry = humantime::parse_duration(&opts.expiry)
+        .map_err(|_| anyhow!("Cannot parse expiry as a duration (e.g. `1h`, `1h 30m`)"))?;
+    let expiration = SystemTime::now()
+        .checked_add(expiry)
+        .ok_or_else(|| anyhow!("Time wrapped around."))?
+        .duration_since(UNIX_EPOCH)?
+        .as_nanos()
+        .try_into()
+        .context("Expiration is too far in the future.")?;
+    let targets = (!opts.targets.is_empty()).then_some(opts.targets);
+    let chain = DelegationChain::delegate(&*identity, to_public_key, expiration, targets)?;
+
+    if to_identity {
+        identity_manager.set_delegation_chain(log, &opts.to, &chain)?;
+        info!(
+            log,
+            "Identity '{}' makes calls as '{}' for {}.", opts.to, from_name, opts.expiry
+        );
+    } else if let Some(output) = &opts.output {
+        save_json_file(output, &chain)?;
+        info!(log, "Delegation chain written to [{}].", output.display());
+    } else {
+        println!("{}", serde_json::to_string_pretty(&chain)?);
+    }
+    Ok(())
+}
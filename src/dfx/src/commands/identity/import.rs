@@ -3,7 +3,9 @@ use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use anyhow::Context;
 use clap::Parser;
+use dfx_core::identity::delegation::DelegationChain;
 use dfx_core::identity::identity_manager::{IdentityCreationParameters, IdentityStorageMode};
+use dfx_core::json::load_json_file;
 use slog::{info, warn};
 use std::fs;
 use std::path::PathBuf;
@@ -36,6 +38,11 @@ pub struct ImportOpts {
     /// If the identity already exists, remove and re-import it.
     #[arg(long)]
     force: bool,
+
+    /// A delegation chain to the imported key, created with `dfx identity delegate`.
+    /// The identity then makes calls as the identity that delegated to it.
+    #[arg(long)]
+    delegation: Option<PathBuf>,
 }
 
 /// Executes the import subcommand.
@@ -62,7 +69,17 @@ pub fn exec(env: &dyn Environment, opts: ImportOpts) -> DfxResult {
         IdentityCreationParameters::SeedPhrase { mnemonic, mode }
     };
 
+    let chain: Option<DelegationChain> =
+        opts.delegation.as_deref().map(load_json_file).transpose()?;
     create_new_dfx_identity(env, log, name, params, opts.force)?;
+    if let Some(chain) = chain {
+        let identity_manager = env.new_identity_manager()?;
+        if let Err(err) = identity_manager.set_delegation_chain(log, name, &chain) {
+            // Do not leave an identity that would sign as itself instead of the delegating identity.
+            identity_manager.remove(log, name, true, None)?;
+            return Err(err.into());
+        }
+    }
 
     info!(log, r#"Imported identity: "{}"."#, name);
     Ok(())
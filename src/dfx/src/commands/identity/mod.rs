@@ -3,6 +3,9 @@ use crate::lib::error::DfxResult;
 use crate::lib::network::network_opt::NetworkOpt;
 use clap::Parser;
 
+mod backup;
+mod change_password;
+mod delegate;
 mod deploy_wallet;
 mod export;
 mod get_wallet;
@@ -12,6 +15,8 @@ mod new;
 mod principal;
 mod remove;
 mod rename;
+mod restore;
+mod set_storage_mode;
 mod set_wallet;
 mod r#use;
 mod whoami;
@@ -30,6 +35,9 @@ pub struct IdentityOpts {
 
 #[derive(Parser)]
 enum SubCommand {
+    Backup(backup::BackupOpts),
+    ChangePassword(change_password::ChangePasswordOpts),
+    Delegate(delegate::DelegateOpts),
     DeployWallet(deploy_wallet::DeployWalletOpts),
     Export(export::ExportOpts),
     GetWallet(get_wallet::GetWalletOpts),
@@ -39,6 +47,8 @@ enum SubCommand {
     GetPrincipal(principal::GetPrincipalOpts),
     Remove(remove::RemoveOpts),
     Rename(rename::RenameOpts),
+    Restore(restore::RestoreOpts),
+    SetStorageMode(set_storage_mode::SetStorageModeOpts),
     SetWallet(set_wallet::SetWalletOpts),
     Use(r#use::UseOpts),
     Whoami(whoami::WhoAmIOpts),
@@ -46,15 +56,20 @@ enum SubCommand {
 
 pub fn exec(env: &dyn Environment, opts: IdentityOpts) -> DfxResult {
     match opts.subcmd {
+        SubCommand::Backup(v) => backup::exec(env, v),
+        SubCommand::ChangePassword(v) => change_password::exec(env, v),
+        SubCommand::Delegate(v) => delegate::exec(env, v, opts.network),
         SubCommand::DeployWallet(v) => deploy_wallet::exec(env, v, opts.network),
         SubCommand::Export(v) => export::exec(env, v),
         SubCommand::GetWallet(v) => get_wallet::exec(env, v, opts.network),
         SubCommand::List(v) => list::exec(env, v),
-        SubCommand::New(v) => new::exec(env, v),
-        SubCommand::GetPrincipal(v) => principal::exec(env, v),
+        SubCommand::New(v) => new::exec(env, v, opts.network),
+        SubCommand::GetPrincipal(v) => principal::exec(env, v, opts.network),
         SubCommand::Import(v) => import::exec(env, v),
         SubCommand::Remove(v) => remove::exec(env, v),
         SubCommand::Rename(v) => rename::exec(env, v),
+        SubCommand::Restore(v) => restore::exec(env, v),
+        SubCommand::SetStorageMode(v) => set_storage_mode::exec(env, v),
         SubCommand::SetWallet(v) => set_wallet::exec(env, v, opts.network),
         SubCommand::Use(v) => r#use::exec(env, v),
         SubCommand::Whoami(v) => whoami::exec(env, v),
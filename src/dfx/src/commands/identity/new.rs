@@ -1,15 +1,29 @@
-use crate::lib::environment::Environment;
+use crate::lib::environment::{create_agent, Environment};
 use crate::lib::error::DfxResult;
+use crate::lib::identity::internet_identity::{
+    add_device, delegate_to_session_key, register_anchor,
+};
+use crate::lib::network::network_opt::NetworkOpt;
 use crate::util::clap::parsers::hsm_key_id_parser;
-use anyhow::Context;
+use anyhow::{anyhow, bail, Context};
+use candid::Principal;
 use clap::Parser;
+use dfx_core::config::model::canister_id_store::CanisterIdStore;
+use dfx_core::config::model::network_descriptor::NetworkDescriptor;
 use dfx_core::error::identity::CreateNewIdentityError::SwitchBackToIdentityFailed;
 use dfx_core::identity::identity_manager::{
-    HardwareIdentityConfiguration, IdentityCreationParameters, IdentityStorageMode,
+    HardwareIdentityConfiguration, IdentityCreationParameters, IdentityManager,
+    IdentityStorageMode, InternetIdentityConfiguration,
 };
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+use dfx_core::network::root_key::fetch_root_key_when_non_mainnet;
+use dfx_core::util::expiry_duration;
+use ic_agent::Identity as _;
 use regex::Regex;
 use slog::{info, warn, Logger};
 use std::str::FromStr;
+use tokio::runtime::Runtime;
+use url::Url;
 use IdentityCreationParameters::{Hardware, Pem};
 
 /// Creates a new identity.
@@ -46,8 +60,24 @@ pub struct NewIdentityOpts {
     storage_mode: Option<String>,
 
     /// If the identity already exists, remove and re-create it.
+    /// An Internet Identity identity keeps its anchor and origin unless they are specified.
     #[arg(long)]
     force: bool,
+
+    /// The type of identity to create. `ii-local` signs in to Internet Identity on the local network,
+    /// so that the identity makes calls as the user that a frontend sees.
+    #[arg(long = "type", value_parser = ["ii-local"], conflicts_with("hsm_pkcs11_lib_path"))]
+    identity_type: Option<String>,
+
+    /// The Internet Identity anchor to sign in with. It must have been registered by another identity
+    /// created with `--type ii-local`. By default, a new anchor is registered.
+    #[arg(long, requires("identity_type"))]
+    anchor: Option<u64>,
+
+    /// The frontend to sign in to: its origin (e.g. `http://<canister id>.localhost:4943`),
+    /// or the name or id of a canister on the local network.
+    #[arg(long, requires("identity_type"))]
+    origin: Option<String>,
 }
 
 fn identity_name_validator(name: &str) -> Result<String, String> {
@@ -58,7 +88,7 @@ fn identity_name_validator(name: &str) -> Result<String, String> {
     Ok(name.into())
 }
 
-pub fn exec(env: &dyn Environment, opts: NewIdentityOpts) -> DfxResult {
+pub fn exec(env: &dyn Environment, opts: NewIdentityOpts, network: NetworkOpt) -> DfxResult {
     let log = env.get_logger();
 
     if opts.disable_encryption {
@@ -67,18 +97,18 @@ pub fn exec(env: &dyn Environment, opts: NewIdentityOpts) -> DfxResult {
 
     let name = opts.new_identity.as_str();
 
-    let creation_parameters = match (opts.hsm_pkcs11_lib_path, opts.hsm_key_id) {
+    let creation_parameters = match (&opts.hsm_pkcs11_lib_path, &opts.hsm_key_id) {
         (Some(pkcs11_lib_path), Some(key_id)) => Hardware {
             hsm: HardwareIdentityConfiguration {
-                pkcs11_lib_path,
-                key_id,
+                pkcs11_lib_path: pkcs11_lib_path.clone(),
+                key_id: key_id.clone(),
             },
         },
         _ => {
             let mode = if opts.disable_encryption {
                 IdentityStorageMode::Plaintext
-            } else if let Some(mode_str) = opts.storage_mode {
-                IdentityStorageMode::from_str(&mode_str)?
+            } else if let Some(mode_str) = &opts.storage_mode {
+                IdentityStorageMode::from_str(mode_str)?
             } else {
                 IdentityStorageMode::default()
             };
@@ -87,12 +117,155 @@ pub fn exec(env: &dyn Environment, opts: NewIdentityOpts) -> DfxResult {
         }
     };
 
+    if opts.identity_type.is_some() {
+        return create_internet_identity(env, log, name, creation_parameters, &opts, network);
+    }
+
     create_new_dfx_identity(env, log, name, creation_parameters, opts.force)?;
 
     info!(log, r#"Created identity: "{}"."#, name);
     Ok(())
 }
 
+/// Creates an identity whose key is a device of an anchor of Internet Identity on the local network,
+/// delegated to by the principal of the anchor for the frontend origin.
+fn create_internet_identity(
+    env: &dyn Environment,
+    log: &Logger,
+    name: &str,
+    creation_parameters: IdentityCreationParameters,
+    opts: &NewIdentityOpts,
+    network: NetworkOpt,
+) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network.to_network_name(),
+        None,
+        LocalBindDetermination::ApplyRunningWebserverPort,
+    )?;
+    if network_descriptor.is_ic {
+        bail!("Internet Identity identities can only be created for a local network.");
+    }
+    let identity_manager = env.new_identity_manager()?;
+    let previous = if identity_manager.require_identity_exists(log, name).is_ok() {
+        identity_manager
+            .get_identity_config_or_default(name)?
+            .internet_identity
+    } else {
+        None
+    };
+    let anchor = opts
+        .anchor
+        .or(previous.as_ref().map(|previous| previous.anchor));
+    let origin = opts
+        .origin
+        .clone()
+        .or(previous.map(|previous| previous.origin))
+        .ok_or_else(|| anyhow!("Specify the frontend to sign in to with --origin."))?;
+    let origin = frontend_origin(env, &network_descriptor, &origin)?;
+
+    let url = network_descriptor.first_provider()?;
+    let timeout = expiry_duration();
+    // The device is loaded before the identity is created, in case --force replaces it.
+    let device_agent = match anchor {
+        Some(anchor) => {
+            let device = find_device(log, &identity_manager, anchor)?;
+            let key = identity_manager.load_identity_key(&device, log)?;
+            Some(create_agent(log.clone(), url, Box::new(key), timeout)?)
+        }
+        None => None,
+    };
+
+    create_new_dfx_identity(env, log, name, creation_parameters, opts.force)?;
+    let identity_manager = env.new_identity_manager()?;
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    let result = runtime.block_on(async {
+        let key = identity_manager.load_identity_key(name, log)?;
+        let session_key = key
+            .public_key()
+            .ok_or_else(|| anyhow!("Identity '{}' has no public key.", name))?;
+        let agent = create_agent(log.clone(), url, Box::new(key), timeout)?;
+        fetch_root_key_when_non_mainnet(&agent, &network_descriptor).await?;
+        let alias = format!("dfx identity {name}");
+        let anchor = match (&device_agent, anchor) {
+            (Some(device_agent), Some(anchor)) => {
+                fetch_root_key_when_non_mainnet(device_agent, &network_descriptor).await?;
+                add_device(device_agent, anchor, &session_key, &alias).await?;
+                anchor
+            }
+            _ => register_anchor(&agent, &session_key, &alias).await?,
+        };
+        let chain = delegate_to_session_key(&agent, anchor, &origin, &session_key).await?;
+        identity_manager.set_delegation_chain(log, name, &chain)?;
+        identity_manager.set_internet_identity(
+            log,
+            name,
+            InternetIdentityConfiguration {
+                anchor,
+                origin: origin.clone(),
+            },
+        )?;
+        DfxResult::Ok((anchor, Principal::self_authenticating(chain.public_key()?)))
+    });
+    let (anchor, principal) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            // Do not leave an identity that would sign as its key instead of the Internet Identity user.
+            identity_manager.remove(log, name, true, None)?;
+            return Err(err);
+        }
+    };
+
+    info!(
+        log,
+        r#"Created identity: "{}". It signs in to {} with anchor {} as {}."#,
+        name,
+        origin,
+        anchor,
+        principal
+    );
+    Ok(())
+}
+
+/// Finds an identity whose key is a device of the anchor.
+fn find_device(log: &Logger, identity_manager: &IdentityManager, anchor: u64) -> DfxResult<String> {
+    for name in identity_manager.get_identity_names(log)? {
+        let config = identity_manager.get_identity_config_or_default(&name)?;
+        if config
+            .internet_identity
+            .is_some_and(|internet_identity| internet_identity.anchor == anchor)
+        {
+            return Ok(name);
+        }
+    }
+    bail!(
+        "No identity signs in with anchor {}. Omit --anchor to register a new anchor.",
+        anchor
+    )
+}
+
+/// The origin of a frontend URL, or of the URL of a frontend canister on the local network.
+fn frontend_origin(
+    env: &dyn Environment,
+    network_descriptor: &NetworkDescriptor,
+    origin: &str,
+) -> DfxResult<String> {
+    if let Ok(url) = Url::parse(origin) {
+        if url.has_host() {
+            return Ok(url.origin().ascii_serialization());
+        }
+    }
+    let canister_id = match Principal::from_text(origin) {
+        Ok(canister_id) => canister_id,
+        Err(_) => CanisterIdStore::new(env.get_logger(), network_descriptor, env.get_config()?)?
+            .get(origin)?,
+    };
+    let mut url = Url::parse(network_descriptor.first_provider()?)?;
+    url.set_host(Some(&format!("{canister_id}.localhost")))?;
+    Ok(url.origin().ascii_serialization())
+}
+
 pub fn create_new_dfx_identity(
     env: &dyn Environment,
     log: &Logger,
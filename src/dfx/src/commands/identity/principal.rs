@@ -1,5 +1,7 @@
+use crate::lib::agent::create_network_identity_manager;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
 use anyhow::anyhow;
 use clap::Parser;
 use ic_agent::identity::Identity;
@@ -8,9 +10,8 @@ use ic_agent::identity::Identity;
 #[derive(Parser)]
 pub struct GetPrincipalOpts {}
 
-pub fn exec(env: &dyn Environment, _opts: GetPrincipalOpts) -> DfxResult {
-    let identity = env
-        .new_identity_manager()?
+pub fn exec(env: &dyn Environment, _opts: GetPrincipalOpts, network: NetworkOpt) -> DfxResult {
+    let identity = create_network_identity_manager(env, network.to_network_name())?
         .instantiate_selected_identity(env.get_logger())?;
     let principal_id = identity
         .as_ref()
@@ -0,0 +1,52 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use clap::Parser;
+use dialoguer::Password;
+use slog::{info, warn};
+use std::path::PathBuf;
+
+/// Recreates the identities contained in a backup created with `dfx identity backup`.
+/// Identities keep the storage mode they had when the backup was created.
+#[derive(Parser)]
+pub struct RestoreOpts {
+    /// The backup file to restore identities from.
+    file: PathBuf,
+
+    /// Replace identities that already exist.
+    /// Without this flag, existing identities are skipped.
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn exec(env: &dyn Environment, opts: RestoreOpts) -> DfxResult {
+    let log = env.get_logger();
+    let mut identity_manager = env.new_identity_manager()?;
+
+    let password = Password::new()
+        .with_prompt("Please enter the passphrase for the backup")
+        .interact()?;
+
+    let summary = identity_manager.restore_identities(log, &opts.file, &password, opts.force)?;
+    for name in &summary.skipped {
+        warn!(
+            log,
+            "Identity '{}' already exists and was not restored. Use --force to replace it.", name
+        );
+    }
+    info!(
+        log,
+        "Restored {} identities: {}",
+        summary.restored.len(),
+        summary.restored.join(", ")
+    );
+    if identity_manager.get_default_identity_name() != summary.default_identity {
+        info!(
+            log,
+            "The default identity at the time of the backup was '{}'. Run 'dfx identity use {}' to use it again.",
+            summary.default_identity,
+            summary.default_identity
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use clap::Parser;
+use dfx_core::identity::identity_manager::IdentityStorageMode;
+use dfx_core::identity::ANONYMOUS_IDENTITY_NAME;
+use slog::info;
+use std::str::FromStr;
+
+/// Moves an identity's private key to a different storage mode.
+#[derive(Parser)]
+pub struct SetStorageModeOpts {
+    /// The identity to migrate.
+    #[arg(required_unless_present("all_plaintext"), requires("mode"))]
+    identity: Option<String>,
+
+    /// The storage mode to move the identity's key to.
+    #[arg(value_parser = ["keyring", "password-protected", "plaintext"])]
+    mode: Option<String>,
+
+    /// Move the keys of all plaintext identities to the given storage mode.
+    #[arg(long, value_name = "MODE", conflicts_with_all(["identity", "mode"]),
+        value_parser = ["keyring", "password-protected"])]
+    all_plaintext: Option<String>,
+}
+
+pub fn exec(env: &dyn Environment, opts: SetStorageModeOpts) -> DfxResult {
+    let log = env.get_logger();
+    let identity_manager = env.new_identity_manager()?;
+
+    let (names, mode) = if let Some(mode) = &opts.all_plaintext {
+        let mut names = vec![];
+        for name in identity_manager.get_identity_names(log)? {
+            if name == ANONYMOUS_IDENTITY_NAME {
+                continue;
+            }
+            let config = identity_manager.get_identity_config_or_default(&name)?;
+            if config.storage_mode() == Some(IdentityStorageMode::Plaintext) {
+                names.push(name);
+            }
+        }
+        (names, mode)
+    } else {
+        (
+            vec![opts.identity.clone().unwrap()],
+            opts.mode.as_ref().unwrap(),
+        )
+    };
+    let mode = IdentityStorageMode::from_str(mode)?;
+
+    if names.is_empty() {
+        info!(log, "There are no plaintext identities.");
+    }
+    for name in names {
+        identity_manager.set_storage_mode(log, &name, mode)?;
+        info!(
+            log,
+            r#"Identity "{}" now uses storage mode {}."#, name, mode
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::lib::network::process::running_dfx_pid;
+use crate::lib::{environment::Environment, error::DfxResult};
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+use slog::error;
+use std::collections::BTreeSet;
+use sysinfo::{System, SystemExt};
+
+/// One line per running local network: its name, the port of its HTTP gateway,
+/// and the port of its PocketIC or replica.
+pub(crate) fn get_local_networks(env: &dyn Environment) -> DfxResult<String> {
+    let mut names = BTreeSet::from(["local".to_string()]);
+    if let Some(config) = env.get_config()? {
+        names.extend(
+            config
+                .get_config()
+                .networks
+                .iter()
+                .flat_map(|networks| networks.keys().cloned()),
+        );
+    }
+    names.extend(
+        env.get_networks_config()
+            .get_interface()
+            .networks
+            .keys()
+            .cloned(),
+    );
+
+    let mut system = System::new();
+    system.refresh_processes();
+    let mut lines = vec![];
+    for name in names {
+        // A network that cannot be read is reported without hiding the others.
+        match local_network_line(env, &system, &name) {
+            Ok(Some(line)) => lines.push(line),
+            Ok(None) => {}
+            Err(err) => error!(
+                env.get_logger(),
+                "Cannot read local network '{}': {:#}", name, err
+            ),
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// The line of a local network, if it is running.
+fn local_network_line(
+    env: &dyn Environment,
+    system: &System,
+    name: &str,
+) -> DfxResult<Option<String>> {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        Some(name.to_string()),
+        None,
+        LocalBindDetermination::ApplyRunningWebserverPort,
+    )?;
+    let Some(local) = &network_descriptor.local_server_descriptor else {
+        return Ok(None);
+    };
+    if running_dfx_pid(system, local).is_none() {
+        return Ok(None);
+    }
+    let backend_port = match local.is_pocketic()? {
+        Some(true) => local.get_running_pocketic_port(None)?,
+        Some(false) => local.get_running_replica_port(None)?,
+        None => None,
+    };
+    let backend_port = backend_port.map_or_else(|| "-".to_string(), |port| port.to_string());
+    Ok(Some(format!(
+        "{} {} {}",
+        name,
+        local.bind_address.port(),
+        backend_port
+    )))
+}
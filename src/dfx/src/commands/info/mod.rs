@@ -1,8 +1,13 @@
+mod local_networks;
 mod pocketic_config_port;
 mod replica_port;
+mod subnets;
 mod webserver_port;
 
-use crate::commands::info::{replica_port::get_replica_port, webserver_port::get_webserver_port};
+use crate::commands::info::{
+    local_networks::get_local_networks, replica_port::get_replica_port, subnets::get_subnets,
+    webserver_port::get_webserver_port,
+};
 use crate::lib::agent::create_anonymous_agent_environment;
 use crate::lib::error::DfxResult;
 use crate::lib::info;
@@ -37,6 +42,10 @@ enum InfoType {
     TelemetryLogPath,
     /// Show the effective canister ID that dfx will use for management canister calls that don't imply one
     DefaultEffectiveCanisterId,
+    /// Show the subnets of the running PocketIC instance, with their canister ID ranges
+    Subnets,
+    /// Show the running local networks, with the ports of their HTTP gateway and PocketIC or replica
+    LocalNetworks,
 }
 
 #[derive(Parser)]
@@ -69,10 +78,14 @@ pub fn exec(env: &dyn Environment, opts: InfoOpts) -> DfxResult {
         InfoType::SecurityPolicy => {
             ic_asset::security_policy::SecurityPolicy::Standard.to_json5_str()
         }
-        InfoType::ReplicaPort => get_replica_port(env)?,
-        InfoType::PocketicConfigPort => get_pocketic_config_port(env)?,
+        InfoType::ReplicaPort => get_replica_port(env, opts.network.to_network_name())?,
+        InfoType::PocketicConfigPort => {
+            get_pocketic_config_port(env, opts.network.to_network_name())?
+        }
+        InfoType::Subnets => get_subnets(env, opts.network.to_network_name())?,
+        InfoType::LocalNetworks => get_local_networks(env)?,
         InfoType::ReplicaRev => info::replica_rev().to_string(),
-        InfoType::WebserverPort => get_webserver_port(env)?,
+        InfoType::WebserverPort => get_webserver_port(env, opts.network.to_network_name())?,
         InfoType::NetworksJsonPath => NetworksConfig::new()?.get_path().display().to_string(),
         InfoType::ConfigJsonPath => ToolConfig::new()?.config_path().display().to_string(),
         InfoType::TelemetryLogPath => Telemetry::get_log_path()?.display().to_string(),
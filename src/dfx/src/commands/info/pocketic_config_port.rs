@@ -3,11 +3,14 @@ use dfx_core::network::provider::{create_network_descriptor, LocalBindDeterminat
 
 use crate::lib::{environment::Environment, error::DfxResult};
 
-pub(crate) fn get_pocketic_config_port(env: &dyn Environment) -> DfxResult<String> {
+pub(crate) fn get_pocketic_config_port(
+    env: &dyn Environment,
+    network: Option<String>,
+) -> DfxResult<String> {
     let network_descriptor = create_network_descriptor(
         env.get_config()?,
         env.get_networks_config(),
-        None,
+        network,
         None,
         LocalBindDetermination::AsConfigured,
     )?;
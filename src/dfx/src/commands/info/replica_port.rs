@@ -3,11 +3,14 @@ use crate::Environment;
 use anyhow::bail;
 use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
 
-pub(crate) fn get_replica_port(env: &dyn Environment) -> DfxResult<String> {
+pub(crate) fn get_replica_port(
+    env: &dyn Environment,
+    network: Option<String>,
+) -> DfxResult<String> {
     let network_descriptor = create_network_descriptor(
         env.get_config()?,
         env.get_networks_config(),
-        None,
+        network,
         None,
         LocalBindDetermination::AsConfigured,
     )?;
@@ -0,0 +1,38 @@
+use crate::lib::{environment::Environment, error::DfxResult};
+use anyhow::bail;
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+
+pub(crate) fn get_subnets(env: &dyn Environment, network: Option<String>) -> DfxResult<String> {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network,
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let local = network_descriptor.local_server_descriptor()?;
+    let Some(effective_config) = local.effective_config()? else {
+        bail!("No running PocketIC instance found");
+    };
+    if effective_config.get_subnets().is_empty() {
+        bail!("The running server does not report its subnets. Subnets are only available with PocketIC.");
+    }
+    let lines: Vec<String> = effective_config
+        .get_subnets()
+        .iter()
+        .map(|subnet| {
+            let ranges: Vec<String> = subnet
+                .canister_ranges
+                .iter()
+                .map(|range| format!("{}..{}", range.start, range.end))
+                .collect();
+            format!(
+                "{} {} {}",
+                subnet.name(),
+                subnet.subnet_id,
+                ranges.join(" ")
+            )
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
@@ -1,16 +1,25 @@
+use crate::lib::network::process::{descendant_pids, running_dfx_pid};
 use crate::lib::{environment::Environment, error::DfxResult};
 
 use anyhow::Error;
 use clap::Parser;
 use dfx_core::config::cache::ensure_cache_versions_dir;
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
 use slog::info;
 use sysinfo::{ProcessExt, System, SystemExt};
 
 /// Kills all dfx-related processes on the system. Useful if a process gets stuck.
 #[derive(Parser)]
-pub struct KillallOpts;
+pub struct KillallOpts {
+    /// Only kills the processes of this local network, leaving other local networks running.
+    #[arg(long)]
+    network: Option<String>,
+}
 
-pub fn exec(env: &dyn Environment, _: KillallOpts) -> DfxResult {
+pub fn exec(env: &dyn Environment, opts: KillallOpts) -> DfxResult {
+    if let Some(network) = opts.network {
+        return kill_network(env, network);
+    }
     let mut info = System::new();
     info.refresh_processes();
     let mut n = 0;
@@ -34,3 +43,40 @@ pub fn exec(env: &dyn Environment, _: KillallOpts) -> DfxResult {
     info!(env.get_logger(), "Killed {n} processes");
     Ok(())
 }
+
+/// Kills the `dfx start` process of the network and everything it started.
+fn kill_network(env: &dyn Environment, network: String) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        Some(network),
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let local_server_descriptor = network_descriptor.local_server_descriptor()?;
+    let mut info = System::new();
+    info.refresh_processes();
+    let mut n = 0;
+    if let Some(proc) =
+        running_dfx_pid(&info, local_server_descriptor).and_then(|pid| info.process(pid))
+    {
+        // descendants are listed before their parents, so the dfx process is killed last
+        let mut pids = descendant_pids(&info, proc);
+        // first, kill the dfx process, so it can't restart the child processes
+        pids.rotate_right(1);
+        for pid in pids {
+            if let Some(proc) = info.process(pid) {
+                n += 1;
+                proc.kill();
+            }
+        }
+    }
+    for pid_path in local_server_descriptor.dfx_pid_paths() {
+        let _ = std::fs::remove_file(pid_path);
+    }
+    info!(
+        env.get_logger(),
+        "Killed {n} processes of network '{}'", network_descriptor.name
+    );
+    Ok(())
+}
@@ -5,7 +5,7 @@ use crate::lib::nns_types::icpts::ICPTs;
 use crate::lib::operations::ledger;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::icrc_subaccount_parser;
-use candid::Principal;
+use candid::{Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1::account::Subaccount;
 use slog::{info, warn};
@@ -81,9 +81,10 @@ pub async fn exec(env: &dyn Environment, opts: ApproveOpts) -> DfxResult {
         opts.from_subaccount,
         opts.spender,
         opts.spender_subaccount,
-        opts.amount,
-        opts.expected_allowance,
-        opts.fee,
+        Nat::from(opts.amount.get_e8s()),
+        opts.expected_allowance
+            .map(|allowance| Nat::from(allowance.get_e8s())),
+        opts.fee.map(|fee| Nat::from(fee.get_e8s())),
         created_at_time,
         opts.expires_at,
         opts.memo,
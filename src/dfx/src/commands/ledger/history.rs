@@ -0,0 +1,29 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::ledger_types::MAINNET_LEDGER_CANISTER_ID;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::util::clap::history_opt::HistoryOpt;
+use candid::Principal;
+use clap::Parser;
+
+/// Prints past transactions of the ledger, by default those of the current identity.
+/// Reads the ICRC-3 blocks of the ledger, or the transactions of an index canister.
+#[derive(Parser)]
+pub struct HistoryOpts {
+    #[command(flatten)]
+    history: HistoryOpt,
+
+    /// Canister ID of the ledger canister.
+    #[arg(long)]
+    ledger_canister_id: Option<Principal>,
+}
+
+pub async fn exec(env: &dyn Environment, opts: HistoryOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let canister_id = opts
+        .ledger_canister_id
+        .unwrap_or(MAINNET_LEDGER_CANISTER_ID);
+
+    opts.history.print_transactions(env, canister_id).await
+}
@@ -3,16 +3,20 @@ use crate::lib::environment::Environment;
 use crate::lib::error::{DfxResult, NotifyTopUpError::Notify};
 use crate::lib::ledger_types::Memo;
 use crate::lib::ledger_types::NotifyError::Refunded;
+use crate::lib::ledger_types::MAINNET_LEDGER_CANISTER_ID;
 use crate::lib::nns_types::account_identifier::Subaccount;
 use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
-use crate::lib::operations::cmc::{notify_top_up, transfer_cmc};
+use crate::lib::operations::cmc::{notify_top_up, transfer_cmc, transfer_cmc_call};
 use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::sign::signed_bundle::FollowUp;
 use crate::util::clap::parsers::e8s_parser;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use anyhow::{bail, Context};
 use candid::Principal;
 use clap::Parser;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-const MEMO_TOP_UP_CANISTER: u64 = 1347768404_u64;
+pub const MEMO_TOP_UP_CANISTER: u64 = 1347768404_u64;
 
 /// Top up a canister with cycles minted from ICP
 #[derive(Parser)]
@@ -49,6 +53,10 @@ pub struct TopUpOpts {
     /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time. // https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
     #[arg(long)]
     created_at_time: Option<u64>,
+
+    // `dfx send` sends the signed transfer and then notifies the cycles minting canister of it.
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
@@ -67,6 +75,30 @@ pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
             )
         })?;
 
+    if opts.sign_only.is_sign_only() {
+        let created_at_time = opts.created_at_time.unwrap_or(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64,
+        );
+        let (method_name, arg) =
+            transfer_cmc_call(memo, amount, fee, opts.from_subaccount, to, created_at_time)?;
+        let description = format!(
+            "Top up canister {to} with cycles minted from {amount}, created at time {created_at_time}."
+        );
+        return opts.sign_only.sign_update(
+            env,
+            description,
+            MAINNET_LEDGER_CANISTER_ID,
+            method_name,
+            arg,
+            Some(FollowUp::NotifyTopUp {
+                canister_id: to.to_text(),
+            }),
+        );
+    }
+
     let agent = env.get_agent();
 
     fetch_root_key_if_needed(env).await?;
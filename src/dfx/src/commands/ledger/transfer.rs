@@ -4,11 +4,14 @@ use crate::lib::error::DfxResult;
 use crate::lib::ledger_types::{Memo, MAINNET_LEDGER_CANISTER_ID};
 use crate::lib::nns_types::account_identifier::{AccountIdentifier, Subaccount};
 use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
-use crate::lib::operations::ledger::{icrc1_transfer, transfer};
+use crate::lib::operations::ledger::{
+    icrc1_transfer, icrc1_transfer_call, transfer, transfer_call,
+};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::{e8s_parser, icrc_subaccount_parser, memo_parser};
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use anyhow::{anyhow, Context};
-use candid::Principal;
+use candid::{Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1;
 use std::str::FromStr;
@@ -61,19 +64,26 @@ pub struct TransferOpts {
     /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time. // https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
     #[arg(long)]
     created_at_time: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
     let amount = get_icpts_from_args(opts.amount, opts.icp, opts.e8s)?;
 
-    let agent = env.get_agent();
-
-    fetch_root_key_if_needed(env).await?;
-
     let canister_id = opts
         .ledger_canister_id
         .unwrap_or(MAINNET_LEDGER_CANISTER_ID);
 
+    if opts.sign_only.is_sign_only() {
+        return sign_transfer(env, &opts, amount, canister_id);
+    }
+
+    let agent = env.get_agent();
+
+    fetch_root_key_if_needed(env).await?;
+
     if let Some(to) = opts.to {
         let fee = opts.fee.unwrap_or(TRANSACTION_FEE);
         let memo = Memo(opts.memo);
@@ -119,8 +129,8 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
             &canister_id,
             opts.from_subaccount.map(|s| s.0),
             to,
-            amount,
-            opts.fee,
+            Nat::from(amount.get_e8s()),
+            opts.fee.map(|fee| Nat::from(fee.get_e8s())),
             Some(opts.memo),
             created_at_time,
         )
@@ -146,3 +156,58 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
 
     Ok(())
 }
+
+/// Signs the transfer for `dfx send`, without contacting the network.
+fn sign_transfer(
+    env: &dyn Environment,
+    opts: &TransferOpts,
+    amount: ICPTs,
+    canister_id: Principal,
+) -> DfxResult {
+    let created_at_time = opts.created_at_time.unwrap_or(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+    let ((method_name, arg), to) = if let Some(to) = &opts.to {
+        let to = AccountIdentifier::from_str(to)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("Failed to parse transfer destination from string '{to}'."))?;
+        (
+            transfer_call(
+                Memo(opts.memo),
+                amount,
+                opts.fee.unwrap_or(TRANSACTION_FEE),
+                opts.from_subaccount,
+                to.to_address(),
+                created_at_time,
+            )?,
+            to.to_string(),
+        )
+    } else if let Some(to) = opts.to_principal {
+        let to = icrc1::account::Account {
+            owner: to,
+            subaccount: opts.to_subaccount,
+        };
+        (
+            icrc1_transfer_call(
+                opts.from_subaccount.map(|s| s.0),
+                to,
+                Nat::from(amount.get_e8s()),
+                opts.fee.map(|fee| Nat::from(fee.get_e8s())),
+                Some(opts.memo),
+                created_at_time,
+            )?,
+            to.to_string(),
+        )
+    } else {
+        return Err(anyhow!("Please provide the transfer destination."));
+    };
+    let description = format!(
+        "Transfer {amount} to {to} with memo {} on ledger {canister_id}, created at time {created_at_time}.",
+        opts.memo
+    );
+    opts.sign_only
+        .sign_update(env, description, canister_id, method_name, arg, None)
+}
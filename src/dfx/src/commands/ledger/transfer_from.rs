@@ -4,7 +4,7 @@ use crate::lib::nns_types::icpts::ICPTs;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::lib::{environment::Environment, operations::ledger};
 use crate::util::clap::parsers::icrc_subaccount_parser;
-use candid::Principal;
+use candid::{Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1::{self, account::Subaccount};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -87,8 +87,8 @@ pub async fn exec(env: &dyn Environment, opts: TransferFromOpts) -> DfxResult {
         opts.spender_subaccount,
         from,
         to,
-        opts.amount,
-        opts.fee,
+        Nat::from(opts.amount.get_e8s()),
+        opts.fee.map(|fee| Nat::from(fee.get_e8s())),
         created_at_time,
         opts.memo,
     )
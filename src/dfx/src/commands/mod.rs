@@ -5,6 +5,7 @@ use anyhow::bail;
 use clap::Subcommand;
 
 mod beta;
+mod bitcoin;
 mod build;
 mod cache;
 mod canister;
@@ -24,12 +25,15 @@ mod language_service;
 mod ledger;
 mod new;
 mod ping;
+mod pocketic;
 mod quickstart;
 mod remote;
 mod schema;
+mod send;
 mod send_telemetry;
 mod start;
 mod stop;
+mod token;
 mod toolchain;
 mod upgrade;
 mod wallet;
@@ -38,6 +42,7 @@ mod wallet;
 pub enum DfxCommand {
     #[command(hide = true)]
     Beta(beta::BetaOpts),
+    Bitcoin(bitcoin::BitcoinOpts),
     Build(build::CanisterBuildOpts),
     Cache(cache::CacheOpts),
     Canister(canister::CanisterOpts),
@@ -58,13 +63,16 @@ pub enum DfxCommand {
     Ledger(ledger::LedgerOpts),
     New(new::NewOpts),
     Ping(ping::PingOpts),
+    Pocketic(pocketic::PocketIcOpts),
     Quickstart(quickstart::QuickstartOpts),
     Remote(remote::RemoteOpts),
     Schema(schema::SchemaOpts),
+    Send(send::SendOpts),
     #[command(name = "_send-telemetry", hide = true)]
     SendTelemetry(send_telemetry::SendTelemetryOpts),
     Start(start::StartOpts),
     Stop(stop::StopOpts),
+    Token(token::TokenOpts),
     #[command(hide = true)]
     Toolchain(toolchain::ToolchainOpts),
     #[command(hide = true)]
@@ -75,6 +83,7 @@ pub enum DfxCommand {
 pub fn exec(env: &dyn Environment, cmd: DfxCommand) -> DfxResult {
     match cmd {
         DfxCommand::Beta(v) => beta::exec(env, v),
+        DfxCommand::Bitcoin(v) => bitcoin::exec(env, v),
         DfxCommand::Build(v) => build::exec(env, v),
         DfxCommand::Cache(v) => cache::exec(env, v),
         DfxCommand::Canister(v) => canister::exec(env, v),
@@ -94,12 +103,15 @@ pub fn exec(env: &dyn Environment, cmd: DfxCommand) -> DfxResult {
         DfxCommand::Ledger(v) => ledger::exec(env, v),
         DfxCommand::New(v) => new::exec(env, v),
         DfxCommand::Ping(v) => ping::exec(env, v),
+        DfxCommand::Pocketic(v) => pocketic::exec(env, v),
         DfxCommand::Quickstart(v) => quickstart::exec(env, v),
         DfxCommand::Remote(v) => remote::exec(env, v),
         DfxCommand::Schema(v) => schema::exec(v),
+        DfxCommand::Send(v) => send::exec(env, v),
         DfxCommand::SendTelemetry(v) => send_telemetry::exec(v),
         DfxCommand::Start(v) => start::exec(env, v),
         DfxCommand::Stop(v) => stop::exec(env, v),
+        DfxCommand::Token(v) => token::exec(env, v),
         DfxCommand::Toolchain(v) => toolchain::exec(env, v),
         DfxCommand::Upgrade(v) => upgrade::exec(env, v),
         DfxCommand::Wallet(v) => wallet::exec(env, v),
@@ -0,0 +1,49 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::lib::replica::http_mock::read_outcall_log;
+use anyhow::bail;
+use clap::Parser;
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+
+/// Shows the HTTPS outcalls that were answered from the mock rules in `canister_http.mock`,
+/// since the local network was started.
+#[derive(Parser)]
+pub struct HttpOutcallsOpts {
+    /// Prints one JSON object per outcall, including the request headers and body.
+    #[arg(long)]
+    json: bool,
+}
+
+pub fn exec(env: &dyn Environment, opts: HttpOutcallsOpts, network: NetworkOpt) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network.to_network_name(),
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let local_server_descriptor = network_descriptor.local_server_descriptor()?;
+    if local_server_descriptor.canister_http.mock.is_none() {
+        bail!(
+            "HTTPS outcalls of network '{}' are not mocked. Set `canister_http.mock` in its configuration.",
+            network_descriptor.name
+        );
+    }
+    for entry in read_outcall_log(&local_server_descriptor.canister_http_mock_log_path())? {
+        if opts.json {
+            println!("{}", serde_json::to_string(&entry)?);
+        } else {
+            let result = match (entry.status, entry.rule) {
+                (Some(status), _) => status.to_string(),
+                (None, Some(_)) => "rejected".to_string(),
+                (None, None) => "rejected (no matching rule)".to_string(),
+            };
+            println!(
+                "{} {} {} -> {}",
+                entry.time, entry.method, entry.url, result
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use clap::Parser;
+
+mod http_outcalls;
+mod time;
+
+/// Commands to control the local PocketIC network.
+#[derive(Parser)]
+#[command(name = "pocketic")]
+pub struct PocketIcOpts {
+    #[command(flatten)]
+    network: NetworkOpt,
+
+    #[command(subcommand)]
+    subcmd: SubCommand,
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    HttpOutcalls(http_outcalls::HttpOutcallsOpts),
+    Time(time::TimeOpts),
+}
+
+pub fn exec(env: &dyn Environment, opts: PocketIcOpts) -> DfxResult {
+    match opts.subcmd {
+        SubCommand::HttpOutcalls(v) => http_outcalls::exec(env, v, opts.network),
+        SubCommand::Time(v) => time::exec(env, v, opts.network),
+    }
+}
@@ -0,0 +1,118 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::lib::replica::time::PocketIcTime;
+use clap::{Parser, Subcommand};
+use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
+use slog::info;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tokio::runtime::Runtime;
+
+/// Shows or changes the time of the local PocketIC instance.
+#[derive(Parser)]
+pub struct TimeOpts {
+    #[command(subcommand)]
+    subcmd: TimeSubcommand,
+}
+
+#[derive(Subcommand)]
+enum TimeSubcommand {
+    /// Shows the current time.
+    Show,
+    /// Sets the time. It cannot be set back.
+    Set {
+        /// An RFC 3339 timestamp such as `2030-01-01T00:00:00Z`, or nanoseconds since the UNIX epoch.
+        #[arg(value_parser = time_parser)]
+        time: SystemTime,
+    },
+    /// Moves the time forward.
+    Advance {
+        /// The duration to advance by, e.g. `90s`, `2h 30m` or `30days`.
+        #[arg(value_parser = humantime::parse_duration)]
+        duration: Duration,
+    },
+    /// Stops executing rounds and advancing time automatically.
+    /// Time then only changes with `set`, `advance` and `tick`.
+    Pause,
+    /// Resumes executing rounds and advancing time automatically.
+    Resume,
+    /// Executes rounds on all subnets. Useful while paused, e.g. to let timers fire after changing the time.
+    Tick {
+        /// The number of rounds to execute.
+        #[arg(default_value = "1")]
+        rounds: u64,
+    },
+}
+
+pub fn exec(env: &dyn Environment, opts: TimeOpts, network: NetworkOpt) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network.to_network_name(),
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let pocketic = PocketIcTime::connect(&network_descriptor)?;
+    let log = env.get_logger();
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    runtime.block_on(async {
+        match opts.subcmd {
+            TimeSubcommand::Show => {
+                println!("{}", format_time(pocketic.get_time().await?));
+            }
+            TimeSubcommand::Set { time } => {
+                pocketic.set_time(time).await?;
+                info!(log, "Time set to {}.", format_time(time));
+            }
+            TimeSubcommand::Advance { duration } => {
+                let time = pocketic.advance_time(duration).await?;
+                info!(log, "Time advanced to {}.", format_time(time));
+            }
+            TimeSubcommand::Pause => {
+                pocketic.pause().await?;
+                info!(log, "Paused automatic progress.");
+            }
+            TimeSubcommand::Resume => {
+                pocketic.resume().await?;
+                info!(log, "Resumed automatic progress.");
+            }
+            TimeSubcommand::Tick { rounds } => {
+                pocketic.tick(rounds).await?;
+                info!(log, "Executed {} round(s).", rounds);
+            }
+        }
+        Ok(())
+    })
+}
+
+fn format_time(time: SystemTime) -> String {
+    OffsetDateTime::from(time)
+        .format(&Rfc3339)
+        .expect("Failed to format timestamp")
+}
+
+fn time_parser(input: &str) -> Result<SystemTime, String> {
+    if let Ok(nanos) = input.parse::<u64>() {
+        return Ok(UNIX_EPOCH + Duration::from_nanos(nanos));
+    }
+    OffsetDateTime::parse(input, &Rfc3339)
+        .map(SystemTime::from)
+        .map_err(|_| {
+            "Must be an RFC 3339 timestamp such as 2030-01-01T00:00:00Z, or nanoseconds since the UNIX epoch.".to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_time() {
+        let expected = UNIX_EPOCH + Duration::from_secs(1_893_456_000);
+        assert_eq!(time_parser("2030-01-01T00:00:00Z"), Ok(expected));
+        assert_eq!(time_parser("1893456000000000000"), Ok(expected));
+        assert!(time_parser("tomorrow").is_err());
+    }
+}
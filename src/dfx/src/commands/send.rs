@@ -0,0 +1,230 @@
+use crate::commands::canister::call::get_effective_canister_id_of_call;
+use crate::lib::environment::Environment;
+use crate::lib::error::{DfxResult, NotifyTopUpError::Notify};
+use crate::lib::ledger_types::NotifyError::Refunded;
+use crate::lib::ledger_types::{TransferError, TransferResult};
+use crate::lib::operations::cmc::notify_top_up;
+use crate::lib::sign::signed_bundle::{FollowUp, SignedBundleV1};
+use crate::lib::sign::signed_message::SignedMessageV1;
+use anyhow::{anyhow, bail, Context};
+use candid::{Decode, IDLArgs, IDLValue, Principal};
+use clap::Parser;
+use ic_agent::agent::{CallResponse, RequestStatusResponse};
+use ic_agent::Agent;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::runtime::Runtime;
+
+/// Sends transactions that were signed offline with `--sign-only` or `dfx canister sign`.
+/// The files are sent in order, and sending stops at the first transaction that fails.
+#[derive(Parser)]
+pub struct SendOpts {
+    /// The files with the signed transactions.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Send the transactions without asking for confirmation.
+    #[arg(long, short)]
+    yes: bool,
+}
+
+pub fn exec(_env: &dyn Environment, opts: SendOpts) -> DfxResult {
+    let bundles = opts
+        .files
+        .iter()
+        .map(|file| SignedBundleV1::load(file).map(|bundle| (file, bundle)))
+        .collect::<DfxResult<Vec<_>>>()?;
+
+    eprintln!("Will send:");
+    for (file, bundle) in &bundles {
+        eprintln!("  [{}] {}", file.display(), bundle.description);
+        for message in &bundle.messages {
+            eprintln!(
+                "    {} call to {}.{} on {} by {}, expires at {}",
+                message.call_type,
+                message.canister_id,
+                message.method_name,
+                message.network,
+                message.sender,
+                message.expiration
+            );
+        }
+        if let Some(FollowUp::NotifyTopUp { canister_id }) = &bundle.follow_up {
+            eprintln!("    then notify the cycles minting canister to top up {canister_id}");
+        }
+    }
+    if !opts.yes {
+        // Not using dialoguer because it doesn't support non terminal env like bats e2e
+        eprintln!("\nOkay? [y/N]");
+        let mut input = String::new();
+        std::io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read stdin.")?;
+        if !["y", "yes"].contains(&input.to_lowercase().trim()) {
+            return Ok(());
+        }
+    }
+
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    runtime.block_on(async {
+        let mut agents = BTreeMap::new();
+        for (file, bundle) in bundles {
+            eprintln!("[{}] {}", file.display(), bundle.description);
+            let mut last_reply = None;
+            for message in &bundle.messages {
+                let agent = agent_for(&mut agents, message).await?;
+                let reply = send_message(agent, message).await?;
+                match IDLArgs::from_bytes(&reply) {
+                    Ok(idl) => {
+                        println!("{idl}");
+                        // The follow-up decides what to do with a failed transfer.
+                        if is_err_reply(&idl) && bundle.follow_up.is_none() {
+                            bail!("The transaction in [{}] failed.", file.display());
+                        }
+                    }
+                    Err(_) => println!("{}", hex::encode(&reply)),
+                }
+                last_reply = Some(reply);
+            }
+            if let Some(follow_up) = &bundle.follow_up {
+                let message = bundle.messages.last().unwrap();
+                let agent = agent_for(&mut agents, message).await?;
+                run_follow_up(agent, follow_up, &last_reply.unwrap()).await?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// An anonymous agent for the network of the message, shared by the messages of that network.
+async fn agent_for<'a>(
+    agents: &'a mut BTreeMap<String, Agent>,
+    message: &SignedMessageV1,
+) -> DfxResult<&'a Agent> {
+    if !agents.contains_key(&message.network) {
+        let agent = Agent::builder().with_url(&message.network).build()?;
+        if !message.is_ic {
+            agent.fetch_root_key().await?;
+        }
+        agents.insert(message.network.clone(), agent);
+    }
+    Ok(&agents[&message.network])
+}
+
+/// Sends a signed message and returns its reply, waiting for update calls to complete.
+pub(crate) async fn send_message(agent: &Agent, message: &SignedMessageV1) -> DfxResult<Vec<u8>> {
+    let content = hex::decode(&message.content).context("Failed to decode message content.")?;
+    let canister_id = Principal::from_text(&message.canister_id)
+        .with_context(|| format!("Failed to parse canister id {:?}.", message.canister_id))?;
+    let effective_canister_id =
+        get_effective_canister_id_of_call(canister_id, &message.method_name, &message.arg)?;
+    if message.call_type == "query" {
+        return agent
+            .query_signed(effective_canister_id, content)
+            .await
+            .with_context(|| format!("Query call to {canister_id} failed."));
+    }
+
+    let request_id = match agent
+        .update_signed(effective_canister_id, content)
+        .await
+        .with_context(|| format!("Update call to {canister_id} failed."))?
+    {
+        CallResponse::Response(reply) => return Ok(reply),
+        CallResponse::Poll(request_id) => request_id,
+    };
+    let Some(signed_request_status) = &message.signed_request_status else {
+        bail!(
+            "No signed request_status to read the result of request 0x{}.",
+            String::from(request_id)
+        );
+    };
+    let envelope =
+        hex::decode(signed_request_status).context("Failed to decode request_status envelope.")?;
+    loop {
+        let (response, _cert) = agent
+            .request_status_signed(&request_id, effective_canister_id, envelope.clone())
+            .await
+            .with_context(|| format!("Failed to read canister state of {canister_id}."))?;
+        match response {
+            RequestStatusResponse::Replied(reply) => return Ok(reply.arg),
+            RequestStatusResponse::Rejected(response) => bail!(
+                "Rejected ({:?}): {}",
+                response.reject_code,
+                response.reject_message
+            ),
+            RequestStatusResponse::Done => bail!(
+                "The result of request 0x{} is no longer available.",
+                String::from(request_id)
+            ),
+            RequestStatusResponse::Received
+            | RequestStatusResponse::Processing
+            | RequestStatusResponse::Unknown => {
+                if OffsetDateTime::now_utc() > message.expiration {
+                    bail!(
+                        "Request 0x{} expired before it completed. Check its status with `dfx canister request-status {} {}`.",
+                        String::from(request_id),
+                        String::from(request_id),
+                        canister_id
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+/// Whether the reply is the `Err` variant of a `Result`, as ledgers reply to failed transactions.
+fn is_err_reply(idl: &IDLArgs) -> bool {
+    matches!(
+        idl.args.as_slice(),
+        [IDLValue::Variant(variant)] if variant.0.id.get_id() == candid::idl_hash("Err")
+    )
+}
+
+async fn run_follow_up(agent: &Agent, follow_up: &FollowUp, last_reply: &[u8]) -> DfxResult {
+    match follow_up {
+        FollowUp::NotifyTopUp { canister_id } => {
+            let canister_id = Principal::from_text(canister_id)
+                .with_context(|| format!("Failed to parse canister id {canister_id:?}."))?;
+            let height = match Decode!(last_reply, TransferResult)
+                .context("Failed to decode transfer response.")?
+            {
+                Ok(height) => height,
+                Err(TransferError::TxDuplicate { duplicate_of }) => duplicate_of,
+                Err(err) => return Err(anyhow!(err)),
+            };
+            match notify_top_up(agent, canister_id, height).await {
+                Ok(cycles) => println!("Canister was topped up with {cycles} cycles!"),
+                Err(Notify(Refunded {
+                    reason,
+                    block_index,
+                })) => match block_index {
+                    Some(height) => {
+                        println!("Refunded at block height {height} with message: {reason}")
+                    }
+                    None => println!("Refunded with message: {reason}"),
+                },
+                Err(other) => bail!("{other:?}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn err_replies() {
+        let reply = |text: &str| candid_parser::parse_idl_args(text).unwrap();
+        assert!(is_err_reply(&reply(
+            "(variant { Err = variant { InsufficientFunds = record { balance = 0 : nat } } })"
+        )));
+        assert!(!is_err_reply(&reply("(variant { Ok = 42 : nat })")));
+        assert!(!is_err_reply(&reply("(42 : nat)")));
+    }
+}
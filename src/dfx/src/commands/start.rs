@@ -1,15 +1,18 @@
 use crate::actors::pocketic_proxy::{signals::PortReadySubscribe, PocketIcProxyConfig};
 use crate::actors::{
-    start_btc_adapter_actor, start_canister_http_adapter_actor, start_pocketic_actor,
-    start_pocketic_proxy_actor, start_post_start_actor, start_replica_actor,
-    start_shutdown_controller,
+    http_mock_rules_path, start_bitcoind_actor, start_btc_adapter_actor,
+    start_canister_http_adapter_actor, start_pocketic_actor, start_pocketic_proxy_actor,
+    start_post_start_actor, start_replica_actor, start_shutdown_controller,
 };
 use crate::config::dfx_version_str;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::info::replica_rev;
 use crate::lib::integrations::status::wait_for_integrations_initialized;
+use crate::lib::network::checkpoint::{CheckpointCommand, Checkpoints};
 use crate::lib::network::id::write_network_id;
+use crate::lib::network::process::running_dfx_pid;
+use crate::lib::replica::http_mock::HttpMock;
 use crate::lib::replica::status::ping_and_wait;
 use crate::util::get_reusable_socket_addr;
 use actix::Recipient;
@@ -19,11 +22,12 @@ use dfx_core::{
     config::model::{
         bitcoin_adapter, canister_http_adapter,
         local_server_descriptor::{LocalNetworkScopeDescriptor, LocalServerDescriptor},
-        network_descriptor::NetworkDescriptor,
+        network_descriptor::{NetworkDescriptor, NetworkTypeDescriptor},
         replica_config::{CachedConfig, ReplicaConfig},
         settings_digest::get_settings_digest,
     },
     fs,
+    identity::Identity,
     json::{load_json_file, save_json_file},
     network::provider::{create_network_descriptor, LocalBindDetermination},
 };
@@ -31,11 +35,11 @@ use fn_error_context::context;
 use os_str_bytes::{OsStrBytes, OsStringBytes};
 use slog::{info, warn, Logger};
 use std::io::Read;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use sysinfo::{Pid, System, SystemExt};
+use sysinfo::{System, SystemExt};
 use tokio::runtime::Runtime;
 
 /// Starts the local replica and a web server for the current project.
@@ -53,10 +57,20 @@ pub struct StartOpts {
     #[arg(long, env = "DFX_RUNNING_IN_BACKGROUND", hide = true)]
     running_in_background: bool,
 
+    /// The local network to start. By default, the `local` network is started.
+    /// Local networks other than `local` bind to a free port unless they configure `bind`.
+    #[arg(long)]
+    network: Option<String>,
+
     /// Cleans the state of the current project.
     #[arg(long)]
     clean: bool,
 
+    /// Saves the state of the stopped local network as a named checkpoint, restores it before starting, or lists checkpoints.
+    /// Usage: --checkpoint save <NAME>, --checkpoint restore <NAME>, --checkpoint list
+    #[arg(long, num_args = 1..=2, value_names = ["ACTION", "NAME"], conflicts_with = "clean")]
+    checkpoint: Vec<String>,
+
     /// Address of bitcoind node.  Implies --enable-bitcoin.
     #[arg(long, action = ArgAction::Append)]
     bitcoin_node: Vec<SocketAddr>,
@@ -65,6 +79,10 @@ pub struct StartOpts {
     #[arg(long)]
     enable_bitcoin: bool,
 
+    /// Runs a bitcoind node in regtest mode as part of the local network. Implies --enable-bitcoin.
+    #[arg(long)]
+    bitcoind: bool,
+
     /// enable canister http requests (on by default for --pocketic)
     #[arg(long)]
     enable_canister_http: bool,
@@ -149,10 +167,13 @@ pub fn exec(
         host,
         background,
         running_in_background,
+        network,
         clean,
+        checkpoint,
         force,
         bitcoin_node,
         enable_bitcoin,
+        bitcoind,
         enable_canister_http,
         artificial_delay,
         domain,
@@ -178,7 +199,7 @@ pub fn exec(
     let network_descriptor = create_network_descriptor(
         project_config,
         env.get_networks_config(),
-        None,
+        network,
         network_descriptor_logger,
         LocalBindDetermination::AsConfigured,
     )?;
@@ -189,6 +210,7 @@ pub fn exec(
         host,
         enable_bitcoin,
         bitcoin_node,
+        bitcoind,
         enable_canister_http,
         domain,
         artificial_delay,
@@ -204,7 +226,28 @@ pub fn exec(
     // As we know no start process is running in this project, we can
     // clean up the state if it is necessary.
     if clean {
-        clean_state(local_server_descriptor, env.get_project_temp_dir()?)?;
+        clean_state(&network_descriptor, env.get_project_temp_dir()?)?;
+    }
+
+    if !checkpoint.is_empty() {
+        let checkpoints = Checkpoints::new(&network_descriptor, env.get_project_temp_dir()?);
+        match CheckpointCommand::parse(&checkpoint)? {
+            CheckpointCommand::Save(name) => {
+                checkpoints.save(&name)?;
+                info!(env.get_logger(), "Saved checkpoint '{}'.", name);
+                return Ok(());
+            }
+            CheckpointCommand::List => {
+                for checkpoint in checkpoints.list()? {
+                    println!("{checkpoint}");
+                }
+                return Ok(());
+            }
+            CheckpointCommand::Restore(name) => {
+                checkpoints.restore(&name)?;
+                info!(env.get_logger(), "Restored checkpoint '{}'.", name);
+            }
+        }
     }
 
     let (frontend_url, address_and_port) = frontend_address(local_server_descriptor, background)?;
@@ -237,6 +280,7 @@ pub fn exec(
     let pid_file_path = empty_writable_path(pid_file_path)?;
     let btc_adapter_pid_file_path =
         empty_writable_path(local_server_descriptor.btc_adapter_pid_path())?;
+    empty_writable_path(local_server_descriptor.bitcoind_pid_path())?;
     let btc_adapter_config_path =
         empty_writable_path(local_server_descriptor.btc_adapter_config_path())?;
     let canister_http_adapter_pid_file_path =
@@ -248,6 +292,7 @@ pub fn exec(
     let pocketic_proxy_port_file_path =
         empty_writable_path(local_server_descriptor.pocketic_proxy_port_path())?;
     let webserver_port_path = empty_writable_path(local_server_descriptor.webserver_port_path())?;
+    empty_writable_path(local_server_descriptor.canister_http_mock_log_path())?;
 
     let previous_config_path = local_server_descriptor.effective_config_path();
 
@@ -303,6 +348,23 @@ pub fn exec(
         .replica
         .log_level
         .unwrap_or_default();
+    let subnets = local_server_descriptor.replica.subnets;
+    if replica && subnets.is_some() {
+        bail!("The replica does not support configuring `replica.subnets`. Remove it or use PocketIC.");
+    }
+    if replica && local_server_descriptor.system_canisters != Default::default() {
+        bail!("The replica does not support installing `system_canisters`. Remove them or use PocketIC.");
+    }
+    if let Some(rules_path) = http_mock_rules_path(env, local_server_descriptor)? {
+        if replica {
+            bail!("The replica does not support mocking HTTPS outcalls with `canister_http.mock`. Remove it or use PocketIC.");
+        }
+        // Fail early on invalid rules rather than in the background.
+        HttpMock::new(
+            rules_path,
+            local_server_descriptor.canister_http_mock_log_path(),
+        )?;
+    }
 
     let proxy_domains = local_server_descriptor
         .proxy
@@ -312,7 +374,8 @@ pub fn exec(
 
     let replica_config = {
         let replica_config =
-            ReplicaConfig::new(&state_root, subnet_type, log_level, artificial_delay);
+            ReplicaConfig::new(&state_root, subnet_type, log_level, artificial_delay)
+                .with_subnets(subnets);
         let mut replica_config = if let Some(port) = local_server_descriptor.replica.port {
             replica_config.with_port(port)
         } else {
@@ -364,6 +427,9 @@ pub fn exec(
     let _proxy = system.block_on(async move {
         let shutdown_controller = start_shutdown_controller(env)?;
 
+        // bitcoind is started first, so that the bitcoin adapter finds it.
+        start_bitcoind_actor(env, local_server_descriptor, shutdown_controller.clone())?;
+
         let port_ready_subscribe: Recipient<PortReadySubscribe> = if replica {
             let btc_adapter_ready_subscribe = btc_adapter_config
                 .map(|btc_adapter_config| {
@@ -401,6 +467,7 @@ pub fn exec(
             let server = start_pocketic_actor(
                 env,
                 replica_config,
+                &network_descriptor.name,
                 local_server_descriptor,
                 shutdown_controller.clone(),
                 pocketic_port_path,
@@ -493,6 +560,7 @@ pub fn apply_command_line_parameters(
     host: Option<String>,
     enable_bitcoin: bool,
     bitcoin_nodes: Vec<SocketAddr>,
+    bitcoind: bool,
     enable_canister_http: bool,
     domain: Vec<String>,
     artificial_delay: u32,
@@ -523,6 +591,18 @@ pub fn apply_command_line_parameters(
         local_server_descriptor = local_server_descriptor.with_bitcoin_nodes(bitcoin_nodes)
     }
 
+    if bitcoind && local_server_descriptor.bitcoin.bitcoind.is_none() {
+        local_server_descriptor = local_server_descriptor.with_bitcoind(Default::default());
+    }
+
+    if let Some(bitcoind) = local_server_descriptor.bitcoin.bitcoind.clone() {
+        local_server_descriptor = local_server_descriptor.with_bitcoin_enabled();
+        if local_server_descriptor.bitcoin.nodes.is_none() {
+            let node = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), bitcoind.port);
+            local_server_descriptor = local_server_descriptor.with_bitcoin_nodes(vec![node]);
+        }
+    }
+
     if !domain.is_empty() {
         local_server_descriptor = local_server_descriptor.with_proxy_domains(domain)
     }
@@ -543,10 +623,8 @@ pub fn apply_command_line_parameters(
 }
 
 #[context("Failed to clean existing replica state.")]
-fn clean_state(
-    local_server_descriptor: &LocalServerDescriptor,
-    temp_dir: Option<PathBuf>,
-) -> DfxResult {
+fn clean_state(network_descriptor: &NetworkDescriptor, temp_dir: Option<PathBuf>) -> DfxResult {
+    let local_server_descriptor = network_descriptor.local_server_descriptor()?;
     if local_server_descriptor.data_directory.is_dir() {
         fs::remove_dir_all(&local_server_descriptor.data_directory).with_context(|| {
             format!(
@@ -557,13 +635,28 @@ fn clean_state(
     }
 
     if let Some(temp_dir) = temp_dir {
-        let local_dir = temp_dir.join("local");
+        let local_dir = temp_dir.join(&network_descriptor.name);
         if local_dir.is_dir() {
             fs::remove_dir_all(&local_dir).with_context(|| {
                 format!("Cannot remove directory at '{}'.", local_dir.display())
             })?;
         }
     }
+
+    // The wallets of all project networks are kept in `.dfx/local`, so removing the
+    // directory of a network other than `local` leaves its wallets behind.
+    if let NetworkTypeDescriptor::Ephemeral {
+        wallet_config_path: Some(wallet_config_path),
+    } = &network_descriptor.r#type
+    {
+        if wallet_config_path.is_file() {
+            let mut config = Identity::load_wallet_config(wallet_config_path)?;
+            for wallets in config.identities.values_mut() {
+                wallets.networks.remove(&network_descriptor.name);
+            }
+            Identity::save_wallet_config(wallet_config_path, &config)?;
+        }
+    }
     Ok(())
 }
 
@@ -574,18 +667,35 @@ fn send_background() -> DfxResult<()> {
     let exe = std::env::current_exe().context("Failed to get current executable.")?;
     let mut cmd = Command::new(exe);
     // Skip 1 because arg0 is this executable's path.
-    cmd.args(
-        std::env::args()
-            .skip(1)
-            .filter(|a| !a.eq("--background"))
-            .filter(|a| !a.eq("--clean")),
-    )
-    .env("DFX_RUNNING_IN_BACKGROUND", "true"); // Set the `DFX_RUNNING_IN_BACKGROUND` environment variable which will be used by the second start.
+    cmd.args(background_args(std::env::args().skip(1)))
+        .env("DFX_RUNNING_IN_BACKGROUND", "true"); // Set the `DFX_RUNNING_IN_BACKGROUND` environment variable which will be used by the second start.
 
     cmd.spawn().context("Failed to spawn child process.")?;
     Ok(())
 }
 
+/// The arguments of the background process: the state was already cleaned or restored
+/// by this process, so `--clean` and `--checkpoint <ACTION> [NAME]` are left out.
+fn background_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut result = vec![];
+    let mut checkpoint_values = 0;
+    for arg in args {
+        if checkpoint_values > 0 && !arg.starts_with('-') {
+            checkpoint_values -= 1;
+            continue;
+        }
+        checkpoint_values = 0;
+        if arg == "--checkpoint" {
+            checkpoint_values = 2;
+        } else if arg.starts_with("--checkpoint=") {
+            checkpoint_values = 1;
+        } else if arg != "--background" && arg != "--clean" {
+            result.push(arg);
+        }
+    }
+    result
+}
+
 #[context("Failed to get frontend address.")]
 fn frontend_address(
     local_server_descriptor: &LocalServerDescriptor,
@@ -612,20 +722,11 @@ fn frontend_address(
 fn check_previous_process_running(
     local_server_descriptor: &LocalServerDescriptor,
 ) -> DfxResult<()> {
-    for pid_path in local_server_descriptor.dfx_pid_paths() {
-        if pid_path.exists() {
-            // Read and verify it's not running. If it is just return.
-            if let Ok(s) = std::fs::read_to_string(&pid_path) {
-                if let Ok(pid) = s.parse::<Pid>() {
-                    // If we find the pid in the file, we tell the user and don't start!
-                    let mut system = System::new();
-                    system.refresh_processes();
-                    if let Some(_process) = system.process(pid) {
-                        bail!("dfx is already running.");
-                    }
-                }
-            }
-        }
+    let mut system = System::new();
+    system.refresh_processes();
+    // If we find a running process in the pid file, we tell the user and don't start!
+    if running_dfx_pid(&system, local_server_descriptor).is_some() {
+        bail!("dfx is already running.");
     }
     Ok(())
 }
@@ -1,34 +1,18 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::network::process::descendant_pids;
 use anyhow::bail;
 use clap::Parser;
 use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
 use std::time::Duration;
-use sysinfo::{Pid, Process, ProcessExt, Signal, System, SystemExt};
+use sysinfo::{Pid, ProcessExt, Signal, System, SystemExt};
 
 /// Stops the local network replica.
 #[derive(Parser)]
-pub struct StopOpts {}
-
-fn list_all_descendants<'a>(system: &'a System, proc: &'a Process) -> Vec<&'a Process> {
-    let mut result = Vec::new();
-
-    for process in system.processes().values() {
-        if let Some(ppid) = process.parent() {
-            if ppid == proc.pid() {
-                result.extend(list_all_descendants(system, process));
-            }
-        }
-    }
-    result.push(proc);
-
-    result
-}
-
-/// Recursively list all descendants of a process.
-fn descendant_pids(system: &System, proc: &Process) -> Vec<Pid> {
-    let processes = list_all_descendants(system, proc);
-    processes.iter().map(|proc| proc.pid()).collect()
+pub struct StopOpts {
+    /// The local network to stop. By default, the `local` network is stopped.
+    #[arg(long)]
+    network: Option<String>,
 }
 
 fn wait_until_all_exited(mut system: System, mut pids: Vec<Pid>) -> DfxResult {
@@ -55,11 +39,11 @@ fn wait_until_all_exited(mut system: System, mut pids: Vec<Pid>) -> DfxResult {
     }
 }
 
-pub fn exec(env: &dyn Environment, _opts: StopOpts) -> DfxResult {
+pub fn exec(env: &dyn Environment, opts: StopOpts) -> DfxResult {
     let network_descriptor = create_network_descriptor(
         env.get_config()?,
         env.get_networks_config(),
-        None,
+        opts.network,
         Some(env.get_logger().clone()),
         LocalBindDetermination::AsConfigured,
     )?;
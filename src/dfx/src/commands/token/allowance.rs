@@ -0,0 +1,57 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::ledger;
+use crate::lib::token::IcrcToken;
+use crate::util::clap::parsers::icrc_subaccount_parser;
+use candid::Principal;
+use clap::Parser;
+use icrc_ledger_types::icrc1::{self, account::Subaccount};
+
+/// Get the number of tokens that the spender account can transfer from the owner account.
+#[derive(Parser)]
+pub struct AllowanceOpts {
+    /// Specifies a owner principal to get the allowance of.
+    /// If not specified, the principal of the current identity is used.
+    #[arg(long)]
+    owner: Option<Principal>,
+
+    /// Subaccount of the owner principal to get the allowance of.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    owner_subaccount: Option<Subaccount>,
+
+    /// Specifies a spender principal to get the allowance of.
+    spender: Principal,
+
+    /// Subaccount of the spender principal to get the allowance of.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    spender_subaccount: Option<Subaccount>,
+}
+
+pub async fn exec(env: &dyn Environment, token: &IcrcToken, opts: AllowanceOpts) -> DfxResult {
+    let owner = opts.owner.unwrap_or_else(|| {
+        env.get_selected_identity_principal()
+            .expect("Selected identity not instantiated.")
+    });
+
+    let owner = icrc1::account::Account {
+        owner,
+        subaccount: opts.owner_subaccount,
+    };
+    let spender = icrc1::account::Account {
+        owner: opts.spender,
+        subaccount: opts.spender_subaccount,
+    };
+
+    let allowance =
+        ledger::icrc2_allowance(env.get_agent(), &token.canister_id, owner, spender).await?;
+
+    match allowance.expires_at {
+        Some(expires_at) => println!(
+            "{} (expires at {expires_at})",
+            token.format_amount(&allowance.allowance)
+        ),
+        None => println!("{}", token.format_amount(&allowance.allowance)),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::ledger;
+use crate::lib::token::IcrcToken;
+use crate::util::clap::parsers::icrc_subaccount_parser;
+use candid::Principal;
+use clap::Parser;
+use icrc_ledger_types::icrc1::account::Subaccount;
+use slog::{info, warn};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Approve a principal to spend tokens on your behalf.
+#[derive(Parser)]
+pub struct ApproveOpts {
+    /// Allow this principal to spend tokens.
+    spender: Principal,
+
+    /// The number of tokens to approve, with up to as many decimals as the token has, i.e. 1.25
+    amount: String,
+
+    /// Allow this subaccount to spend tokens.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    spender_subaccount: Option<Subaccount>,
+
+    /// Approve tokens to be spent from this subaccount.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    from_subaccount: Option<Subaccount>,
+
+    /// The number of previously approved tokens.
+    /// See https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-2/README.md for details.
+    #[arg(long)]
+    expected_allowance: Option<String>,
+
+    /// Transaction fee. If not specified, the ledger charges its fee.
+    #[arg(long)]
+    fee: Option<String>,
+
+    /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time.
+    /// https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
+    #[arg(long)]
+    created_at_time: Option<u64>,
+
+    /// Timestamp until which the approval is valid. None means that the approval is valid indefinitely.
+    #[arg(long)]
+    expires_at: Option<u64>,
+
+    /// Memo.
+    #[arg(long)]
+    memo: Option<u64>,
+}
+
+pub async fn exec(env: &dyn Environment, token: &IcrcToken, opts: ApproveOpts) -> DfxResult {
+    let amount = token.parse_amount(&opts.amount)?;
+    let expected_allowance = opts
+        .expected_allowance
+        .as_deref()
+        .map(|allowance| token.parse_amount(allowance))
+        .transpose()?;
+    let fee = opts
+        .fee
+        .as_deref()
+        .map(|fee| token.parse_amount(fee))
+        .transpose()?;
+
+    let created_at_time = opts.created_at_time.unwrap_or(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+
+    let result = ledger::icrc2_approve(
+        env.get_agent(),
+        env.get_logger(),
+        &token.canister_id,
+        opts.from_subaccount,
+        opts.spender,
+        opts.spender_subaccount,
+        amount.clone(),
+        expected_allowance,
+        fee,
+        created_at_time,
+        opts.expires_at,
+        opts.memo,
+    )
+    .await;
+
+    if result.is_err() && opts.created_at_time.is_none() {
+        warn!(
+            env.get_logger(),
+            "If you retry this operation, use --created-at-time {}", created_at_time
+        );
+    }
+    let block_index = result?;
+
+    info!(
+        env.get_logger(),
+        "Approved {} for {} at block index {}",
+        token.format_amount(&amount),
+        opts.spender,
+        block_index
+    );
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::ledger;
+use crate::lib::token::IcrcToken;
+use crate::util::clap::parsers::icrc_subaccount_parser;
+use candid::{Nat, Principal};
+use clap::Parser;
+use icrc_ledger_types::icrc1::{self, account::Subaccount};
+
+/// Prints the token balance of an account.
+#[derive(Parser)]
+pub struct BalanceOpts {
+    /// Specifies a principal to get the balance of.
+    /// If not specified, the principal of the current identity is used.
+    #[arg(long)]
+    of: Option<Principal>,
+
+    /// Subaccount to get the balance of.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    subaccount: Option<Subaccount>,
+
+    /// Print the balance in the smallest unit of the token.
+    #[arg(long)]
+    precise: bool,
+}
+
+pub async fn exec(env: &dyn Environment, token: &IcrcToken, opts: BalanceOpts) -> DfxResult {
+    let owner = opts.of.unwrap_or_else(|| {
+        env.get_selected_identity_principal()
+            .expect("Selected identity not instantiated.")
+    });
+    let account = icrc1::account::Account {
+        owner,
+        subaccount: opts.subaccount,
+    };
+
+    let balance =
+        Nat::from(ledger::icrc1_balance(env.get_agent(), &token.canister_id, account).await?);
+
+    if opts.precise {
+        println!("{}", balance.0);
+    } else {
+        println!("{}", token.format_amount(&balance));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::lib::error::DfxResult;
+use crate::lib::token::{format_metadata_value, IcrcToken};
+use clap::Parser;
+
+/// Prints the metadata of the ledger, like its name, symbol, decimals and fee.
+#[derive(Parser)]
+pub struct MetadataOpts {}
+
+pub fn exec(token: &IcrcToken, _opts: MetadataOpts) -> DfxResult {
+    for (key, value) in &token.metadata {
+        println!("{key}: {}", format_metadata_value(value));
+    }
+    Ok(())
+}
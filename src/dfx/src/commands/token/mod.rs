@@ -0,0 +1,63 @@
+use crate::lib::agent::create_agent_environment;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::token::IcrcToken;
+use candid::Principal;
+use clap::Parser;
+use tokio::runtime::Runtime;
+
+mod allowance;
+mod approve;
+mod balance;
+mod metadata;
+mod transfer;
+mod transfer_from;
+
+/// Commands to use the tokens of any ICRC-1 ledger, like ckBTC or SNS tokens.
+/// Amounts are given and shown in the decimals that the ledger reports in its metadata.
+#[derive(Parser)]
+#[command(name = "token")]
+pub struct TokenOpts {
+    #[command(flatten)]
+    network: NetworkOpt,
+
+    /// The ledger canister of the token, as a canister ID or the name of a canister of the project.
+    #[arg(long)]
+    ledger: String,
+
+    #[command(subcommand)]
+    subcmd: SubCommand,
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    Allowance(allowance::AllowanceOpts),
+    Approve(approve::ApproveOpts),
+    Balance(balance::BalanceOpts),
+    Metadata(metadata::MetadataOpts),
+    Transfer(transfer::TransferOpts),
+    TransferFrom(transfer_from::TransferFromOpts),
+}
+
+pub fn exec(env: &dyn Environment, opts: TokenOpts) -> DfxResult {
+    let agent_env = create_agent_environment(env, opts.network.to_network_name())?;
+    let canister_id = match Principal::from_text(&opts.ledger) {
+        Ok(canister_id) => canister_id,
+        Err(_) => agent_env.get_canister_id_store()?.get(&opts.ledger)?,
+    };
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    runtime.block_on(async {
+        fetch_root_key_if_needed(&agent_env).await?;
+        let token = IcrcToken::fetch(agent_env.get_agent(), canister_id).await?;
+        match opts.subcmd {
+            SubCommand::Allowance(v) => allowance::exec(&agent_env, &token, v).await,
+            SubCommand::Approve(v) => approve::exec(&agent_env, &token, v).await,
+            SubCommand::Balance(v) => balance::exec(&agent_env, &token, v).await,
+            SubCommand::Metadata(v) => metadata::exec(&token, v),
+            SubCommand::Transfer(v) => transfer::exec(&agent_env, &token, v).await,
+            SubCommand::TransferFrom(v) => transfer_from::exec(&agent_env, &token, v).await,
+        }
+    })
+}
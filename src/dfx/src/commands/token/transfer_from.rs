@@ -0,0 +1,107 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::ledger;
+use crate::lib::token::IcrcToken;
+use crate::util::clap::parsers::icrc_subaccount_parser;
+use candid::Principal;
+use clap::Parser;
+use icrc_ledger_types::icrc1::{self, account::Subaccount};
+use slog::{info, warn};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Transfer tokens from an account that approved the current identity to spend them.
+#[derive(Parser)]
+pub struct TransferFromOpts {
+    /// Transfer tokens from this principal.
+    #[arg(long)]
+    from: Principal,
+
+    /// Transfer tokens from this subaccount.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    from_subaccount: Option<Subaccount>,
+
+    /// Transfer tokens to this principal.
+    to: Principal,
+
+    /// The number of tokens to transfer, with up to as many decimals as the token has, i.e. 1.25
+    amount: String,
+
+    /// Transfer tokens to this subaccount.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    to_subaccount: Option<Subaccount>,
+
+    /// Deduct allowance from this subaccount.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    spender_subaccount: Option<Subaccount>,
+
+    /// Transaction fee. If not specified, the ledger charges its fee.
+    #[arg(long)]
+    fee: Option<String>,
+
+    /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time.
+    /// https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
+    #[arg(long)]
+    created_at_time: Option<u64>,
+
+    /// Memo.
+    #[arg(long)]
+    memo: Option<u64>,
+}
+
+pub async fn exec(env: &dyn Environment, token: &IcrcToken, opts: TransferFromOpts) -> DfxResult {
+    let amount = token.parse_amount(&opts.amount)?;
+    let fee = opts
+        .fee
+        .as_deref()
+        .map(|fee| token.parse_amount(fee))
+        .transpose()?;
+
+    let created_at_time = opts.created_at_time.unwrap_or(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+
+    let from = icrc1::account::Account {
+        owner: opts.from,
+        subaccount: opts.from_subaccount,
+    };
+    let to = icrc1::account::Account {
+        owner: opts.to,
+        subaccount: opts.to_subaccount,
+    };
+
+    let result = ledger::icrc2_transfer_from(
+        env.get_agent(),
+        env.get_logger(),
+        &token.canister_id,
+        opts.spender_subaccount,
+        from,
+        to,
+        amount.clone(),
+        fee,
+        created_at_time,
+        opts.memo,
+    )
+    .await;
+
+    if result.is_err() && opts.created_at_time.is_none() {
+        warn!(
+            env.get_logger(),
+            "If you retry this operation, use --created-at-time {}", created_at_time
+        );
+    }
+    let block_index = result?;
+
+    info!(
+        env.get_logger(),
+        "Transferred {} from {} to {} at block index {}",
+        token.format_amount(&amount),
+        from,
+        to,
+        block_index
+    );
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use crate::lib::environment::AgentEnvironment;
 use crate::lib::error::DfxResult;
 use crate::Environment;
+use anyhow::Context;
+use dfx_core::config::model::dfinity::ConfigNetworkIdentities;
+use dfx_core::identity::identity_manager::IdentityManager;
 use dfx_core::identity::ANONYMOUS_IDENTITY_NAME;
 use dfx_core::network::provider::{create_network_descriptor, LocalBindDetermination};
 
@@ -19,8 +22,10 @@ pub fn create_agent_environment<'a>(
         None,
         LocalBindDetermination::ApplyRunningWebserverPort,
     )?;
+    let mut identity_manager = env.new_identity_manager()?;
+    apply_network_identities(env, &mut identity_manager, &network_descriptor.name)?;
     let timeout = expiry_duration();
-    AgentEnvironment::new(env, network_descriptor, timeout, None)
+    AgentEnvironment::new(env, network_descriptor, timeout, identity_manager)
 }
 
 pub fn create_anonymous_agent_environment<'a>(
@@ -34,11 +39,71 @@ pub fn create_anonymous_agent_environment<'a>(
         None,
         LocalBindDetermination::ApplyRunningWebserverPort,
     )?;
+    let mut identity_manager = env.new_identity_manager()?;
+    identity_manager.select_identity(ANONYMOUS_IDENTITY_NAME);
     let timeout = expiry_duration();
-    AgentEnvironment::new(
-        env,
-        network_descriptor,
-        timeout,
-        Some(ANONYMOUS_IDENTITY_NAME),
-    )
+    AgentEnvironment::new(env, network_descriptor, timeout, identity_manager)
+}
+
+/// Creates an identity manager that follows the identities of a network, for commands that
+/// use identities without an agent for the network.
+pub fn create_network_identity_manager(
+    env: &dyn Environment,
+    network: Option<String>,
+) -> DfxResult<IdentityManager> {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network,
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let mut identity_manager = env.new_identity_manager()?;
+    apply_network_identities(env, &mut identity_manager, &network_descriptor.name)?;
+    Ok(identity_manager)
+}
+
+/// Selects the default identity of a network, unless `--identity` is specified,
+/// and restricts the identity manager to the identities that the network allows.
+fn apply_network_identities(
+    env: &dyn Environment,
+    identity_manager: &mut IdentityManager,
+    network_name: &str,
+) -> DfxResult {
+    let identities = get_network_identities(env, network_name)?;
+    // `--identity` takes precedence over the default identity of the network.
+    if let Some(name) = identities
+        .default
+        .as_deref()
+        .filter(|_| env.get_identity_override().is_none())
+    {
+        identity_manager
+            .require_identity_exists(env.get_logger(), name)
+            .with_context(|| {
+                format!("The default identity of network '{network_name}' does not exist.")
+            })?;
+        identity_manager.select_identity(name);
+    }
+    identity_manager.restrict_to_network(network_name, identities);
+    Ok(())
+}
+
+/// The identities of a network in dfx.json, or else in networks.json.
+/// The built-in `ic` network can be listed in either file only for its identities.
+fn get_network_identities(
+    env: &dyn Environment,
+    network_name: &str,
+) -> DfxResult<ConfigNetworkIdentities> {
+    let project_network = env
+        .get_config()?
+        .and_then(|config| config.get_config().get_network(network_name).cloned());
+    let network = project_network.or_else(|| {
+        env.get_networks_config()
+            .get_interface()
+            .get_network(network_name)
+            .cloned()
+    });
+    Ok(network
+        .and_then(|network| network.identities().cloned())
+        .unwrap_or_default())
 }
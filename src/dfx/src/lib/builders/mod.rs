@@ -6,13 +6,15 @@ use crate::lib::models::canister::CanisterPool;
 use crate::util::command::direct_or_shell_command;
 use crate::util::with_suspend_all_spinners;
 use anyhow::{bail, Context};
+use candid::types::{FuncMode, Type, TypeEnv};
 use candid::Principal as CanisterId;
 use candid_parser::utils::CandidSource;
-use dfx_core::config::model::dfinity::{Config, Profile};
+use dfx_core::config::model::dfinity::{Config, Profile, RustBindingsTarget};
 use dfx_core::network::provider::get_network_context;
 use dfx_core::util;
 use fn_error_context::context;
 use handlebars::Handlebars;
+use serde_json::json;
 use slog::{info, trace, Logger};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
@@ -20,7 +22,7 @@ use std::ffi::OsStr;
 use std::fmt::Write;
 use std::fs;
 use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 
@@ -158,6 +160,7 @@ pub trait CanisterBuilder {
         }
 
         let (env, ty) = CandidSource::File(did_from_build.as_path()).load()?;
+        let template_data = declarations_template_data(info, &env, &ty)?;
 
         // Typescript
         if bindings.contains(&"ts".to_string()) {
@@ -174,7 +177,7 @@ pub trait CanisterBuilder {
             })?;
             trace!(logger, "  {}", &output_did_ts_path.display());
 
-            compile_handlebars_files("ts", info, generate_output_dir)?;
+            compile_handlebars_files("ts", info, generate_output_dir, &template_data)?;
         }
 
         // Javascript
@@ -189,7 +192,7 @@ pub trait CanisterBuilder {
                 .with_context(|| format!("Failed to write to {}.", output_did_js_path.display()))?;
             trace!(logger, "  {}", &output_did_js_path.display());
 
-            compile_handlebars_files("js", info, generate_output_dir)?;
+            compile_handlebars_files("js", info, generate_output_dir, &template_data)?;
         }
 
         // Motoko
@@ -204,6 +207,19 @@ pub trait CanisterBuilder {
             trace!(logger, "  {}", &output_mo_path.display());
         }
 
+        // Rust
+        if bindings.contains(&"rs".to_string()) {
+            let output_rs_path = generate_output_dir
+                .join(info.get_name())
+                .with_extension("rs");
+            let config = rust_bindings_config(info);
+            let content =
+                ensure_trailing_newline(candid_parser::bindings::rust::compile(&config, &env, &ty));
+            std::fs::write(&output_rs_path, content)
+                .with_context(|| format!("Failed to write to {}.", output_rs_path.display()))?;
+            trace!(logger, "  {}", &output_rs_path.display());
+        }
+
         // Candid
         if bindings.contains(&"did".to_string()) {
             let output_did_path = generate_output_dir
@@ -214,6 +230,8 @@ pub trait CanisterBuilder {
             trace!(logger, "  {}", &output_did_path.display());
         }
 
+        compile_user_templates(logger, info, generate_output_dir, &template_data)?;
+
         spinner.finish_and_clear();
         info!(
             logger,
@@ -236,11 +254,37 @@ pub trait CanisterBuilder {
     ) -> DfxResult<PathBuf>;
 }
 
+/// Translates the `declarations.rust` options of a canister into the configuration of the Rust binding generator.
+fn rust_bindings_config(info: &CanisterInfo) -> candid_parser::bindings::rust::Config {
+    use candid_parser::bindings::rust::{Config, Target};
+
+    let options = info
+        .get_declarations_config()
+        .rust
+        .clone()
+        .unwrap_or_default();
+    let mut config = Config::new();
+    config.set_target(match options.target {
+        RustBindingsTarget::IcCdk => Target::CanisterCall,
+        RustBindingsTarget::IcAgent => Target::Agent,
+    });
+    if let Some(type_attributes) = options.type_attributes {
+        config.set_type_attributes(type_attributes);
+    }
+    if let Some(candid_crate) = options.candid_crate {
+        config.set_candid_crate(candid_crate);
+    }
+    config
+}
+
 fn compile_handlebars_files(
     lang: &str,
     info: &CanisterInfo,
     generate_output_dir: &Path,
+    data: &serde_json::Value,
 ) -> DfxResult {
+    let templates = info.get_declarations_config().templates.as_ref();
+
     // index.js
     let mut language_bindings = crate::util::assets::language_bindings()
         .context("Failed to get language bindings archive.")?;
@@ -258,8 +302,15 @@ fn compile_handlebars_files(
         let is_template = pathname
             .to_str()
             .is_some_and(|name| name.ends_with(&file_extension));
-
-        if is_template {
+        let output_name = pathname.with_extension("");
+        // A user-supplied template for the same file replaces the bundled one.
+        let is_overridden = templates.is_some_and(|templates| {
+            templates
+                .keys()
+                .any(|name| Path::new(name) == output_name.as_path())
+        });
+
+        if is_template && !is_overridden {
             let mut file_contents = String::new();
             file.read_to_string(&mut file_contents)
                 .context("Failed to read language bindings archive file content.")?;
@@ -267,55 +318,120 @@ fn compile_handlebars_files(
             // create the handlebars registry
             let handlebars = Handlebars::new();
 
-            let mut data: BTreeMap<String, &String> = BTreeMap::new();
-
-            let canister_name = &info.get_name().to_string();
-            let canister_name_ident = &canister_name.replace('-', "_");
+            let new_file_contents = handlebars.render_template(&file_contents, data).unwrap();
+            let new_path = generate_output_dir.join(output_name);
+            std::fs::write(&new_path, new_file_contents)
+                .with_context(|| format!("Failed to write to {}.", new_path.display()))?;
+        }
+    }
 
-            let node_compatibility = info.get_declarations_config().node_compatibility;
+    Ok(())
+}
 
-            // Insert only if node outputs are specified
-            let actor_export = if node_compatibility {
-                // leave empty for nodejs
-                "".to_string()
-            } else {
-                format!(
-                    r#"
+/// Renders the templates from `declarations.templates` in dfx.json into the declarations directory.
+fn compile_user_templates(
+    logger: &Logger,
+    info: &CanisterInfo,
+    generate_output_dir: &Path,
+    data: &serde_json::Value,
+) -> DfxResult {
+    let Some(templates) = &info.get_declarations_config().templates else {
+        return Ok(());
+    };
+
+    let mut handlebars = Handlebars::new();
+    // The templates produce source code, not HTML.
+    handlebars.register_escape_fn(handlebars::no_escape);
+    for (name, template) in templates {
+        let output_name = Path::new(name);
+        if !output_name
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!(
+                "Invalid file name '{}' in `{}.declarations.templates`: it must be a relative path inside the declarations directory.",
+                name,
+                info.get_name()
+            );
+        }
+        let template_path = info.get_workspace_root().join(template);
+        let template_contents = dfx_core::fs::read_to_string(&template_path)?;
+        let new_file_contents = handlebars
+            .render_template(&template_contents, data)
+            .with_context(|| format!("Failed to render template {}.", template_path.display()))?;
+        let new_path = generate_output_dir.join(output_name);
+        dfx_core::fs::composite::ensure_parent_dir_exists(&new_path)?;
+        std::fs::write(&new_path, new_file_contents)
+            .with_context(|| format!("Failed to write to {}.", new_path.display()))?;
+        trace!(logger, "  {}", &new_path.display());
+    }
 
-export const {canister_name_ident} = canisterId ? createActor(canisterId) : undefined;"#,
-                )
-                .to_string()
-            };
+    Ok(())
+}
 
-            data.insert("canister_name".to_string(), canister_name);
-            data.insert("canister_name_ident".to_string(), canister_name_ident);
-            data.insert("actor_export".to_string(), &actor_export);
+/// The data available to the handlebars templates of the generated declarations.
+fn declarations_template_data(
+    info: &CanisterInfo,
+    env: &TypeEnv,
+    ty: &Option<Type>,
+) -> DfxResult<serde_json::Value> {
+    let canister_name = info.get_name().to_string();
+    let canister_name_ident = canister_name.replace('-', "_");
+
+    let node_compatibility = info.get_declarations_config().node_compatibility;
+
+    // Insert only if node outputs are specified
+    let actor_export = if node_compatibility {
+        // leave empty for nodejs
+        "".to_string()
+    } else {
+        format!(
+            r#"
 
-            // Switches to prefixing the canister id with the env variable for frontend declarations as new default
-            let process_string_prefix: String = match &info.get_declarations_config().env_override {
-                Some(s) => format!(r#""{}""#, s.clone()),
-                None => {
-                    format!(
-                        "process.env.{}{}",
-                        "CANISTER_ID_",
-                        &canister_name_ident.to_ascii_uppercase(),
-                    )
-                }
+export const {canister_name_ident} = canisterId ? createActor(canisterId) : undefined;"#,
+        )
+        .to_string()
+    };
+
+    // Switches to prefixing the canister id with the env variable for frontend declarations as new default
+    let process_string_prefix: String = match &info.get_declarations_config().env_override {
+        Some(s) => format!(r#""{}""#, s.clone()),
+        None => {
+            format!(
+                "process.env.{}{}",
+                "CANISTER_ID_",
+                &canister_name_ident.to_ascii_uppercase(),
+            )
+        }
+    };
+
+    let mut methods = vec![];
+    if let Some(ty) = ty {
+        for (name, method) in env.as_service(ty)? {
+            let function = env.as_func(method)?;
+            let mode = match function.modes.first() {
+                Some(FuncMode::Query) => "query",
+                Some(FuncMode::CompositeQuery) => "composite_query",
+                Some(FuncMode::Oneway) => "oneway",
+                None => "update",
             };
-
-            data.insert(
-                "canister_name_process_env".to_string(),
-                &process_string_prefix,
-            );
-
-            let new_file_contents = handlebars.render_template(&file_contents, &data).unwrap();
-            let new_path = generate_output_dir.join(pathname.with_extension(""));
-            std::fs::write(&new_path, new_file_contents)
-                .with_context(|| format!("Failed to write to {}.", new_path.display()))?;
+            methods.push(json!({
+                "name": name,
+                "mode": mode,
+                "args": function.args.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "rets": function.rets.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            }));
         }
     }
 
-    Ok(())
+    Ok(json!({
+        "canister_name": canister_name,
+        "canister_name_ident": canister_name_ident,
+        "canister_name_process_env": process_string_prefix,
+        "canister_id": info.get_canister_id_if_known().map(|id| id.to_text()),
+        "actor_export": actor_export,
+        "methods": methods,
+    }))
 }
 
 // TODO: this function was copied from src/lib/models/canister.rs
@@ -132,6 +132,8 @@ impl CanisterInfo {
                 .or_else(|| Some(vec!["js".to_string(), "ts".to_string(), "did".to_string()])),
             env_override: declarations_config_pre.env_override,
             node_compatibility: declarations_config_pre.node_compatibility,
+            rust: declarations_config_pre.rust,
+            templates: declarations_config_pre.templates,
         };
 
         let output_root = build_root.join(name);
@@ -240,6 +242,11 @@ impl CanisterInfo {
         &self.output_root
     }
 
+    /// The canister id, if it has been set.
+    pub fn get_canister_id_if_known(&self) -> Option<CanisterId> {
+        self.canister_id
+    }
+
     #[context("Failed to get canister id for '{}'.", self.name)]
     pub fn get_canister_id(&self) -> DfxResult<CanisterId> {
         match &self.canister_id {
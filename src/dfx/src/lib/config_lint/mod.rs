@@ -0,0 +1,533 @@
+//! Checks dfx.json and networks.json for problems, reporting each with its position in the file.
+use crate::lib::config_lint::schema::{SchemaValidator, SchemaViolation};
+use dfx_core::config::model::dfinity::{
+    CanisterTypeProperties, ConfigCanistersCanister, ConfigInterface, TopLevelConfigNetworks,
+    BUILTIN_CANISTER_TYPES,
+};
+use dfx_core::config::model::extension_canister_type::apply_extension_canister_types;
+use dfx_core::config::model::network_descriptor::PLAYGROUND_NETWORK_NAME;
+use dfx_core::extension::manager::ExtensionManager;
+use dfx_core::json::position::JsonPositions;
+use schemars::schema_for;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+pub mod schema;
+
+/// Fields that are still accepted, but ignored. `*` matches any single key.
+const DEPRECATED_FIELDS: &[(&str, &str)] = &[
+    (
+        "/defaults/bootstrap",
+        "the bootstrap command has been removed and this field is ignored",
+    ),
+    (
+        "/networks/*/bootstrap",
+        "the bootstrap command has been removed and this field is ignored",
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    /// A short, stable identifier of the check that produced this diagnostic.
+    pub code: &'static str,
+    /// JSON pointer to the value the diagnostic is about.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {} [{}]",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.severity,
+            self.message,
+            self.code
+        )
+    }
+}
+
+struct Diagnostics<'a> {
+    file: &'a Path,
+    positions: JsonPositions,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics<'_> {
+    fn add(&mut self, severity: Severity, code: &'static str, pointer: &str, message: String) {
+        let position = self.positions.find(pointer);
+        self.diagnostics.push(Diagnostic {
+            file: self.file.to_path_buf(),
+            line: position.line,
+            column: position.column,
+            severity,
+            code,
+            pointer: pointer.to_string(),
+            message,
+        });
+    }
+
+    fn error(&mut self, code: &'static str, pointer: &str, message: String) {
+        self.add(Severity::Error, code, pointer, message)
+    }
+
+    fn warning(&mut self, code: &'static str, pointer: &str, message: String) {
+        self.add(Severity::Warning, code, pointer, message)
+    }
+
+    fn into_sorted(mut self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .sort_by_key(|d| (d.line, d.column, d.severity, d.code));
+        self.diagnostics
+    }
+}
+
+/// Checks the contents of a dfx.json file.
+///
+/// `known_networks` are the networks defined outside of dfx.json, i.e. in networks.json.
+pub fn lint_dfx_json(
+    file: &Path,
+    content: &str,
+    extension_manager: &ExtensionManager,
+    known_networks: &BTreeSet<String>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics {
+        file,
+        positions: JsonPositions::parse(content),
+        diagnostics: vec![],
+    };
+    let Some(json) = parse_json(content, &mut diagnostics) else {
+        return diagnostics.into_sorted();
+    };
+
+    check_deprecated_fields(&json, &mut diagnostics);
+    let effective_json = check_canister_types(&json, extension_manager, &mut diagnostics);
+
+    let schema = serde_json::to_value(schema_for!(ConfigInterface)).unwrap();
+    if !check_schema(&schema, &effective_json, &mut diagnostics) {
+        return diagnostics.into_sorted();
+    }
+    let config: ConfigInterface = match serde_json::from_value(effective_json) {
+        Ok(config) => config,
+        Err(e) => {
+            diagnostics.error("invalid-config", "", e.to_string());
+            return diagnostics.into_sorted();
+        }
+    };
+
+    let project_root = file.parent().unwrap_or(Path::new("."));
+    // Canisters of unknown types were left out of the config, but they can still be depended on.
+    let canister_names: BTreeSet<&str> = json
+        .get("canisters")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|canisters| canisters.keys().map(String::as_str))
+        .collect();
+    check_dependencies(&config, &canister_names, &mut diagnostics);
+    check_paths(&config, project_root, &mut diagnostics);
+    check_specified_ids(&config, &mut diagnostics);
+    check_remote_networks(&config, known_networks, &mut diagnostics);
+    diagnostics.into_sorted()
+}
+
+/// Checks the contents of the shared networks.json file.
+pub fn lint_networks_json(file: &Path, content: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics {
+        file,
+        positions: JsonPositions::parse(content),
+        diagnostics: vec![],
+    };
+    if let Some(json) = parse_json(content, &mut diagnostics) {
+        // networks.json has the same shape as the "networks" field of dfx.json
+        for (pattern, message) in DEPRECATED_FIELDS {
+            if let Some(pattern) = pattern.strip_prefix("/networks") {
+                for pointer in matching_pointers(&json, pattern) {
+                    diagnostics.warning("deprecated", &pointer, message.to_string());
+                }
+            }
+        }
+        let schema = serde_json::to_value(schema_for!(TopLevelConfigNetworks)).unwrap();
+        check_schema(&schema, &json, &mut diagnostics);
+    }
+    diagnostics.into_sorted()
+}
+
+/// The schema violations of the JSON of a dfx.json file, including unknown fields.
+pub fn dfx_json_schema_violations(
+    json: &Value,
+    extension_manager: &ExtensionManager,
+) -> Vec<SchemaViolation> {
+    let effective_json = apply_extension_canister_types(json.clone(), Some(extension_manager))
+        .unwrap_or_else(|_| json.clone());
+    let schema = serde_json::to_value(schema_for!(ConfigInterface)).unwrap();
+    SchemaValidator::new(&schema)
+        .deny_unknown_fields()
+        .validate(&effective_json)
+}
+
+/// The schema violations of the JSON of the shared networks.json file, including unknown fields.
+pub fn networks_json_schema_violations(json: &Value) -> Vec<SchemaViolation> {
+    let schema = serde_json::to_value(schema_for!(TopLevelConfigNetworks)).unwrap();
+    SchemaValidator::new(&schema)
+        .deny_unknown_fields()
+        .validate(json)
+}
+
+fn parse_json(content: &str, diagnostics: &mut Diagnostics) -> Option<Value> {
+    match serde_json::from_str(content) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            diagnostics.diagnostics.push(Diagnostic {
+                file: diagnostics.file.to_path_buf(),
+                line: e.line().max(1),
+                column: e.column().max(1),
+                severity: Severity::Error,
+                code: "invalid-json",
+                pointer: String::new(),
+                message: e.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Returns false if there were any violations.
+fn check_schema(schema: &Value, json: &Value, diagnostics: &mut Diagnostics) -> bool {
+    let violations = SchemaValidator::new(schema).validate(json);
+    for violation in &violations {
+        diagnostics.error("schema", &violation.pointer, violation.message.clone());
+    }
+    violations.is_empty()
+}
+
+fn check_deprecated_fields(json: &Value, diagnostics: &mut Diagnostics) {
+    for (pattern, message) in DEPRECATED_FIELDS {
+        for pointer in matching_pointers(json, pattern) {
+            diagnostics.warning("deprecated", &pointer, message.to_string());
+        }
+    }
+}
+
+/// Returns the pointers of all values in `json` matching `pattern`,
+/// where a `*` segment in the pattern matches any key.
+fn matching_pointers(json: &Value, pattern: &str) -> Vec<String> {
+    let mut matches = vec![(String::new(), json)];
+    for segment in pattern.split('/').skip(1) {
+        matches = matches
+            .into_iter()
+            .flat_map(|(pointer, value)| {
+                value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(key, _)| segment == "*" || *key == segment)
+                    .map(move |(key, child)| (format!("{pointer}/{}", escape(key)), child))
+            })
+            .collect();
+    }
+    matches.into_iter().map(|(pointer, _)| pointer).collect()
+}
+
+/// Reports canisters whose type is neither built in nor provided by an installed extension,
+/// and returns the JSON with the types of installed extensions applied.
+/// Canisters that are missing a type default to Motoko, as when loading dfx.json.
+fn check_canister_types(
+    json: &Value,
+    extension_manager: &ExtensionManager,
+    diagnostics: &mut Diagnostics,
+) -> Value {
+    let mut json = json.clone();
+    if let Some(canisters) = json.get_mut("canisters").and_then(Value::as_object_mut) {
+        canisters.retain(|name, canister| {
+            let Some(canister) = canister.as_object_mut() else {
+                return true;
+            };
+            let canister_type = match canister.get("type") {
+                None => {
+                    canister.insert("type".to_string(), Value::from("motoko"));
+                    return true;
+                }
+                Some(Value::String(canister_type)) => canister_type.clone(),
+                Some(_) => return true,
+            };
+            if BUILTIN_CANISTER_TYPES.contains(&canister_type.as_str())
+                || extension_manager.is_extension_installed(&canister_type)
+            {
+                return true;
+            }
+            diagnostics.error(
+                "unknown-canister-type",
+                &format!("/canisters/{}/type", escape(name)),
+                format!(
+                    "canister '{name}' has type '{canister_type}', which is not a built-in type, and there is no installed extension by that name"
+                ),
+            );
+            false
+        });
+    }
+    match apply_extension_canister_types(json.clone(), Some(extension_manager)) {
+        Ok(effective_json) => effective_json,
+        Err(e) => {
+            diagnostics.error("extension-canister-type", "/canisters", e.to_string());
+            json
+        }
+    }
+}
+
+fn check_dependencies(
+    config: &ConfigInterface,
+    canister_names: &BTreeSet<&str>,
+    diagnostics: &mut Diagnostics,
+) {
+    let Some(canisters) = &config.canisters else {
+        return;
+    };
+    for (name, canister) in canisters {
+        for (index, dependency) in canister.dependencies.iter().enumerate() {
+            if !canister_names.contains(dependency.as_str()) {
+                diagnostics.error(
+                    "unknown-dependency",
+                    &format!("/canisters/{}/dependencies/{index}", escape(name)),
+                    format!("canister '{name}' depends on unknown canister '{dependency}'"),
+                );
+            }
+        }
+    }
+    // Unknown dependencies are reported above, so the search for cycles skips them
+    // instead of stopping at the first one, as `get_canister_names_with_dependencies` does.
+    let mut visited = BTreeSet::new();
+    let mut reported_cycles = BTreeSet::new();
+    for name in canisters.keys() {
+        find_dependency_cycles(
+            canisters,
+            name,
+            &mut vec![],
+            &mut visited,
+            &mut reported_cycles,
+            diagnostics,
+        );
+    }
+}
+
+/// Reports each cycle reachable from `name` once, at the dependency that starts it.
+/// `path` holds the canisters on the way to `name`.
+fn find_dependency_cycles<'a>(
+    canisters: &'a BTreeMap<String, ConfigCanistersCanister>,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+    visited: &mut BTreeSet<&'a str>,
+    reported_cycles: &mut BTreeSet<BTreeSet<&'a str>>,
+    diagnostics: &mut Diagnostics,
+) {
+    if let Some(start) = path.iter().position(|n| *n == name) {
+        let cycle = &path[start..];
+        if reported_cycles.insert(cycle.iter().copied().collect()) {
+            let next = cycle.get(1).copied().unwrap_or(name);
+            let index = canisters[cycle[0]]
+                .dependencies
+                .iter()
+                .position(|d| d == next)
+                .unwrap_or_default();
+            diagnostics.error(
+                "circular-dependency",
+                &format!("/canisters/{}/dependencies/{index}", escape(cycle[0])),
+                format!(
+                    "circular canister dependencies: {} -> {name}",
+                    cycle.join(" -> ")
+                ),
+            );
+        }
+        return;
+    }
+    let Some(canister) = canisters.get(name) else {
+        return;
+    };
+    if !visited.insert(name) {
+        return;
+    }
+    path.push(name);
+    for dependency in &canister.dependencies {
+        find_dependency_cycles(
+            canisters,
+            dependency,
+            path,
+            visited,
+            reported_cycles,
+            diagnostics,
+        );
+    }
+    path.pop();
+}
+
+fn check_paths(config: &ConfigInterface, project_root: &Path, diagnostics: &mut Diagnostics) {
+    let Some(canisters) = &config.canisters else {
+        return;
+    };
+    for (name, canister) in canisters {
+        let mut check = |field: &str, path: &Path, severity: Severity| {
+            check_path_exists(project_root, name, field, path, severity, diagnostics)
+        };
+        match &canister.type_specific {
+            CanisterTypeProperties::Motoko => {
+                if let Some(main) = &canister.main {
+                    check("main", main, Severity::Error);
+                } else {
+                    diagnostics.error(
+                        "missing-file",
+                        &format!("/canisters/{}", escape(name)),
+                        format!("Motoko canister '{name}' has no 'main' field"),
+                    );
+                }
+            }
+            CanisterTypeProperties::Rust { candid, .. } => {
+                check("candid", candid, Severity::Warning);
+            }
+            // With build steps, the files are produced by the build.
+            CanisterTypeProperties::Custom {
+                wasm,
+                candid,
+                build,
+            } if build.clone().into_vec().is_empty() => {
+                if Url::parse(wasm).is_err() {
+                    check("wasm", Path::new(wasm), Severity::Error);
+                }
+                if Url::parse(candid).is_err() {
+                    check("candid", Path::new(candid), Severity::Error);
+                }
+            }
+            _ => {}
+        }
+        if let Some(templates) = &canister.declarations.templates {
+            for (file, template) in templates {
+                let field = format!("declarations/templates/{}", escape(file));
+                check_path_exists(
+                    project_root,
+                    name,
+                    &field,
+                    template,
+                    Severity::Error,
+                    diagnostics,
+                );
+            }
+        }
+        if let Some(candid) = canister.remote.as_ref().and_then(|r| r.candid.as_ref()) {
+            let field = "remote/candid";
+            check_path_exists(
+                project_root,
+                name,
+                field,
+                candid,
+                Severity::Warning,
+                diagnostics,
+            );
+        }
+    }
+}
+
+fn check_path_exists(
+    project_root: &Path,
+    canister: &str,
+    field: &str,
+    path: &Path,
+    severity: Severity,
+    diagnostics: &mut Diagnostics,
+) {
+    if !project_root.join(path).exists() {
+        diagnostics.add(
+            severity,
+            "missing-file",
+            &format!("/canisters/{}/{field}", escape(canister)),
+            format!(
+                "the {} path of canister '{canister}' does not exist: {}",
+                field.replace('/', " "),
+                path.display()
+            ),
+        );
+    }
+}
+
+fn check_specified_ids(config: &ConfigInterface, diagnostics: &mut Diagnostics) {
+    let Some(canisters) = &config.canisters else {
+        return;
+    };
+    let mut by_id: BTreeMap<_, Vec<&String>> = BTreeMap::new();
+    for (name, canister) in canisters {
+        if let Some(id) = canister.specified_id {
+            by_id.entry(id).or_default().push(name);
+        }
+    }
+    for (id, names) in by_id.into_iter().filter(|(_, names)| names.len() > 1) {
+        for name in &names {
+            let others = names
+                .iter()
+                .filter(|n| *n != name)
+                .map(|n| format!("'{n}'"));
+            diagnostics.error(
+                "duplicate-specified-id",
+                &format!("/canisters/{}/specified_id", escape(name)),
+                format!(
+                    "canister '{name}' has the same specified_id ({id}) as {}",
+                    itertools::join(others, ", ")
+                ),
+            );
+        }
+    }
+}
+
+fn check_remote_networks(
+    config: &ConfigInterface,
+    known_networks: &BTreeSet<String>,
+    diagnostics: &mut Diagnostics,
+) {
+    let Some(canisters) = &config.canisters else {
+        return;
+    };
+    let is_known = |network: &str| {
+        ["ic", "local", PLAYGROUND_NETWORK_NAME].contains(&network)
+            || known_networks.contains(network)
+            || config.get_network(network).is_some()
+    };
+    for (name, canister) in canisters {
+        let Some(remote) = &canister.remote else {
+            continue;
+        };
+        for network in remote.id.keys().filter(|network| !is_known(network)) {
+            diagnostics.warning(
+                "unknown-network",
+                &format!("/canisters/{}/remote/id/{}", escape(name), escape(network)),
+                format!("canister '{name}' has a remote id for unknown network '{network}'"),
+            );
+        }
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
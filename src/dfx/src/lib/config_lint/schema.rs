@@ -0,0 +1,524 @@
+//! A validator for the subset of JSON Schema (draft 7) that schemars generates for dfx.json.
+use itertools::Itertools;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value.
+    pub pointer: String,
+    pub message: String,
+    /// Whether the value failed an `enum` or `const` check, which usually means
+    /// that a `oneOf` alternative was not the one intended.
+    mismatched_constant: bool,
+}
+
+impl SchemaViolation {
+    fn new(pointer: &str, message: String) -> Self {
+        Self {
+            pointer: pointer.to_string(),
+            message,
+            mismatched_constant: false,
+        }
+    }
+}
+
+pub struct SchemaValidator<'a> {
+    root: &'a Value,
+    deny_unknown_fields: bool,
+}
+
+impl<'a> SchemaValidator<'a> {
+    pub fn new(root: &'a Value) -> Self {
+        Self {
+            root,
+            deny_unknown_fields: false,
+        }
+    }
+
+    /// Also reports fields that are not among the properties of an object or of its alternatives,
+    /// even if the schema does not forbid them. dfx ignores such fields when it loads dfx.json,
+    /// so they are usually typos.
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.deny_unknown_fields = true;
+        self
+    }
+
+    pub fn validate(&self, instance: &Value) -> Vec<SchemaViolation> {
+        let mut violations = vec![];
+        self.validate_at(self.root, instance, "", true, &[], &mut violations);
+        violations
+    }
+
+    /// `outermost` is false for the alternatives of `allOf`, `anyOf` and `oneOf`,
+    /// which only describe some of the fields of the object.
+    /// `refs` are the references followed for this value, to stop at circular references.
+    fn validate_at(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        pointer: &str,
+        outermost: bool,
+        refs: &[String],
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                let message = "no value is allowed here".to_string();
+                return violations.push(SchemaViolation::new(pointer, message));
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if refs.iter().any(|followed| followed == reference) {
+                return violations.push(SchemaViolation::new(
+                    pointer,
+                    format!("circular schema reference '{reference}'"),
+                ));
+            }
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => {
+                    let refs = [refs, &[reference.to_string()]].concat();
+                    self.validate_at(target, instance, pointer, outermost, &refs, violations)
+                }
+                None => violations.push(SchemaViolation::new(
+                    pointer,
+                    format!("unresolvable schema reference '{reference}'"),
+                )),
+            }
+            return;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let expected: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !expected.iter().any(|t| has_type(instance, t)) {
+                let message = format!(
+                    "expected {}, found {}",
+                    expected.join(" or "),
+                    type_name(instance)
+                );
+                return violations.push(SchemaViolation::new(pointer, message));
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(instance) {
+                let message = format!(
+                    "expected one of {}, found {instance}",
+                    allowed.iter().join(", ")
+                );
+                return violations.push(SchemaViolation {
+                    mismatched_constant: true,
+                    ..SchemaViolation::new(pointer, message)
+                });
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != instance {
+                let message = format!("expected {expected}, found {instance}");
+                return violations.push(SchemaViolation {
+                    mismatched_constant: true,
+                    ..SchemaViolation::new(pointer, message)
+                });
+            }
+        }
+
+        match instance {
+            Value::Object(map) => {
+                if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                    for name in required.iter().filter_map(Value::as_str) {
+                        if !map.contains_key(name) {
+                            let message = format!("missing required field '{name}'");
+                            violations.push(SchemaViolation::new(pointer, message));
+                        }
+                    }
+                }
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let known_fields = if self.deny_unknown_fields && outermost {
+                    self.known_fields(schema)
+                } else {
+                    None
+                };
+                for (name, value) in map {
+                    let child = format!("{pointer}/{}", escape(name));
+                    match properties.and_then(|properties| properties.get(name)) {
+                        Some(property) => {
+                            self.validate_at(property, value, &child, true, &[], violations)
+                        }
+                        None => match schema.get("additionalProperties") {
+                            Some(Value::Bool(false)) => violations.push(SchemaViolation::new(
+                                &child,
+                                format!("unknown field '{name}'"),
+                            )),
+                            Some(additional) => {
+                                self.validate_at(additional, value, &child, true, &[], violations)
+                            }
+                            None if known_fields
+                                .as_ref()
+                                .is_some_and(|known| !known.contains(name.as_str())) =>
+                            {
+                                violations.push(SchemaViolation::new(
+                                    &child,
+                                    format!("unknown field '{name}'"),
+                                ))
+                            }
+                            None => {}
+                        },
+                    }
+                }
+            }
+            Value::Array(items) => {
+                match schema.get("items") {
+                    Some(Value::Array(tuple)) => {
+                        for (index, (item, item_schema)) in items.iter().zip(tuple).enumerate() {
+                            let child = format!("{pointer}/{index}");
+                            self.validate_at(item_schema, item, &child, true, &[], violations);
+                        }
+                    }
+                    Some(item_schema) => {
+                        for (index, item) in items.iter().enumerate() {
+                            let child = format!("{pointer}/{index}");
+                            self.validate_at(item_schema, item, &child, true, &[], violations);
+                        }
+                    }
+                    None => {}
+                }
+                if schema.get("uniqueItems") == Some(&Value::Bool(true))
+                    && !items.iter().map(Value::to_string).all_unique()
+                {
+                    let message = "array items must be unique".to_string();
+                    violations.push(SchemaViolation::new(pointer, message));
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                    if number < minimum {
+                        let message = format!("must be at least {minimum}");
+                        violations.push(SchemaViolation::new(pointer, message));
+                    }
+                }
+                if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                    if number > maximum {
+                        let message = format!("must be at most {maximum}");
+                        violations.push(SchemaViolation::new(pointer, message));
+                    }
+                }
+            }
+            Value::String(s) => {
+                if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                    if let Ok(regex) = Regex::new(pattern) {
+                        if !regex.is_match(s) {
+                            let message = format!("'{s}' does not match the pattern '{pattern}'");
+                            violations.push(SchemaViolation::new(pointer, message));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            for sub_schema in all_of {
+                self.validate_at(sub_schema, instance, pointer, false, refs, violations);
+            }
+        }
+        if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array) {
+            self.validate_alternatives(any_of, instance, pointer, false, refs, violations);
+        }
+        if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+            self.validate_alternatives(one_of, instance, pointer, true, refs, violations);
+        }
+    }
+
+    /// The fields of an object described by `schema` and any of its alternatives,
+    /// or None if the schema allows fields it does not describe.
+    fn known_fields<'s>(&'s self, schema: &'s Map<String, Value>) -> Option<BTreeSet<&'s str>> {
+        let mut known = BTreeSet::new();
+        let mut described = false;
+        let mut followed = BTreeSet::new();
+        let mut pending = vec![schema];
+        while let Some(schema) = pending.pop() {
+            if let Some(target) = schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .filter(|reference| followed.insert(*reference))
+                .and_then(|reference| reference.strip_prefix('#'))
+                .and_then(|pointer| self.root.pointer(pointer))
+                .and_then(Value::as_object)
+            {
+                pending.push(target);
+            }
+            if matches!(schema.get("additionalProperties"), Some(a) if a != &Value::Bool(false)) {
+                return None;
+            }
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                described = true;
+                known.extend(properties.keys().map(String::as_str));
+            }
+            for keyword in ["allOf", "anyOf", "oneOf"] {
+                let alternatives = schema.get(keyword).and_then(Value::as_array);
+                pending.extend(
+                    alternatives
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_object),
+                );
+            }
+        }
+        described.then_some(known)
+    }
+
+    /// Checks `anyOf` and `oneOf`.
+    /// If no alternative matches, reports the violations of the closest one.
+    fn validate_alternatives(
+        &self,
+        alternatives: &[Value],
+        instance: &Value,
+        pointer: &str,
+        exactly_one: bool,
+        refs: &[String],
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let results: Vec<Vec<SchemaViolation>> = alternatives
+            .iter()
+            .map(|alternative| {
+                let mut alternative_violations = vec![];
+                self.validate_at(
+                    alternative,
+                    instance,
+                    pointer,
+                    false,
+                    refs,
+                    &mut alternative_violations,
+                );
+                alternative_violations
+            })
+            .collect();
+        let matching = results.iter().filter(|v| v.is_empty()).count();
+        if matching == 0 {
+            if let Some(closest) = results
+                .into_iter()
+                .min_by_key(|v| (v.iter().filter(|v| v.mismatched_constant).count(), v.len()))
+            {
+                violations.extend(closest);
+            }
+        } else if exactly_one && matching > 1 {
+            let message = "the value matches more than one of the allowed forms".to_string();
+            violations.push(SchemaViolation::new(pointer, message));
+        }
+    }
+}
+
+fn has_type(instance: &Value, t: &str) -> bool {
+    match t {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The violations sorted by pointer, since the order of object keys depends on whether
+    /// serde_json preserves it.
+    fn validate(schema: Value, instance: Value) -> Vec<(String, String)> {
+        let mut violations: Vec<_> = SchemaValidator::new(&schema)
+            .validate(&instance)
+            .into_iter()
+            .map(|v| (v.pointer, v.message))
+            .collect();
+        violations.sort();
+        violations
+    }
+
+    #[test]
+    fn types_and_required_fields() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string" },
+                "count": { "type": ["integer", "null"], "minimum": 0 }
+            }
+        });
+        assert!(validate(schema.clone(), json!({"name": "a", "count": 3})).is_empty());
+        assert_eq!(
+            validate(schema.clone(), json!({"count": -1})),
+            vec![
+                ("".to_string(), "missing required field 'name'".to_string()),
+                ("/count".to_string(), "must be at least 0".to_string()),
+            ]
+        );
+        assert_eq!(
+            validate(schema, json!({"name": 1, "count": 1.5})),
+            vec![
+                (
+                    "/count".to_string(),
+                    "expected integer or null, found number".to_string()
+                ),
+                (
+                    "/name".to_string(),
+                    "expected string, found number".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_enums_and_arrays() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "levels": { "type": "array", "items": { "$ref": "#/definitions/Level" }, "uniqueItems": true }
+            },
+            "definitions": {
+                "Level": { "type": "string", "enum": ["cycles", "size"] }
+            }
+        });
+        assert!(validate(schema.clone(), json!({"levels": ["size", "cycles"]})).is_empty());
+        assert_eq!(
+            validate(schema.clone(), json!({"levels": ["fast"]})),
+            vec![(
+                "/levels/0".to_string(),
+                r#"expected one of "cycles", "size", found "fast""#.to_string()
+            )]
+        );
+        assert_eq!(
+            validate(schema, json!({"levels": ["size", "size"]})),
+            vec![(
+                "/levels".to_string(),
+                "array items must be unique".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn one_of_reports_closest_alternative() {
+        let schema = json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["type", "candid"],
+                    "properties": { "type": { "enum": ["rust"] }, "candid": { "type": "string" } }
+                },
+                {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": { "type": { "enum": ["motoko"] } }
+                }
+            ]
+        });
+        assert!(validate(schema.clone(), json!({"type": "motoko"})).is_empty());
+        assert_eq!(
+            validate(schema, json!({"type": "rust", "candid": 5})),
+            vec![(
+                "/candid".to_string(),
+                "expected string, found number".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn patterns_and_additional_properties() {
+        let schema = json!({
+            "type": "object",
+            "additionalProperties": { "type": "string", "pattern": "^[0-9]+$" }
+        });
+        assert!(validate(schema.clone(), json!({"a": "12"})).is_empty());
+        assert_eq!(
+            validate(schema, json!({"a": "1x"})),
+            vec![(
+                "/a".to_string(),
+                "'1x' does not match the pattern '^[0-9]+$'".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_fields_of_alternatives() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "main": { "type": "string" } },
+            "oneOf": [
+                { "properties": { "type": { "enum": ["rust"] }, "candid": { "type": "string" } } },
+                { "properties": { "type": { "enum": ["motoko"] } } }
+            ]
+        });
+        let instance = json!({"type": "motoko", "main": "a.mo", "tpye": "rust"});
+        assert!(validate(schema.clone(), instance.clone()).is_empty());
+        let violations: Vec<_> = SchemaValidator::new(&schema)
+            .deny_unknown_fields()
+            .validate(&instance)
+            .into_iter()
+            .map(|v| (v.pointer, v.message))
+            .collect();
+        assert_eq!(
+            violations,
+            vec![("/tpye".to_string(), "unknown field 'tpye'".to_string())]
+        );
+    }
+
+    #[test]
+    fn circular_references() {
+        let schema = json!({
+            "$ref": "#/definitions/A",
+            "definitions": {
+                "A": { "allOf": [{ "$ref": "#/definitions/B" }], "properties": { "a": {} } },
+                "B": { "anyOf": [{ "$ref": "#/definitions/A" }], "properties": { "b": {} } }
+            }
+        });
+        assert_eq!(
+            validate(schema.clone(), json!({"a": 1})),
+            vec![(
+                "".to_string(),
+                "circular schema reference '#/definitions/A'".to_string()
+            )]
+        );
+        let violations: Vec<_> = SchemaValidator::new(&schema)
+            .deny_unknown_fields()
+            .validate(&json!({"c": 1}))
+            .into_iter()
+            .map(|v| v.message)
+            .collect();
+        assert!(violations.contains(&"unknown field 'c'".to_string()));
+    }
+}
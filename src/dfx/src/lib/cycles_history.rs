@@ -0,0 +1,244 @@
+//! Snapshots of the cycles balances of canisters, recorded per network by `dfx canister cycles-report`.
+use crate::lib::error::DfxResult;
+use anyhow::Context;
+use ic_utils::interfaces::management_canister::StatusCallResult;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Older snapshots are dropped so that the history file stays small.
+const MAX_SNAPSHOTS_PER_CANISTER: usize = 1000;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CyclesSnapshot {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub balance: u128,
+    pub memory_size: u128,
+    pub idle_cycles_burned_per_day: u128,
+    /// The freezing threshold of the canister, in seconds.
+    pub freezing_threshold: u64,
+    pub query_calls_total: u128,
+    pub query_instructions_total: u128,
+}
+
+impl CyclesSnapshot {
+    pub fn from_status(timestamp: u64, status: &StatusCallResult) -> Self {
+        let nat = |n: &candid::Nat| n.0.to_u128().unwrap_or(u128::MAX);
+        Self {
+            timestamp,
+            balance: nat(&status.cycles),
+            memory_size: nat(&status.memory_size),
+            idle_cycles_burned_per_day: nat(&status.idle_cycles_burned_per_day),
+            freezing_threshold: status
+                .settings
+                .freezing_threshold
+                .0
+                .to_u64()
+                .unwrap_or(u64::MAX),
+            query_calls_total: nat(&status.query_stats.num_calls_total),
+            query_instructions_total: nat(&status.query_stats.num_instructions_total),
+        }
+    }
+
+    /// The balance below which the canister is frozen.
+    pub fn freezing_threshold_cycles(&self) -> u128 {
+        self.idle_cycles_burned_per_day
+            .saturating_mul(u128::from(self.freezing_threshold))
+            / u128::from(SECONDS_PER_DAY)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CanisterCyclesHistory {
+    pub name: String,
+    pub snapshots: Vec<CyclesSnapshot>,
+}
+
+/// The cycles consumption of a canister per day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BurnRate {
+    pub per_day: u128,
+    /// Whether the rate is the idle burn reported by the canister,
+    /// because the history has no period in which the balance went down.
+    pub idle_only: bool,
+}
+
+impl CanisterCyclesHistory {
+    pub fn latest(&self) -> Option<&CyclesSnapshot> {
+        self.snapshots.last()
+    }
+
+    /// The average consumption over the recorded periods in which the balance did not grow.
+    /// Periods with a top-up are skipped, since the consumption within them is unknown.
+    pub fn burn_rate(&self) -> Option<BurnRate> {
+        let latest = self.latest()?;
+        let (burned, seconds) = self
+            .snapshots
+            .windows(2)
+            .filter(|pair| pair[1].balance <= pair[0].balance)
+            .fold((0u128, 0u64), |(burned, seconds), pair| {
+                (
+                    burned + (pair[0].balance - pair[1].balance),
+                    seconds + pair[1].timestamp.saturating_sub(pair[0].timestamp),
+                )
+            });
+        if seconds == 0 || burned == 0 {
+            return Some(BurnRate {
+                per_day: latest.idle_cycles_burned_per_day,
+                idle_only: true,
+            });
+        }
+        Some(BurnRate {
+            per_day: burned * u128::from(SECONDS_PER_DAY) / u128::from(seconds),
+            idle_only: false,
+        })
+    }
+
+    /// The time at which the balance reaches the freezing threshold at the current burn rate,
+    /// in seconds since the Unix epoch.
+    pub fn projected_freeze(&self) -> Option<u64> {
+        let latest = self.latest()?;
+        let rate = self.burn_rate()?;
+        if rate.per_day == 0 {
+            return None;
+        }
+        let spendable = latest
+            .balance
+            .saturating_sub(latest.freezing_threshold_cycles());
+        let seconds = spendable * u128::from(SECONDS_PER_DAY) / rate.per_day;
+        Some(
+            latest
+                .timestamp
+                .saturating_add(u64::try_from(seconds).unwrap_or(u64::MAX)),
+        )
+    }
+
+    /// The queries answered since the first snapshot.
+    pub fn query_calls(&self) -> u128 {
+        match (self.snapshots.first(), self.snapshots.last()) {
+            (Some(first), Some(last)) => last
+                .query_calls_total
+                .saturating_sub(first.query_calls_total),
+            _ => 0,
+        }
+    }
+}
+
+/// The recorded snapshots of one network, by canister id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CyclesHistory {
+    pub canisters: BTreeMap<String, CanisterCyclesHistory>,
+}
+
+impl CyclesHistory {
+    pub fn path(project_temp_dir: &Path, network_name: &str) -> PathBuf {
+        project_temp_dir
+            .join(network_name)
+            .join("cycles_history.json")
+    }
+
+    pub fn load(path: &Path) -> DfxResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        dfx_core::json::load_json_file(path)
+            .with_context(|| format!("Failed to read the cycles history {}.", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> DfxResult {
+        if let Some(parent) = path.parent() {
+            dfx_core::fs::create_dir_all(parent)?;
+        }
+        dfx_core::json::save_json_file(path, self)
+            .with_context(|| format!("Failed to write the cycles history {}.", path.display()))
+    }
+
+    pub fn record(&mut self, canister_id: &str, name: &str, snapshot: CyclesSnapshot) {
+        let history = self.canisters.entry(canister_id.to_string()).or_default();
+        history.name = name.to_string();
+        history.snapshots.push(snapshot);
+        let excess = history
+            .snapshots
+            .len()
+            .saturating_sub(MAX_SNAPSHOTS_PER_CANISTER);
+        history.snapshots.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(timestamp: u64, balance: u128) -> CyclesSnapshot {
+        CyclesSnapshot {
+            timestamp,
+            balance,
+            memory_size: 0,
+            idle_cycles_burned_per_day: 1_000,
+            freezing_threshold: 2 * SECONDS_PER_DAY,
+            query_calls_total: timestamp.into(),
+            query_instructions_total: 0,
+        }
+    }
+
+    fn history(snapshots: Vec<CyclesSnapshot>) -> CanisterCyclesHistory {
+        CanisterCyclesHistory {
+            name: "backend".to_string(),
+            snapshots,
+        }
+    }
+
+    #[test]
+    fn burn_rate_skips_top_ups() {
+        let history = history(vec![
+            snapshot(0, 100_000),
+            snapshot(SECONDS_PER_DAY, 90_000),
+            // topped up
+            snapshot(2 * SECONDS_PER_DAY, 500_000),
+            snapshot(4 * SECONDS_PER_DAY, 470_000),
+        ]);
+        assert_eq!(
+            history.burn_rate(),
+            Some(BurnRate {
+                per_day: 40_000 / 3,
+                idle_only: false
+            })
+        );
+        assert_eq!(history.query_calls(), u128::from(4 * SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn burn_rate_falls_back_to_idle_burn() {
+        let single = history(vec![snapshot(0, 100_000)]);
+        assert_eq!(
+            single.burn_rate(),
+            Some(BurnRate {
+                per_day: 1_000,
+                idle_only: true
+            })
+        );
+        assert_eq!(history(vec![]).burn_rate(), None);
+    }
+
+    #[test]
+    fn projected_freeze_keeps_freezing_threshold() {
+        let history = history(vec![snapshot(0, 12_000), snapshot(SECONDS_PER_DAY, 11_000)]);
+        // 11_000 cycles minus a threshold of 2 days of idle burn, at 1_000 cycles per day.
+        assert_eq!(history.projected_freeze(), Some(10 * SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn record_caps_snapshots() {
+        let mut cycles_history = CyclesHistory::default();
+        for timestamp in 0..(MAX_SNAPSHOTS_PER_CANISTER as u64 + 5) {
+            cycles_history.record("aaaaa-aa", "backend", snapshot(timestamp, 0));
+        }
+        let snapshots = &cycles_history.canisters["aaaaa-aa"].snapshots;
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS_PER_CANISTER);
+        assert_eq!(snapshots[0].timestamp, 5);
+    }
+}
@@ -12,7 +12,7 @@ use crate::lib::deps::{
 use crate::lib::error::DfxResult;
 use crate::util::{download_file, download_file_to_path};
 
-static IC_REV: &str = "1eeb4d74deb00bd52739cbd6f37ce1dc72e0c76e";
+pub(crate) static IC_REV: &str = "1eeb4d74deb00bd52739cbd6f37ce1dc72e0c76e";
 
 #[derive(Debug)]
 struct Facade {
@@ -21,7 +21,7 @@ use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::io::Write;
 use std::path::Path;
 
-mod facade;
+pub(crate) mod facade;
 use facade::{facade_dependencies, facade_download};
 
 pub async fn resolve_all_dependencies(
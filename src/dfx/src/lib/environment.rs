@@ -332,15 +332,10 @@ impl<'a> AgentEnvironment<'a> {
         backend: &'a dyn Environment,
         network_descriptor: NetworkDescriptor,
         timeout: Duration,
-        use_identity: Option<&str>,
+        mut identity_manager: IdentityManager,
     ) -> DfxResult<Self> {
         let logger = backend.get_logger().clone();
-        let mut identity_manager = backend.new_identity_manager()?;
-        let identity = if let Some(identity_name) = use_identity {
-            identity_manager.instantiate_identity_from_name(identity_name, &logger)?
-        } else {
-            identity_manager.instantiate_selected_identity(&logger)?
-        };
+        let identity = identity_manager.instantiate_selected_identity(&logger)?;
         Telemetry::set_identity_type(identity.identity_type());
         Telemetry::set_network(&network_descriptor);
         if network_descriptor.is_ic
@@ -481,6 +476,17 @@ impl<'a> Environment for AgentEnvironment<'a> {
         self.backend.new_progress(message)
     }
 
+    /// An identity manager that selects the identity of the agent, and only loads the identities
+    /// that the network allows.
+    fn new_identity_manager(&self) -> Result<IdentityManager, NewIdentityManagerError> {
+        let mut identity_manager = self.backend.new_identity_manager()?;
+        if let Some((network_name, identities)) = self.identity_manager.get_network_identities() {
+            identity_manager.select_identity(self.identity_manager.get_selected_identity_name());
+            identity_manager.restrict_to_network(network_name, identities.clone());
+        }
+        Ok(identity_manager)
+    }
+
     fn get_selected_identity(&self) -> Option<&String> {
         Some(self.identity_manager.get_selected_identity_name())
     }
@@ -0,0 +1,74 @@
+// Types of the ICRC-3 block log.
+// See https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-3/README.md
+use candid::{CandidType, Int, Nat};
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+
+/// A generic block value.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Icrc3Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<Icrc3Value>),
+    Map(Vec<(String, Icrc3Value)>),
+}
+
+impl Icrc3Value {
+    /// The value of `key`, if this is a map that contains it.
+    pub fn get(&self, key: &str) -> Option<&Icrc3Value> {
+        match self {
+            Icrc3Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Icrc3Value::Text(text) => Some(text.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_nat(&self) -> Option<&Nat> {
+        match self {
+            Icrc3Value::Nat(nat) => Some(nat),
+            _ => None,
+        }
+    }
+
+    pub fn as_blob(&self) -> Option<&[u8]> {
+        match self {
+            Icrc3Value::Blob(blob) => Some(blob.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GetBlocksArgs {
+    pub start: Nat,
+    pub length: Nat,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: Icrc3Value,
+}
+
+candid::define_function!(pub GetBlocksCallback : (Vec<GetBlocksArgs>) -> (GetBlocksResult) query);
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct ArchivedBlocks {
+    pub args: Vec<GetBlocksArgs>,
+    pub callback: GetBlocksCallback,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GetBlocksResult {
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
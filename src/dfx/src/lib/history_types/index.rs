@@ -0,0 +1,82 @@
+// Types of the ICRC index canister, for the transactions of an account.
+// Fields that dfx does not use are left out.
+// See https://github.com/dfinity/ic/blob/master/rs/ledger_suite/icrc1/index-ng/index-ng.did
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GetAccountTransactionsArgs {
+    pub account: Account,
+    /// The newest transaction to return. `None` starts from the most recent one.
+    pub start: Option<Nat>,
+    pub max_results: Nat,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub enum GetTransactionsResult {
+    Ok(GetTransactions),
+    Err(GetTransactionsErr),
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GetTransactions {
+    pub transactions: Vec<TransactionWithId>,
+    pub oldest_tx_id: Option<Nat>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct GetTransactionsErr {
+    pub message: String,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct TransactionWithId {
+    pub id: Nat,
+    pub transaction: Transaction,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Transaction {
+    pub kind: String,
+    pub timestamp: u64,
+    pub mint: Option<Mint>,
+    pub burn: Option<Burn>,
+    pub transfer: Option<Transfer>,
+    pub approve: Option<Approve>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Mint {
+    pub to: Account,
+    pub amount: Nat,
+    pub memo: Option<ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Burn {
+    pub from: Account,
+    pub spender: Option<Account>,
+    pub amount: Nat,
+    pub memo: Option<ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Transfer {
+    pub from: Account,
+    pub to: Account,
+    pub spender: Option<Account>,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct Approve {
+    pub from: Account,
+    pub spender: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<ByteBuf>,
+}
@@ -0,0 +1,2 @@
+pub mod icrc3;
+pub mod index;
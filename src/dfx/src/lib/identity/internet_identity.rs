@@ -0,0 +1,182 @@
+//! Signs in to the development build of Internet Identity on a local network
+//! with the key of a dfx identity as a device of the anchor.
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::system_canisters::MAINNET_II_CANISTER_ID;
+use anyhow::{anyhow, bail, Context};
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use dfx_core::identity::delegation::DelegationChain;
+use ic_agent::identity::{Delegation, SignedDelegation};
+use ic_agent::Agent;
+use serde_bytes::ByteBuf;
+use std::time::Duration;
+
+/// Internet Identity does not issue delegations that are valid for longer than 30 days.
+const MAX_DELEGATION_TIME_TO_LIVE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The development build of Internet Identity accepts this solution for every captcha.
+const DEV_CAPTCHA_SOLUTION: &str = "a";
+
+#[derive(CandidType)]
+struct DeviceData {
+    pubkey: ByteBuf,
+    alias: String,
+    purpose: Purpose,
+    key_type: KeyType,
+    protection: DeviceProtection,
+}
+
+#[derive(CandidType)]
+enum Purpose {
+    #[serde(rename = "authentication")]
+    Authentication,
+}
+
+#[derive(CandidType)]
+enum KeyType {
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+#[derive(CandidType)]
+enum DeviceProtection {
+    #[serde(rename = "unprotected")]
+    Unprotected,
+}
+
+#[derive(CandidType, Deserialize)]
+struct Challenge {
+    challenge_key: String,
+}
+
+#[derive(CandidType)]
+struct ChallengeResult {
+    key: String,
+    chars: String,
+}
+
+#[derive(CandidType, Deserialize)]
+enum RegisterResponse {
+    #[serde(rename = "registered")]
+    Registered { user_number: u64 },
+    #[serde(rename = "canister_full")]
+    CanisterFull,
+    #[serde(rename = "bad_challenge")]
+    BadChallenge,
+}
+
+#[derive(CandidType, Deserialize)]
+enum GetDelegationResponse {
+    #[serde(rename = "signed_delegation")]
+    SignedDelegation(IiSignedDelegation),
+    #[serde(rename = "no_such_delegation")]
+    NoSuchDelegation,
+}
+
+#[derive(CandidType, Deserialize)]
+struct IiSignedDelegation {
+    delegation: IiDelegation,
+    signature: ByteBuf,
+}
+
+#[derive(CandidType, Deserialize)]
+struct IiDelegation {
+    pubkey: ByteBuf,
+    expiration: u64,
+    targets: Option<Vec<Principal>>,
+}
+
+fn device(public_key: &[u8], alias: &str) -> DeviceData {
+    DeviceData {
+        pubkey: ByteBuf::from(public_key),
+        alias: alias.to_string(),
+        purpose: Purpose::Authentication,
+        key_type: KeyType::Unknown,
+        protection: DeviceProtection::Unprotected,
+    }
+}
+
+/// Registers a new anchor whose only device is the key of the agent's identity.
+pub async fn register_anchor(agent: &Agent, public_key: &[u8], alias: &str) -> DfxResult<u64> {
+    let response = agent
+        .update(&MAINNET_II_CANISTER_ID, "create_challenge")
+        .with_arg(Encode!()?)
+        .await
+        .context("Failed to create a captcha. Is Internet Identity installed on the network?")?;
+    let challenge = Decode!(&response, Challenge)?;
+    let result = ChallengeResult {
+        key: challenge.challenge_key,
+        chars: DEV_CAPTCHA_SOLUTION.to_string(),
+    };
+    let response = agent
+        .update(&MAINNET_II_CANISTER_ID, "register")
+        .with_arg(Encode!(
+            &device(public_key, alias),
+            &result,
+            &None::<Principal>
+        )?)
+        .await
+        .context("Failed to register an anchor.")?;
+    match Decode!(&response, RegisterResponse)? {
+        RegisterResponse::Registered { user_number } => Ok(user_number),
+        RegisterResponse::CanisterFull => bail!("Internet Identity cannot register more anchors."),
+        RegisterResponse::BadChallenge => bail!(
+            "Internet Identity rejected the captcha. Only its development build can be used by dfx."
+        ),
+    }
+}
+
+/// Adds a key as a device of the anchor. The agent's identity must be a device of the anchor.
+pub async fn add_device(agent: &Agent, anchor: u64, public_key: &[u8], alias: &str) -> DfxResult {
+    agent
+        .update(&MAINNET_II_CANISTER_ID, "add")
+        .with_arg(Encode!(&anchor, &device(public_key, alias))?)
+        .await
+        .with_context(|| format!("Failed to add a device to anchor {anchor}."))?;
+    Ok(())
+}
+
+/// Signs in to a frontend as the anchor: Internet Identity delegates from the principal of the anchor
+/// for the frontend origin to the session key. The agent's identity must be a device of the anchor.
+pub async fn delegate_to_session_key(
+    agent: &Agent,
+    anchor: u64,
+    origin: &str,
+    session_key: &[u8],
+) -> DfxResult<DelegationChain> {
+    let session_key = ByteBuf::from(session_key);
+    let max_time_to_live = MAX_DELEGATION_TIME_TO_LIVE.as_nanos() as u64;
+    let response = agent
+        .update(&MAINNET_II_CANISTER_ID, "prepare_delegation")
+        .with_arg(Encode!(
+            &anchor,
+            &origin,
+            &session_key,
+            &Some(max_time_to_live)
+        )?)
+        .await
+        .with_context(|| format!("Failed to sign in to {origin} with anchor {anchor}."))?;
+    let (user_key, expiration) = Decode!(&response, ByteBuf, u64)?;
+    let response = agent
+        .query(&MAINNET_II_CANISTER_ID, "get_delegation")
+        .with_arg(Encode!(&anchor, &origin, &session_key, &expiration)?)
+        .await
+        .context("Failed to get the delegation from Internet Identity.")?;
+    let signed = match Decode!(&response, GetDelegationResponse)? {
+        GetDelegationResponse::SignedDelegation(signed) => signed,
+        GetDelegationResponse::NoSuchDelegation => {
+            return Err(anyhow!("Internet Identity did not prepare the delegation."))
+        }
+    };
+    let signed = SignedDelegation {
+        delegation: Delegation {
+            pubkey: signed.delegation.pubkey.into_vec(),
+            expiration: signed.delegation.expiration,
+            targets: signed.delegation.targets,
+        },
+        signature: signed.signature.into_vec(),
+    };
+    Ok(DelegationChain::from_signed_delegations(
+        &user_key,
+        &[signed],
+    ))
+}
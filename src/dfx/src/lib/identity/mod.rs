@@ -1 +1,2 @@
+pub mod internet_identity;
 pub mod wallet;
@@ -21,7 +21,7 @@ pub async fn initialize_bitcoin_canister(
     let name = "bitcoin integration";
     let canister_id = MAINNET_BITCOIN_CANISTER_ID;
     let wasm = bitcoin_wasm(logger)?;
-    let init_arg = &bitcoin_integration_config.canister_init_arg;
+    let init_arg = Some(bitcoin_integration_config.canister_init_arg.as_str());
 
     initialize_integration_canister(agent, logger, name, canister_id, &wasm, init_arg).await
 }
@@ -0,0 +1,252 @@
+//! A client for the JSON-RPC interface of the bitcoind that `dfx start` runs in regtest mode.
+use crate::lib::error::DfxResult;
+use anyhow::{anyhow, bail, Context};
+use dfx_core::config::model::dfinity::default_bitcoind_rpc_port;
+use dfx_core::config::model::network_descriptor::NetworkDescriptor;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub const SATOSHIS_PER_BTC: u64 = 100_000_000;
+
+/// The wallet of the node that funds `dfx bitcoin mine` and `dfx bitcoin send`.
+const WALLET_NAME: &str = "dfx";
+
+/// bitcoind answers with this code while it is still loading blocks.
+const RPC_IN_WARMUP: i64 = -28;
+const RPC_WALLET_NOT_FOUND: i64 = -18;
+const RPC_WALLET_ALREADY_LOADED: i64 = -35;
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Value,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+pub struct BitcoindRpc {
+    client: Client,
+    url: String,
+    user: String,
+    password: String,
+}
+
+impl BitcoindRpc {
+    pub fn connect(network_descriptor: &NetworkDescriptor) -> DfxResult<Self> {
+        let local_server_descriptor = network_descriptor.local_server_descriptor()?;
+        let cookie_path = local_server_descriptor.bitcoind_rpc_cookie_path();
+        if !cookie_path.exists() {
+            bail!(
+                "No running bitcoind found for network '{}'. Start it with `dfx start --bitcoind`, or set `bitcoin.bitcoind` in its configuration.",
+                network_descriptor.name
+            );
+        }
+        let cookie = dfx_core::fs::read_to_string(&cookie_path)?;
+        let (user, password) = cookie
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Malformed bitcoind cookie file {}.", cookie_path.display()))?;
+        let rpc_port = local_server_descriptor
+            .bitcoin
+            .bitcoind
+            .as_ref()
+            .map_or_else(default_bitcoind_rpc_port, |bitcoind| bitcoind.rpc_port);
+        Ok(Self {
+            client: Client::new(),
+            url: format!("http://127.0.0.1:{rpc_port}"),
+            user: user.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    /// Calls `method`, waiting for bitcoind if it is still starting.
+    async fn request(
+        &self,
+        wallet: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> DfxResult<Result<Value, RpcError>> {
+        let url = match wallet {
+            Some(wallet) => format!("{}/wallet/{wallet}", self.url),
+            None => self.url.clone(),
+        };
+        let body = json!({ "jsonrpc": "1.0", "id": "dfx", "method": method, "params": params });
+        let mut retries = 0;
+        loop {
+            let sent = self
+                .client
+                .post(&url)
+                .basic_auth(&self.user, Some(&self.password))
+                .json(&body)
+                .send()
+                .await;
+            let response = match sent {
+                Err(err) if err.is_connect() && retries < 30 => None,
+                sent => {
+                    let response: RpcResponse = sent
+                        .with_context(|| format!("Failed to call bitcoind method '{method}'."))?
+                        .json()
+                        .await
+                        .with_context(|| format!("Failed to read the response to '{method}'."))?;
+                    match response.error {
+                        Some(error) if error.code == RPC_IN_WARMUP && retries < 30 => None,
+                        Some(error) => Some(Err(error)),
+                        None => Some(Ok(response.result)),
+                    }
+                }
+            };
+            if let Some(response) = response {
+                return Ok(response);
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            retries += 1;
+        }
+    }
+
+    async fn call(&self, wallet: Option<&str>, method: &str, params: Value) -> DfxResult<Value> {
+        match self.request(wallet, method, params).await? {
+            Ok(result) => Ok(result),
+            Err(error) => bail!(
+                "bitcoind method '{method}' failed: {} (code {})",
+                error.message,
+                error.code
+            ),
+        }
+    }
+
+    /// Loads the wallet of the node, creating it on first use.
+    async fn ensure_wallet(&self) -> DfxResult {
+        match self
+            .request(None, "loadwallet", json!([WALLET_NAME]))
+            .await?
+        {
+            Err(error) if error.code == RPC_WALLET_NOT_FOUND => {
+                self.call(None, "createwallet", json!([WALLET_NAME]))
+                    .await?;
+            }
+            Err(error) if error.code != RPC_WALLET_ALREADY_LOADED => bail!(
+                "Failed to load the bitcoind wallet '{WALLET_NAME}': {}",
+                error.message
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Mines blocks with their rewards paid to `address`, or to the wallet of the node.
+    /// Returns the hashes of the mined blocks.
+    pub async fn mine(&self, blocks: u64, address: Option<String>) -> DfxResult<Vec<String>> {
+        let address = match address {
+            Some(address) => address,
+            None => {
+                self.ensure_wallet().await?;
+                self.call(Some(WALLET_NAME), "getnewaddress", json!([]))
+                    .await?
+                    .as_str()
+                    .context("bitcoind returned an invalid address.")?
+                    .to_string()
+            }
+        };
+        let hashes = self
+            .call(None, "generatetoaddress", json!([blocks, address]))
+            .await?;
+        serde_json::from_value(hashes).context("bitcoind returned invalid block hashes.")
+    }
+
+    /// Sends satoshis from the wallet of the node to `address`. Returns the transaction id.
+    pub async fn send(&self, address: &str, satoshis: u64) -> DfxResult<String> {
+        self.ensure_wallet().await?;
+        let txid = self
+            .call(
+                Some(WALLET_NAME),
+                "sendtoaddress",
+                json!([address, format_btc(satoshis)]),
+            )
+            .await?;
+        txid.as_str()
+            .map(|txid| txid.to_string())
+            .context("bitcoind returned an invalid transaction id.")
+    }
+
+    /// The confirmed balance of `address`, or of the wallet of the node, in satoshis.
+    pub async fn balance(&self, address: Option<&str>) -> DfxResult<u64> {
+        let btc = match address {
+            Some(address) => {
+                let scan = self
+                    .call(
+                        None,
+                        "scantxoutset",
+                        json!(["start", [format!("addr({address})")]]),
+                    )
+                    .await?;
+                scan["total_amount"].as_f64()
+            }
+            None => {
+                self.ensure_wallet().await?;
+                self.call(Some(WALLET_NAME), "getbalance", json!([]))
+                    .await?
+                    .as_f64()
+            }
+        }
+        .context("bitcoind returned an invalid amount.")?;
+        Ok((btc * SATOSHIS_PER_BTC as f64).round() as u64)
+    }
+}
+
+/// Parses an amount of BTC with up to 8 decimals into satoshis.
+pub fn parse_btc(amount: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid amount '{amount}'. Expected BTC with up to 8 decimals.");
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > 8
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u64 = format!("{fraction:0<8}").parse().map_err(|_| invalid())?;
+    whole
+        .checked_mul(SATOSHIS_PER_BTC)
+        .and_then(|satoshis| satoshis.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+pub fn format_btc(satoshis: u64) -> String {
+    format!(
+        "{}.{:08}",
+        satoshis / SATOSHIS_PER_BTC,
+        satoshis % SATOSHIS_PER_BTC
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn btc_amounts() {
+        assert_eq!(parse_btc("1"), Ok(100_000_000));
+        assert_eq!(parse_btc("0.5"), Ok(50_000_000));
+        assert_eq!(parse_btc(".00000001"), Ok(1));
+        assert_eq!(parse_btc("21000000.12345678"), Ok(2_100_000_012_345_678));
+        assert!(parse_btc("0.123456789").is_err());
+        assert!(parse_btc("-1").is_err());
+        assert!(parse_btc("1e3").is_err());
+        assert!(parse_btc(".").is_err());
+        assert_eq!(format_btc(150_000_001), "1.50000001");
+        assert_eq!(format_btc(0), "0.00000000");
+    }
+}
@@ -17,7 +17,9 @@ use slog::{debug, info, Logger};
 use std::time::Duration;
 
 pub mod bitcoin;
+pub mod bitcoind;
 pub mod status;
+pub mod system_canisters;
 
 pub async fn create_integrations_agent(url: &str, logger: &Logger) -> DfxResult<Agent> {
     let timeout = expiry_duration();
@@ -36,7 +38,7 @@ pub async fn initialize_integration_canister(
     name: &str,
     canister_id: Principal,
     wasm: &[u8],
-    init_arg: &str,
+    init_arg: Option<&str>,
 ) -> DfxResult {
     if already_installed(agent, &canister_id, wasm).await? {
         debug!(logger, "Canister {canister_id} already installed");
@@ -49,7 +51,12 @@ pub async fn initialize_integration_canister(
     };
     try_create_canister(agent, logger, &canister_id, &pulled_canister).await?;
 
-    let install_arg = blob_from_arguments(None, Some(init_arg), None, None, &None, true, false)?;
+    let install_arg = match init_arg {
+        Some(init_arg) => {
+            blob_from_arguments(None, Some(init_arg), None, None, &None, true, false)?
+        }
+        None => vec![],
+    };
     install_canister(agent, logger, &canister_id, wasm, install_arg, name).await
 }
 
@@ -1,5 +1,6 @@
 use crate::lib::error::DfxResult;
 use crate::lib::integrations::bitcoin::MAINNET_BITCOIN_CANISTER_ID;
+use crate::lib::integrations::system_canisters::configured_system_canister_ids;
 use crate::lib::integrations::{create_integrations_agent, wait_for_canister_installed};
 use dfx_core::config::model::local_server_descriptor::LocalServerDescriptor;
 use slog::Logger;
@@ -9,7 +10,9 @@ pub async fn wait_for_integrations_initialized(
     logger: &Logger,
     local_server_descriptor: &LocalServerDescriptor,
 ) -> DfxResult {
-    if !local_server_descriptor.bitcoin.enabled {
+    let system_canister_ids =
+        configured_system_canister_ids(&local_server_descriptor.system_canisters);
+    if !local_server_descriptor.bitcoin.enabled && system_canister_ids.is_empty() {
         return Ok(());
     }
 
@@ -18,6 +21,9 @@ pub async fn wait_for_integrations_initialized(
     if local_server_descriptor.bitcoin.enabled {
         wait_for_canister_installed(&agent, &MAINNET_BITCOIN_CANISTER_ID).await?;
     }
+    for canister_id in &system_canister_ids {
+        wait_for_canister_installed(&agent, canister_id).await?;
+    }
 
     Ok(())
 }
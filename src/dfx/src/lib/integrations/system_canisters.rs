@@ -0,0 +1,328 @@
+use crate::lib::agent::create_network_identity_manager;
+use crate::lib::deps::pull::facade::IC_REV;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::integrations::initialize_integration_canister;
+use crate::lib::ledger_types::{MAINNET_CYCLE_MINTER_CANISTER_ID, MAINNET_LEDGER_CANISTER_ID};
+use crate::lib::nns_types::account_identifier::AccountIdentifier;
+use crate::util::download_file;
+use anyhow::{bail, Context};
+use candid::Principal;
+use dfx_core::config::cache::get_cache_root;
+use dfx_core::config::model::dfinity::{ConfigSystemCanisters, ConfigSystemLedger};
+use dfx_core::fs::composite::ensure_parent_dir_exists;
+use fn_error_context::context;
+use ic_agent::{Agent, Identity as _};
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use slog::{debug, info, Logger};
+
+pub const MAINNET_GOVERNANCE_CANISTER_ID: Principal =
+    Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01]);
+pub const MAINNET_II_CANISTER_ID: Principal =
+    Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x01, 0x01]);
+pub const MAINNET_CYCLES_LEDGER_CANISTER_ID: Principal =
+    Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x02, 0x10, 0x00, 0x02, 0x01, 0x01]);
+
+// The wasm modules are pinned to releases by their sha256 hashes, like the bundled assets.
+// Update a hash together with the release it belongs to, e.g. with `curl -L <url> | sha256sum`.
+const ICP_LEDGER_WASM_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+const CMC_WASM_SHA256: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const NNS_GOVERNANCE_WASM_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+const INTERNET_IDENTITY_VERSION: &str = "release-2024-12-13";
+const INTERNET_IDENTITY_WASM_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+const CYCLES_LEDGER_VERSION: &str = "0.3.0";
+const CYCLES_LEDGER_WASM_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A system canister that `dfx start` installs at its mainnet canister ID.
+#[derive(Clone, Debug)]
+pub struct SystemCanister {
+    pub name: &'static str,
+    pub canister_id: Principal,
+    /// Identifies the wasm in the cache.
+    version: String,
+    wasm_url: String,
+    /// The hex-encoded sha256 hash of the wasm at `wasm_url`.
+    wasm_sha256: &'static str,
+    /// Candid, or `None` for an empty argument.
+    init_arg: Option<String>,
+}
+
+impl SystemCanister {
+    /// The environment variable that overrides the wasm, like `DFX_BITCOIN_WASM` for the bitcoin canister.
+    fn wasm_env_var(&self) -> String {
+        format!("DFX_{}_WASM", self.name.to_uppercase())
+    }
+
+    fn wasm_file_name(&self) -> &str {
+        self.wasm_url
+            .rsplit('/')
+            .next()
+            .expect("wasm url has no file name")
+    }
+}
+
+/// The canister IDs of the system canisters configured for the network.
+pub fn configured_system_canister_ids(config: &ConfigSystemCanisters) -> Vec<Principal> {
+    [
+        config
+            .icp_ledger
+            .is_some()
+            .then_some(MAINNET_LEDGER_CANISTER_ID),
+        config
+            .cmc
+            .is_some()
+            .then_some(MAINNET_CYCLE_MINTER_CANISTER_ID),
+        config
+            .internet_identity
+            .is_some()
+            .then_some(MAINNET_II_CANISTER_ID),
+        config
+            .cycles_ledger
+            .is_some()
+            .then_some(MAINNET_CYCLES_LEDGER_CANISTER_ID),
+        config
+            .nns_governance
+            .is_some()
+            .then_some(MAINNET_GOVERNANCE_CANISTER_ID),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Resolves the system canisters configured for the network, including the principals of the
+/// identities that receive initial balances, which must be allowed on the network.
+#[context("Failed to determine the system canisters to install.")]
+pub fn get_system_canisters(
+    env: &dyn Environment,
+    network_name: &str,
+    config: &ConfigSystemCanisters,
+) -> DfxResult<Vec<SystemCanister>> {
+    let ic_canister_url = |file: &str| {
+        format!("https://download.dfinity.systems/ic/{IC_REV}/canisters/{file}.wasm.gz")
+    };
+    let minting_account = AccountIdentifier::new(MAINNET_GOVERNANCE_CANISTER_ID, None);
+    let mut canisters = vec![];
+    if let Some(icp_ledger) = &config.icp_ledger {
+        let init_arg = match &icp_ledger.init_arg {
+            Some(init_arg) => init_arg.clone(),
+            None => icp_ledger_init_arg(env, network_name, icp_ledger, &minting_account)?,
+        };
+        canisters.push(SystemCanister {
+            name: "icp_ledger",
+            canister_id: MAINNET_LEDGER_CANISTER_ID,
+            version: IC_REV.to_string(),
+            wasm_url: ic_canister_url("ledger-canister"),
+            wasm_sha256: ICP_LEDGER_WASM_SHA256,
+            init_arg: Some(init_arg),
+        });
+    }
+    if let Some(cmc) = &config.cmc {
+        let init_arg = cmc.init_arg.clone().unwrap_or_else(|| {
+            format!(
+                r#"(opt record {{ ledger_canister_id = opt principal "{MAINNET_LEDGER_CANISTER_ID}"; governance_canister_id = opt principal "{MAINNET_GOVERNANCE_CANISTER_ID}"; minting_account_id = opt "{minting_account}"; last_purged_notification = opt 0; exchange_rate_canister = null; cycles_ledger_canister_id = opt principal "{MAINNET_CYCLES_LEDGER_CANISTER_ID}" }})"#
+            )
+        });
+        canisters.push(SystemCanister {
+            name: "cmc",
+            canister_id: MAINNET_CYCLE_MINTER_CANISTER_ID,
+            version: IC_REV.to_string(),
+            wasm_url: ic_canister_url("cycles-minting-canister"),
+            wasm_sha256: CMC_WASM_SHA256,
+            init_arg: Some(init_arg),
+        });
+    }
+    if let Some(internet_identity) = &config.internet_identity {
+        canisters.push(SystemCanister {
+            name: "internet_identity",
+            canister_id: MAINNET_II_CANISTER_ID,
+            version: INTERNET_IDENTITY_VERSION.to_string(),
+            wasm_url: format!("https://github.com/dfinity/internet-identity/releases/download/{INTERNET_IDENTITY_VERSION}/internet_identity_dev.wasm.gz"),
+            wasm_sha256: INTERNET_IDENTITY_WASM_SHA256,
+            init_arg: Some(internet_identity.init_arg.clone().unwrap_or_else(|| "(null)".to_string())),
+        });
+    }
+    if let Some(cycles_ledger) = &config.cycles_ledger {
+        canisters.push(SystemCanister {
+            name: "cycles_ledger",
+            canister_id: MAINNET_CYCLES_LEDGER_CANISTER_ID,
+            version: CYCLES_LEDGER_VERSION.to_string(),
+            wasm_url: format!("https://github.com/dfinity/cycles-ledger/releases/download/cycles-ledger-v{CYCLES_LEDGER_VERSION}/cycles-ledger.wasm.gz"),
+            wasm_sha256: CYCLES_LEDGER_WASM_SHA256,
+            init_arg: Some(cycles_ledger.init_arg.clone().unwrap_or_else(|| {
+                "(variant { Init = record { max_blocks_per_request = 1000; index_id = null } })"
+                    .to_string()
+            })),
+        });
+    }
+    if let Some(nns_governance) = &config.nns_governance {
+        canisters.push(SystemCanister {
+            name: "nns_governance",
+            canister_id: MAINNET_GOVERNANCE_CANISTER_ID,
+            version: IC_REV.to_string(),
+            wasm_url: ic_canister_url("governance-canister_test"),
+            wasm_sha256: NNS_GOVERNANCE_WASM_SHA256,
+            init_arg: Some(
+                nns_governance
+                    .init_arg
+                    .clone()
+                    .unwrap_or_else(nns_governance_init_arg),
+            ),
+        });
+    }
+    Ok(canisters)
+}
+
+/// Governance without neurons or proposals. The fields that are not optional must be given;
+/// governance fills in the default network economics.
+fn nns_governance_init_arg() -> String {
+    "(record { \
+        default_followees = vec {}; \
+        wait_for_quiet_threshold_seconds = 86_400 : nat64; \
+        short_voting_period_seconds = 43_200 : nat64; \
+        node_providers = vec {}; \
+        to_claim_transfers = vec {}; \
+        topic_followee_index = vec {}; \
+        proposals = vec {}; \
+        in_flight_commands = vec {}; \
+        neurons = vec {}; \
+        genesis_timestamp_seconds = 0 : nat64 \
+    })"
+    .to_string()
+}
+
+fn icp_ledger_init_arg(
+    env: &dyn Environment,
+    network_name: &str,
+    icp_ledger: &ConfigSystemLedger,
+    minting_account: &AccountIdentifier,
+) -> DfxResult<String> {
+    let mut identity_manager =
+        create_network_identity_manager(env, Some(network_name.to_string()))?;
+    let mut initial_values = vec![];
+    for (identity_name, e8s) in &icp_ledger.initial_balances {
+        let principal = identity_manager
+            .instantiate_identity_from_name(identity_name, env.get_logger())?
+            .sender()
+            .map_err(anyhow::Error::msg)
+            .with_context(|| {
+                format!("Failed to get the principal of identity '{identity_name}'.")
+            })?;
+        let account = AccountIdentifier::new(principal, None);
+        initial_values.push(format!(
+            r#"record {{ "{account}"; record {{ e8s = {e8s} : nat64 }} }}"#
+        ));
+    }
+    Ok(format!(
+        r#"(variant {{ Init = record {{ minting_account = "{minting_account}"; initial_values = vec {{ {} }}; send_whitelist = vec {{}}; transfer_fee = opt record {{ e8s = 10_000 : nat64 }}; token_symbol = opt "LICP"; token_name = opt "Local ICP"; feature_flags = opt record {{ icrc2 = true }} }} }})"#,
+        initial_values.join("; ")
+    ))
+}
+
+#[context("Failed to initialize system canisters")]
+pub async fn initialize_system_canisters(
+    agent: &Agent,
+    logger: &Logger,
+    system_canisters: &[SystemCanister],
+) -> DfxResult {
+    for canister in system_canisters {
+        debug!(logger, "Initializing {} canister", canister.name);
+        let wasm = system_canister_wasm(logger, canister).await?;
+        initialize_integration_canister(
+            agent,
+            logger,
+            canister.name,
+            canister.canister_id,
+            &wasm,
+            canister.init_arg.as_deref(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Downloads the wasm of a system canister, or reads it from the cache.
+/// Both must match the pinned hash of the wasm.
+#[context("Failed to load the wasm of the {} canister.", canister.name)]
+async fn system_canister_wasm(logger: &Logger, canister: &SystemCanister) -> DfxResult<Vec<u8>> {
+    if let Ok(wasm_path) = std::env::var(canister.wasm_env_var()) {
+        info!(logger, "Using wasm at path: {}", wasm_path);
+        return Ok(dfx_core::fs::read(wasm_path.as_ref())?);
+    }
+
+    let wasm_path = get_cache_root()?
+        .join("system_canisters")
+        .join(canister.name)
+        .join(&canister.version)
+        .join(canister.wasm_file_name());
+    if wasm_path.exists() {
+        let wasm = dfx_core::fs::read(&wasm_path)?;
+        if hex::encode(Sha256::digest(&wasm)) == canister.wasm_sha256 {
+            debug!(logger, "The {} wasm was found in the cache.", canister.name);
+            return Ok(wasm);
+        }
+    }
+
+    info!(logger, "Downloading the {} canister", canister.name);
+    let wasm = download_file(&Url::parse(&canister.wasm_url)?).await?;
+    let sha256 = hex::encode(Sha256::digest(&wasm));
+    if sha256 != canister.wasm_sha256 {
+        bail!(
+            "The sha256 hash of {} is {}, but expected {}.",
+            canister.wasm_url,
+            sha256,
+            canister.wasm_sha256
+        );
+    }
+    ensure_parent_dir_exists(&wasm_path)?;
+    dfx_core::fs::write(&wasm_path, &wasm)?;
+    Ok(wasm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_canister_ids() {
+        assert_eq!(
+            MAINNET_GOVERNANCE_CANISTER_ID,
+            Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap()
+        );
+        assert_eq!(
+            MAINNET_II_CANISTER_ID,
+            Principal::from_text("rdmx6-jaaaa-aaaaa-aaadq-cai").unwrap()
+        );
+        assert_eq!(
+            MAINNET_CYCLES_LEDGER_CANISTER_ID,
+            Principal::from_text("um5iw-rqaaa-aaaaq-qaaba-cai").unwrap()
+        );
+    }
+
+    #[test]
+    fn wasm_hashes_are_pinned() {
+        for sha256 in [
+            ICP_LEDGER_WASM_SHA256,
+            CMC_WASM_SHA256,
+            NNS_GOVERNANCE_WASM_SHA256,
+            INTERNET_IDENTITY_WASM_SHA256,
+            CYCLES_LEDGER_WASM_SHA256,
+        ] {
+            assert_eq!(hex::decode(sha256).map(|hash| hash.len()), Ok(32));
+            assert!(
+                sha256.chars().any(|c| c != '0'),
+                "{sha256} is a placeholder, not the hash of a release"
+            );
+        }
+    }
+
+    #[test]
+    fn nns_governance_init_arg_is_candid() {
+        candid_parser::parse_idl_args(&nns_governance_init_arg()).unwrap();
+    }
+}
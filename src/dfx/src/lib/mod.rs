@@ -2,6 +2,8 @@ pub mod agent;
 pub mod builders;
 pub mod canister_info;
 pub mod canister_logs;
+pub mod config_lint;
+pub mod cycles_history;
 pub mod cycles_ledger_types;
 pub mod deps;
 pub mod dfxvm;
@@ -9,6 +11,7 @@ pub mod diagnosis;
 pub mod environment;
 pub mod error;
 pub mod error_code;
+pub mod history_types;
 pub mod ic_attributes;
 pub mod identity;
 pub mod info;
@@ -35,6 +38,10 @@ pub mod root_key;
 pub mod sign;
 pub mod state_tree;
 pub mod subnet;
+pub mod table;
 pub mod telemetry;
+pub mod token;
+pub mod transaction_history;
 pub mod warning;
 pub mod wasm;
+pub mod watch;
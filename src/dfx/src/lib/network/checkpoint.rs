@@ -0,0 +1,297 @@
+//! Checkpoints of the state of a local network, for `dfx start --checkpoint`.
+use crate::lib::error::DfxResult;
+use anyhow::{bail, Context};
+use dfx_core::config::model::network_descriptor::{NetworkDescriptor, NetworkTypeDescriptor};
+use dfx_core::fs;
+use dfx_core::json::{load_json_file, save_json_file};
+use fn_error_context::context;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use walkdir::WalkDir;
+
+const METADATA_FILENAME: &str = "checkpoint.json";
+
+/// The entries of the network data directory that make up the network state.
+/// Pid and port files only describe a running network, so they are left out.
+const NETWORK_ENTRIES: [&str; 4] = [
+    "state",
+    "network-id",
+    "replica-effective-config.json",
+    "bitcoind",
+];
+
+/// The entries of the project's `.dfx/<network>` directory that refer to the network state.
+const PROJECT_ENTRIES: [&str; 2] = ["canister_ids.json", "network-id"];
+
+const WALLETS_FILENAME: &str = "wallets.json";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointCommand {
+    Save(String),
+    Restore(String),
+    List,
+}
+
+impl CheckpointCommand {
+    pub fn parse(args: &[String]) -> DfxResult<Self> {
+        let command = match args {
+            [action] if action == "list" => Self::List,
+            [action, name] if action == "save" => Self::Save(validate_name(name)?),
+            [action, name] if action == "restore" => Self::Restore(validate_name(name)?),
+            [action] if action == "save" || action == "restore" => {
+                bail!("`--checkpoint {action}` requires a checkpoint name.")
+            }
+            [action, _] if action == "list" => bail!("`--checkpoint list` does not take a name."),
+            [action, ..] => bail!(
+                "Unknown checkpoint action '{action}'. Expected 'save <name>', 'restore <name>' or 'list'."
+            ),
+            [] => bail!("Missing checkpoint action."),
+        };
+        Ok(command)
+    }
+}
+
+fn validate_name(name: &str) -> DfxResult<String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Invalid checkpoint name '{name}'. Names may only contain letters, digits, '-' and '_'.");
+    }
+    Ok(name.to_string())
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CheckpointMetadata {
+    pub created: OffsetDateTime,
+    pub settings_digest: String,
+}
+
+pub struct Checkpoint {
+    pub name: String,
+    pub metadata: CheckpointMetadata,
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let created = self
+            .metadata
+            .created
+            .format(&Rfc3339)
+            .map_err(|_| fmt::Error)?;
+        write!(f, "{}  {}", self.name, created)
+    }
+}
+
+/// Saves and restores the state of a stopped local network,
+/// together with the canister ids and wallets that the project recorded for it.
+pub struct Checkpoints<'a> {
+    network_descriptor: &'a NetworkDescriptor,
+    project_network_dir: Option<PathBuf>,
+}
+
+impl<'a> Checkpoints<'a> {
+    pub fn new(
+        network_descriptor: &'a NetworkDescriptor,
+        project_temp_dir: Option<PathBuf>,
+    ) -> Self {
+        let project_network_dir = project_temp_dir.map(|dir| dir.join(&network_descriptor.name));
+        Self {
+            network_descriptor,
+            project_network_dir,
+        }
+    }
+
+    fn dir(&self) -> DfxResult<PathBuf> {
+        Ok(self
+            .network_descriptor
+            .local_server_descriptor()?
+            .checkpoints_dir())
+    }
+
+    fn wallets_path(&self) -> Option<&Path> {
+        match &self.network_descriptor.r#type {
+            NetworkTypeDescriptor::Ephemeral { wallet_config_path } => {
+                wallet_config_path.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    #[context("Failed to list checkpoints.")]
+    pub fn list(&self) -> DfxResult<Vec<Checkpoint>> {
+        let dir = self.dir()?;
+        let mut checkpoints = vec![];
+        if !dir.is_dir() {
+            return Ok(checkpoints);
+        }
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let metadata_path = path.join(METADATA_FILENAME);
+            if !metadata_path.is_file() {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            checkpoints.push(Checkpoint {
+                name: name.to_string(),
+                metadata: load_json_file(&metadata_path)?,
+            });
+        }
+        checkpoints.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(checkpoints)
+    }
+
+    #[context("Failed to save checkpoint '{}'.", name)]
+    pub fn save(&self, name: &str) -> DfxResult {
+        let local_server_descriptor = self.network_descriptor.local_server_descriptor()?;
+        if !local_server_descriptor.state_dir().is_dir() {
+            bail!(
+                "Network '{}' has no state to save. Start it with `dfx start` first.",
+                self.network_descriptor.name
+            );
+        }
+        let checkpoint_dir = self.dir()?.join(name);
+        if checkpoint_dir.exists() {
+            fs::remove_dir_all(&checkpoint_dir)?;
+        }
+        copy_entries(
+            &local_server_descriptor.data_dir_by_settings_digest(),
+            &checkpoint_dir.join("network"),
+            &NETWORK_ENTRIES,
+        )?;
+        if let Some(project_network_dir) = &self.project_network_dir {
+            copy_entries(
+                project_network_dir,
+                &checkpoint_dir.join("project"),
+                &PROJECT_ENTRIES,
+            )?;
+        }
+        if let Some(wallets_path) = self.wallets_path() {
+            if wallets_path.is_file() {
+                fs::copy(wallets_path, &checkpoint_dir.join(WALLETS_FILENAME))?;
+            }
+        }
+        let metadata = CheckpointMetadata {
+            created: OffsetDateTime::now_utc(),
+            settings_digest: local_server_descriptor.settings_digest().to_string(),
+        };
+        save_json_file(&checkpoint_dir.join(METADATA_FILENAME), &metadata)?;
+        Ok(())
+    }
+
+    /// Replaces the network state with the one saved in the checkpoint.
+    #[context("Failed to restore checkpoint '{}'.", name)]
+    pub fn restore(&self, name: &str) -> DfxResult {
+        let local_server_descriptor = self.network_descriptor.local_server_descriptor()?;
+        let checkpoint_dir = self.dir()?.join(name);
+        let metadata_path = checkpoint_dir.join(METADATA_FILENAME);
+        if !metadata_path.is_file() {
+            bail!("Checkpoint '{name}' does not exist. Run `dfx start --checkpoint list` to list checkpoints.");
+        }
+        let metadata: CheckpointMetadata = load_json_file(&metadata_path)?;
+        if metadata.settings_digest != local_server_descriptor.settings_digest() {
+            bail!(
+                "Checkpoint '{name}' was saved with different network settings. Restore it with the settings it was saved with."
+            );
+        }
+        replace_entries(
+            &checkpoint_dir.join("network"),
+            &local_server_descriptor.data_dir_by_settings_digest(),
+            &NETWORK_ENTRIES,
+        )?;
+        if let Some(project_network_dir) = &self.project_network_dir {
+            replace_entries(
+                &checkpoint_dir.join("project"),
+                project_network_dir,
+                &PROJECT_ENTRIES,
+            )?;
+        }
+        if let Some(wallets_path) = self.wallets_path() {
+            let saved_wallets_path = checkpoint_dir.join(WALLETS_FILENAME);
+            if saved_wallets_path.is_file() {
+                fs::create_dir_all(&fs::parent(wallets_path)?)?;
+                fs::copy(&saved_wallets_path, wallets_path)?;
+            } else if wallets_path.is_file() {
+                fs::remove_file(wallets_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies the `entries` of `from` that exist to `to`.
+fn copy_entries(from: &Path, to: &Path, entries: &[&str]) -> DfxResult {
+    fs::create_dir_all(to)?;
+    for entry in entries {
+        let source = from.join(entry);
+        if source.exists() {
+            copy_recursively(&source, &to.join(entry))?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes the `entries` of `to`, then copies the ones saved in `from`.
+fn replace_entries(from: &Path, to: &Path, entries: &[&str]) -> DfxResult {
+    for entry in entries {
+        let target = to.join(entry);
+        if target.is_dir() {
+            fs::remove_dir_all(&target)?;
+        } else if target.exists() {
+            fs::remove_file(&target)?;
+        }
+    }
+    copy_entries(from, to, entries)
+}
+
+fn copy_recursively(from: &Path, to: &Path) -> DfxResult {
+    for entry in WalkDir::new(from) {
+        let entry = entry.with_context(|| format!("Failed to walk {}.", from.display()))?;
+        let relative = entry
+            .path()
+            .strip_prefix(from)
+            .expect("walked path is not below its root");
+        let target = to.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_checkpoint_command() {
+        assert_eq!(
+            CheckpointCommand::parse(&args(&["save", "seeded"])).unwrap(),
+            CheckpointCommand::Save("seeded".to_string())
+        );
+        assert_eq!(
+            CheckpointCommand::parse(&args(&["restore", "with_tokens-2"])).unwrap(),
+            CheckpointCommand::Restore("with_tokens-2".to_string())
+        );
+        assert_eq!(
+            CheckpointCommand::parse(&args(&["list"])).unwrap(),
+            CheckpointCommand::List
+        );
+        assert!(CheckpointCommand::parse(&args(&["save"])).is_err());
+        assert!(CheckpointCommand::parse(&args(&["list", "x"])).is_err());
+        assert!(CheckpointCommand::parse(&args(&["save", "../x"])).is_err());
+        assert!(CheckpointCommand::parse(&args(&["delete", "x"])).is_err());
+    }
+}
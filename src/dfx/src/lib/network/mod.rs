@@ -1,2 +1,4 @@
+pub mod checkpoint;
 pub mod id;
 pub mod network_opt;
+pub mod process;
@@ -0,0 +1,37 @@
+//! The processes of running local networks.
+use dfx_core::config::model::local_server_descriptor::LocalServerDescriptor;
+use sysinfo::{Pid, Process, ProcessExt, System, SystemExt};
+
+/// The pid of the `dfx start` process that runs the local network, if it is running.
+pub fn running_dfx_pid(
+    system: &System,
+    local_server_descriptor: &LocalServerDescriptor,
+) -> Option<Pid> {
+    local_server_descriptor
+        .dfx_pid_paths()
+        .iter()
+        .filter_map(|pid_path| std::fs::read_to_string(pid_path).ok())
+        .filter_map(|s| s.trim().parse::<Pid>().ok())
+        .find(|pid| system.process(*pid).is_some())
+}
+
+fn list_all_descendants<'a>(system: &'a System, proc: &'a Process) -> Vec<&'a Process> {
+    let mut result = Vec::new();
+
+    for process in system.processes().values() {
+        if let Some(ppid) = process.parent() {
+            if ppid == proc.pid() {
+                result.extend(list_all_descendants(system, process));
+            }
+        }
+    }
+    result.push(proc);
+
+    result
+}
+
+/// Recursively list all descendants of a process.
+pub fn descendant_pids(system: &System, proc: &Process) -> Vec<Pid> {
+    let processes = list_all_descendants(system, proc);
+    processes.iter().map(|proc| proc.pid()).collect()
+}
@@ -121,35 +121,60 @@ The command line value will be used.",
         specified_id = None;
     }
 
+    let subnet = config_interface
+        .canisters
+        .as_ref()
+        .and_then(|canisters| canisters.get(canister_name))
+        .and_then(|canister| canister.subnet.as_deref());
+    let subnet_effective_canister_id = match subnet {
+        Some(subnet) => get_local_subnet_effective_canister_id(env, canister_name, subnet)?,
+        None => None,
+    };
+    if let (Some(subnet), Some(_), CallSender::Wallet(wallet_id)) =
+        (subnet, subnet_effective_canister_id, call_sender)
+    {
+        // A wallet creates canisters on its own subnet.
+        bail!(
+            "Canister '{}' is pinned to subnet '{}', but wallet {} cannot create canisters on another subnet. Create it without --wallet.",
+            canister_name,
+            subnet,
+            wallet_id
+        );
+    }
+
     // Replace call_sender with wallet canister unless:
     // 1. specified_id is in effect OR
-    // 2. --no-wallet is set explicitly OR
-    // 3. call_sender is already wallet
-    let call_sender =
-        if specified_id.is_some() || no_wallet || matches!(call_sender, CallSender::Wallet(_)) {
-            *call_sender
-        } else {
-            match get_or_create_wallet_canister(
-                env,
-                env.get_network_descriptor(),
-                env.get_selected_identity().expect("No selected identity"),
-            )
-            .await
-            {
-                Ok(wallet) => CallSender::Wallet(*wallet.canister_id_()),
-                Err(err) => {
-                    if matches!(
-                        err,
-                        GetOrCreateWalletCanisterError::NoWalletConfigured { .. }
-                    ) {
-                        debug!(env.get_logger(), "No wallet configured.");
-                        *call_sender
-                    } else {
-                        bail!(err)
-                    }
+    // 2. the canister is pinned to a local subnet OR
+    // 3. --no-wallet is set explicitly OR
+    // 4. call_sender is already wallet
+    let call_sender = if specified_id.is_some()
+        || subnet_effective_canister_id.is_some()
+        || no_wallet
+        || matches!(call_sender, CallSender::Wallet(_))
+    {
+        *call_sender
+    } else {
+        match get_or_create_wallet_canister(
+            env,
+            env.get_network_descriptor(),
+            env.get_selected_identity().expect("No selected identity"),
+        )
+        .await
+        {
+            Ok(wallet) => CallSender::Wallet(*wallet.canister_id_()),
+            Err(err) => {
+                if matches!(
+                    err,
+                    GetOrCreateWalletCanisterError::NoWalletConfigured { .. }
+                ) {
+                    debug!(env.get_logger(), "No wallet configured.");
+                    *call_sender
+                } else {
+                    bail!(err)
                 }
             }
-        };
+        }
+    };
 
     let spinner = env.new_spinner(format!("Creating canister {canister_name}...").into());
     let agent = env.get_agent();
@@ -157,7 +182,7 @@ The command line value will be used.",
         CallSender::SelectedId => {
             let auto_wallet_disabled = std::env::var("DFX_DISABLE_AUTO_WALLET").is_ok();
             let ic_network = env.get_network_descriptor().is_ic;
-            if ic_network || auto_wallet_disabled {
+            if (ic_network || auto_wallet_disabled) && subnet_effective_canister_id.is_none() {
                 create_with_cycles_ledger(
                     env,
                     agent,
@@ -170,8 +195,17 @@ The command line value will be used.",
                 )
                 .await
             } else {
-                create_with_management_canister(env, agent, with_cycles, specified_id, settings)
-                    .await
+                // The effective canister ID determines the subnet the canister is created on.
+                let effective_canister_id =
+                    subnet_effective_canister_id.unwrap_or_else(|| env.get_effective_canister_id());
+                create_with_management_canister(
+                    agent,
+                    effective_canister_id,
+                    with_cycles,
+                    specified_id,
+                    settings,
+                )
+                .await
             }
         }
         CallSender::Impersonate(_) => {
@@ -195,9 +229,48 @@ The command line value will be used.",
     Ok(())
 }
 
-async fn create_with_management_canister(
+/// Resolves the subnet a canister is pinned to in dfx.json to a canister ID on that subnet.
+/// Returns None if the network is not a local PocketIC network.
+fn get_local_subnet_effective_canister_id(
     env: &dyn Environment,
+    canister_name: &str,
+    subnet: &str,
+) -> DfxResult<Option<Principal>> {
+    let network = env.get_network_descriptor();
+    let effective_config = match &network.local_server_descriptor {
+        Some(local_server_descriptor) => local_server_descriptor.effective_config()?,
+        None => None,
+    };
+    let Some(effective_config) = effective_config.filter(|config| !config.get_subnets().is_empty())
+    else {
+        warn!(
+            env.get_logger(),
+            "Ignoring subnet '{}' of canister '{}': network '{}' is not a local PocketIC network.",
+            subnet,
+            canister_name,
+            network.name
+        );
+        return Ok(None);
+    };
+    let Some(local_subnet) = effective_config.find_subnet(subnet) else {
+        let available: Vec<String> = effective_config
+            .get_subnets()
+            .iter()
+            .map(|subnet| subnet.name())
+            .collect();
+        bail!(
+            "Canister '{}' is configured for subnet '{}', which does not exist. Available subnets: {}",
+            canister_name,
+            subnet,
+            available.join(", ")
+        );
+    };
+    Ok(local_subnet.effective_canister_id())
+}
+
+async fn create_with_management_canister(
     agent: &Agent,
+    effective_canister_id: Principal,
     with_cycles: Option<u128>,
     specified_id: Option<Principal>,
     settings: DfxCanisterSettings,
@@ -206,7 +279,7 @@ async fn create_with_management_canister(
     let mut builder = mgr
         .create_canister()
         .as_provisional_create_with_amount(with_cycles)
-        .with_effective_canister_id(env.get_effective_canister_id());
+        .with_effective_canister_id(effective_canister_id);
     if let Some(sid) = specified_id {
         builder = builder.as_provisional_create_with_specified_id(sid);
     }
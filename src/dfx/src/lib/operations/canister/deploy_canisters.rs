@@ -7,7 +7,7 @@ use crate::lib::ic_attributes::CanisterSettings;
 use crate::lib::installers::assets::prepare_assets_for_proposal;
 use crate::lib::models::canister::CanisterPool;
 use crate::lib::operations::canister::deploy_canisters::DeployMode::{
-    ComputeEvidence, ForceReinstallSingleCanister, NormalDeploy, PrepareForProposal,
+    ComputeEvidence, ForceReinstallSingleCanister, NormalDeploy, PrepareForProposal, RedeployOnly,
 };
 use crate::lib::operations::canister::motoko_playground::reserve_canister_with_playground;
 use crate::lib::operations::canister::{
@@ -18,6 +18,7 @@ use crate::util::clap::subnet_selection_opt::SubnetSelectionType;
 use anyhow::{anyhow, bail, Context};
 use candid::Principal;
 use dfx_core::config::model::canister_id_store::CanisterIdStore;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::config::model::dfinity::Config;
 use dfx_core::identity::CallSender;
 use fn_error_context::context;
@@ -36,13 +37,15 @@ pub enum DeployMode {
     ForceReinstallSingleCanister(String),
     PrepareForProposal(String),
     ComputeEvidence(String),
+    /// Rebuilds and installs only these canisters, without their dependencies.
+    RedeployOnly(Vec<String>),
 }
 
 #[context("Failed while trying to deploy canisters.")]
 #[allow(clippy::too_many_arguments)]
 pub async fn deploy_canisters(
     env: &dyn Environment,
-    some_canister: Option<&str>,
+    canisters: &CanisterSelector,
     argument: Option<&str>,
     argument_type: Option<&str>,
     deploy_mode: &DeployMode,
@@ -68,7 +71,7 @@ pub async fn deploy_canisters(
     let initial_canister_id_store = env.get_canister_id_store()?;
 
     let pull_canisters_in_config = config.get_config().get_pull_canisters()?;
-    if let Some(canister_name) = some_canister {
+    if let Some(canister_name) = canisters.single() {
         if pull_canisters_in_config.contains_key(canister_name) {
             bail!(
                 "{0} is a pull dependency. Please deploy it using `dfx deps deploy {0}`",
@@ -77,7 +80,7 @@ pub async fn deploy_canisters(
         }
     }
 
-    let canisters_to_deploy = canister_with_dependencies(&config, some_canister)?;
+    let canisters_to_deploy = canister_with_dependencies(&config, canisters)?;
 
     let canisters_to_build = match deploy_mode {
         PrepareForProposal(canister_name) | ComputeEvidence(canister_name) => {
@@ -87,6 +90,7 @@ pub async fn deploy_canisters(
             // don't force-reinstall the dependencies too.
             vec![String::from(canister_name)]
         }
+        RedeployOnly(canister_names) => canister_names.clone(),
         NormalDeploy => canisters_to_deploy
             .clone()
             .into_iter()
@@ -105,7 +109,7 @@ pub async fn deploy_canisters(
         .filter(|canister_name| !pull_canisters_in_config.contains_key(canister_name))
         .collect();
 
-    if some_canister.is_some() {
+    if *canisters != CanisterSelector::All {
         info!(log, "Deploying: {}", canisters_to_install.join(" "));
     } else {
         info!(log, "Deploying all canisters.");
@@ -144,7 +148,7 @@ pub async fn deploy_canisters(
     .await?;
 
     match deploy_mode {
-        NormalDeploy | ForceReinstallSingleCanister(_) => {
+        NormalDeploy | ForceReinstallSingleCanister(_) | RedeployOnly(_) => {
             install_canisters(
                 env,
                 &canisters_to_install,
@@ -178,11 +182,11 @@ pub async fn deploy_canisters(
 #[context("Failed to collect canisters and their dependencies.")]
 fn canister_with_dependencies(
     config: &Config,
-    some_canister: Option<&str>,
+    canisters: &CanisterSelector,
 ) -> DfxResult<Vec<String>> {
     let mut canister_names = config
         .get_config()
-        .get_canister_names_with_dependencies(some_canister)?;
+        .get_selected_canister_names_with_dependencies(canisters)?;
     canister_names.sort();
     Ok(canister_names)
 }
@@ -20,6 +20,7 @@ use candid::Principal as CanisterId;
 use candid::Principal;
 use candid::{decode_args, encode_args};
 use dfx_core::canister::build_wallet_canister;
+use dfx_core::config::model::canister_selector::CanisterSelector;
 use dfx_core::config::model::dfinity::Config;
 use dfx_core::identity::CallSender;
 use fn_error_context::context;
@@ -468,6 +469,22 @@ pub fn all_project_canisters_with_ids(env: &dyn Environment, config: &Config) ->
         .unwrap_or_default()
 }
 
+/// Returns the names of the project canisters selected by `selector`,
+/// skipping those that are remote on the current network.
+pub fn selected_project_canisters(
+    env: &dyn Environment,
+    selector: &CanisterSelector,
+) -> DfxResult<Vec<String>> {
+    let config = env.get_config_or_anyhow()?;
+    let mut canisters = vec![];
+    for canister in config.get_config().get_selected_canister_names(selector)? {
+        if !skip_remote_canister(env, &canister)? {
+            canisters.push(canister);
+        }
+    }
+    Ok(canisters)
+}
+
 #[context("Failed to take snapshot in canister {canister_id}")]
 pub async fn take_canister_snapshot(
     env: &dyn Environment,
@@ -8,7 +8,7 @@ use crate::lib::ledger_types::{
 };
 use crate::lib::nns_types::account_identifier::{AccountIdentifier, Subaccount};
 use crate::lib::nns_types::icpts::ICPTs;
-use crate::lib::operations::ledger::transfer;
+use crate::lib::operations::ledger::{transfer, transfer_call};
 use crate::util::clap::subnet_selection_opt::SubnetSelectionType;
 use candid::{Decode, Encode, Principal};
 use ic_agent::Agent;
@@ -48,6 +48,21 @@ pub async fn transfer_cmc(
     .await
 }
 
+/// The method and argument of the ledger call of `transfer_cmc`, to sign it without sending it.
+pub fn transfer_cmc_call(
+    memo: Memo,
+    amount: ICPTs,
+    fee: ICPTs,
+    from_subaccount: Option<Subaccount>,
+    to_principal: Principal,
+    created_at_time: u64,
+) -> DfxResult<(&'static str, Vec<u8>)> {
+    let to_subaccount = Subaccount::from(&to_principal);
+    let to =
+        AccountIdentifier::new(MAINNET_CYCLE_MINTER_CANISTER_ID, Some(to_subaccount)).to_address();
+    transfer_call(memo, amount, fee, from_subaccount, to, created_at_time)
+}
+
 pub async fn notify_create(
     agent: &Agent,
     controller: Principal,
@@ -9,6 +9,7 @@ use crate::lib::cycles_ledger_types::withdraw::WithdrawError;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::ic_attributes::CanisterSettings as DfxCanisterSettings;
+use crate::lib::integrations::system_canisters::MAINNET_CYCLES_LEDGER_CANISTER_ID;
 use crate::lib::operations::canister::create_canister::{
     CANISTER_CREATE_FEE, CANISTER_INITIAL_CYCLE_BALANCE,
 };
@@ -39,8 +40,6 @@ use slog::{info, Logger};
 const WITHDRAW_METHOD: &str = "withdraw";
 const CREATE_CANISTER_METHOD: &str = "create_canister";
 const CYCLES_LEDGER_DEPOSIT_METHOD: &str = "deposit";
-const CYCLES_LEDGER_CANISTER_ID: Principal =
-    Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x02, 0x10, 0x00, 0x02, 0x01, 0x01]);
 
 pub async fn balance(
     agent: &Agent,
@@ -49,7 +48,7 @@ pub async fn balance(
 ) -> DfxResult<u128> {
     let canister = Canister::builder()
         .with_agent(agent)
-        .with_canister_id(CYCLES_LEDGER_CANISTER_ID)
+        .with_canister_id(MAINNET_CYCLES_LEDGER_CANISTER_ID)
         .build()?;
     let arg = icrc1::account::Account { owner, subaccount };
 
@@ -85,7 +84,7 @@ pub async fn transfer(
 ) -> DfxResult<BlockIndex> {
     let canister = Canister::builder()
         .with_agent(agent)
-        .with_canister_id(CYCLES_LEDGER_CANISTER_ID)
+        .with_canister_id(MAINNET_CYCLES_LEDGER_CANISTER_ID)
         .build()?;
 
     let retry_policy = ExponentialBackoff::default();
@@ -145,7 +144,7 @@ pub async fn transfer_from(
 ) -> DfxResult<BlockIndex> {
     let canister = Canister::builder()
         .with_agent(agent)
-        .with_canister_id(CYCLES_LEDGER_CANISTER_ID)
+        .with_canister_id(MAINNET_CYCLES_LEDGER_CANISTER_ID)
         .build()?;
 
     let retry_policy = ExponentialBackoff::default();
@@ -204,7 +203,7 @@ pub async fn approve(
 ) -> DfxResult<BlockIndex> {
     let canister = Canister::builder()
         .with_agent(agent)
-        .with_canister_id(CYCLES_LEDGER_CANISTER_ID)
+        .with_canister_id(MAINNET_CYCLES_LEDGER_CANISTER_ID)
         .build()?;
 
     let retry_policy = ExponentialBackoff::default();
@@ -258,7 +257,7 @@ pub async fn withdraw(
 ) -> DfxResult<BlockIndex> {
     let canister = Canister::builder()
         .with_agent(agent)
-        .with_canister_id(CYCLES_LEDGER_CANISTER_ID)
+        .with_canister_id(MAINNET_CYCLES_LEDGER_CANISTER_ID)
         .build()?;
 
     let retry_policy = ExponentialBackoff::default();
@@ -306,6 +305,23 @@ pub async fn withdraw(
     Ok(block_index)
 }
 
+/// The method and argument of a `withdraw` call, to sign it without sending it.
+pub fn withdraw_call(
+    to: Principal,
+    amount: u128,
+    created_at_time: u64,
+    from_subaccount: Option<icrc1::account::Subaccount>,
+) -> DfxResult<(&'static str, Vec<u8>)> {
+    let arg = Encode!(&cycles_ledger_types::withdraw::WithdrawArgs {
+        from_subaccount,
+        to,
+        created_at_time: Some(created_at_time),
+        amount: Nat::from(amount),
+    })
+    .context("Failed to encode arguments.")?;
+    Ok((WITHDRAW_METHOD, arg))
+}
+
 #[context("Failed to create canister via cycles ledger.")]
 pub async fn create_with_cycles_ledger(
     env: &dyn Environment,
@@ -344,7 +360,7 @@ pub async fn create_with_cycles_ledger(
     .unwrap();
     let result = loop {
         match agent
-            .update(&CYCLES_LEDGER_CANISTER_ID, CREATE_CANISTER_METHOD)
+            .update(&MAINNET_CYCLES_LEDGER_CANISTER_ID, CREATE_CANISTER_METHOD)
             .with_arg(arg.clone())
             .await
         {
@@ -394,7 +410,7 @@ pub async fn wallet_deposit_to_cycles_ledger(
     build_wallet_canister(wallet_id, agent)
         .await?
         .call128(
-            CYCLES_LEDGER_CANISTER_ID,
+            MAINNET_CYCLES_LEDGER_CANISTER_ID,
             CYCLES_LEDGER_DEPOSIT_METHOD,
             Argument::from_candid((DepositArg { to, memo: None },)),
             cycles_to_withdraw,
@@ -407,6 +423,6 @@ pub async fn wallet_deposit_to_cycles_ledger(
 fn ledger_canister_id_text_representation() {
     assert_eq!(
         Principal::from_text("um5iw-rqaaa-aaaaq-qaaba-cai").unwrap(),
-        CYCLES_LEDGER_CANISTER_ID
+        MAINNET_CYCLES_LEDGER_CANISTER_ID
     );
 }
@@ -1,9 +1,11 @@
 use crate::lib::diagnosis::DiagnosedError;
+use crate::lib::history_types::icrc3::{GetBlocksArgs, GetBlocksResult};
+use crate::lib::history_types::index::{GetAccountTransactionsArgs, GetTransactionsResult};
 use crate::lib::ledger_types::{AccountIdBlob, BlockHeight, Memo, TransferError};
 use crate::lib::nns_types::account_identifier::Subaccount;
 use crate::lib::operations::{
-    ICRC1_BALANCE_OF_METHOD, ICRC1_TRANSFER_METHOD, ICRC2_ALLOWANCE_METHOD, ICRC2_APPROVE_METHOD,
-    ICRC2_TRANSFER_FROM_METHOD,
+    ICRC1_BALANCE_OF_METHOD, ICRC1_METADATA_METHOD, ICRC1_TRANSFER_METHOD, ICRC2_ALLOWANCE_METHOD,
+    ICRC2_APPROVE_METHOD, ICRC2_TRANSFER_FROM_METHOD,
 };
 use crate::lib::{
     error::DfxResult,
@@ -26,6 +28,7 @@ use ic_agent::{
     lookup_value, Agent, AgentError,
 };
 use ic_utils::{call::SyncCall, Canister};
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
 use icrc_ledger_types::icrc1;
 use icrc_ledger_types::icrc1::transfer::BlockIndex;
 use icrc_ledger_types::icrc1::transfer::TransferError as ICRC1TransferError;
@@ -38,6 +41,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 const ACCOUNT_BALANCE_METHOD: &str = "account_balance";
 const TRANSFER_METHOD: &str = "transfer";
+const INDEX_GET_ACCOUNT_TRANSACTIONS_METHOD: &str = "get_account_transactions";
 
 pub async fn balance(
     agent: &Agent,
@@ -80,6 +84,65 @@ pub async fn icrc1_balance(
     Ok(result)
 }
 
+/// Returns the metadata of an ICRC-1 ledger, like its symbol, decimals and fee.
+pub async fn icrc1_metadata(
+    agent: &Agent,
+    canister_id: &Principal,
+) -> DfxResult<Vec<(String, MetadataValue)>> {
+    let canister = Canister::builder()
+        .with_agent(agent)
+        .with_canister_id(*canister_id)
+        .build()?;
+
+    let (result,) = canister.query(ICRC1_METADATA_METHOD).build().call().await?;
+
+    Ok(result)
+}
+
+/// Reads blocks of an ICRC-3 block log, from a ledger or from one of its archives.
+/// `method` is `icrc3_get_blocks` for the ledger, and comes from the callback for an archive.
+pub async fn icrc3_get_blocks(
+    agent: &Agent,
+    canister_id: &Principal,
+    method: Option<&str>,
+    args: Vec<GetBlocksArgs>,
+) -> DfxResult<GetBlocksResult> {
+    let canister = Canister::builder()
+        .with_agent(agent)
+        .with_canister_id(*canister_id)
+        .build()?;
+
+    let (result,) = canister
+        .query(method.unwrap_or(ICRC3_GET_BLOCKS_METHOD))
+        .with_arg(args)
+        .build()
+        .call()
+        .await?;
+
+    Ok(result)
+}
+
+/// Reads the transactions of an account from an ICRC index canister, newest first.
+pub async fn index_get_account_transactions(
+    agent: &Agent,
+    index_id: &Principal,
+    args: GetAccountTransactionsArgs,
+) -> DfxResult<GetTransactionsResult> {
+    let canister = Canister::builder()
+        .with_agent(agent)
+        .with_canister_id(*index_id)
+        .build()?;
+
+    let (result,) = canister
+        .query(INDEX_GET_ACCOUNT_TRANSACTIONS_METHOD)
+        .with_arg(args)
+        .build()
+        .call()
+        .await?;
+
+    Ok(result)
+}
+
 /// Returns XDR-permyriad (i.e. ten-thousandths-of-an-XDR) per ICP.
 pub async fn xdr_permyriad_per_icp(agent: &Agent) -> DfxResult<u64> {
     let canister = Canister::builder()
@@ -203,14 +266,37 @@ pub async fn transfer(
     Ok(block_height)
 }
 
+/// The method and argument of a `transfer` call, to sign it without sending it.
+pub fn transfer_call(
+    memo: Memo,
+    amount: ICPTs,
+    fee: ICPTs,
+    from_subaccount: Option<Subaccount>,
+    to: AccountIdBlob,
+    created_at_time: u64,
+) -> DfxResult<(&'static str, Vec<u8>)> {
+    let arg = Encode!(&TransferArgs {
+        memo,
+        amount,
+        fee,
+        from_subaccount,
+        to,
+        created_at_time: Some(TimeStamp {
+            timestamp_nanos: created_at_time,
+        }),
+    })
+    .context("Failed to encode arguments.")?;
+    Ok((TRANSFER_METHOD, arg))
+}
+
 pub async fn icrc1_transfer(
     agent: &Agent,
     logger: &Logger,
     canister_id: &Principal,
     from_subaccount: Option<icrc1::account::Subaccount>,
     to: icrc1::account::Account,
-    amount: ICPTs,
-    fee: Option<ICPTs>,
+    amount: Nat,
+    fee: Option<Nat>,
     memo: Option<u64>,
     created_at_time: u64,
 ) -> DfxResult<BlockIndex> {
@@ -225,10 +311,10 @@ pub async fn icrc1_transfer(
         let arg = icrc1::transfer::TransferArg {
             from_subaccount,
             to,
-            fee: fee.map(|value| Nat::from(value.get_e8s())),
+            fee: fee.clone(),
             created_at_time: Some(created_at_time),
             memo: memo.map(|v| v.into()),
-            amount: Nat::from(amount.get_e8s()),
+            amount: amount.clone(),
         };
         match canister
             .update(ICRC1_TRANSFER_METHOD)
@@ -261,6 +347,27 @@ pub async fn icrc1_transfer(
     Ok(block_index)
 }
 
+/// The method and argument of an `icrc1_transfer` call, to sign it without sending it.
+pub fn icrc1_transfer_call(
+    from_subaccount: Option<icrc1::account::Subaccount>,
+    to: icrc1::account::Account,
+    amount: Nat,
+    fee: Option<Nat>,
+    memo: Option<u64>,
+    created_at_time: u64,
+) -> DfxResult<(&'static str, Vec<u8>)> {
+    let arg = Encode!(&icrc1::transfer::TransferArg {
+        from_subaccount,
+        to,
+        fee,
+        created_at_time: Some(created_at_time),
+        memo: memo.map(|v| v.into()),
+        amount,
+    })
+    .context("Failed to encode arguments.")?;
+    Ok((ICRC1_TRANSFER_METHOD, arg))
+}
+
 pub async fn icrc2_transfer_from(
     agent: &Agent,
     logger: &Logger,
@@ -268,8 +375,8 @@ pub async fn icrc2_transfer_from(
     spender_subaccount: Option<icrc1::account::Subaccount>,
     from: icrc1::account::Account,
     to: icrc1::account::Account,
-    amount: ICPTs,
-    fee: Option<ICPTs>,
+    amount: Nat,
+    fee: Option<Nat>,
     created_at_time: u64,
     memo: Option<u64>,
 ) -> DfxResult<BlockIndex> {
@@ -285,10 +392,10 @@ pub async fn icrc2_transfer_from(
             spender_subaccount,
             from,
             to,
-            fee: fee.map(|value| Nat::from(value.get_e8s())),
+            fee: fee.clone(),
             created_at_time: Some(created_at_time),
             memo: memo.map(|v| v.into()),
-            amount: Nat::from(amount.get_e8s()),
+            amount: amount.clone(),
         };
         match canister
             .update(ICRC2_TRANSFER_FROM_METHOD)
@@ -327,9 +434,9 @@ pub async fn icrc2_approve(
     from_subaccount: Option<icrc1::account::Subaccount>,
     spender: Principal,
     spender_subaccount: Option<icrc1::account::Subaccount>,
-    amount: ICPTs,
-    expected_allowance: Option<ICPTs>,
-    fee: Option<ICPTs>,
+    amount: Nat,
+    expected_allowance: Option<Nat>,
+    fee: Option<Nat>,
     created_at_time: u64,
     expires_at: Option<u64>,
     memo: Option<u64>,
@@ -344,15 +451,15 @@ pub async fn icrc2_approve(
     let block_index = retry(retry_policy, || async {
         let arg = icrc2::approve::ApproveArgs {
             from_subaccount,
-            fee: fee.map(|value| Nat::from(value.get_e8s())),
+            fee: fee.clone(),
             created_at_time: Some(created_at_time),
             memo: memo.map(|v| v.into()),
-            amount: Nat::from(amount.get_e8s()),
+            amount: amount.clone(),
             spender: icrc1::account::Account {
                 owner: spender,
                 subaccount: spender_subaccount,
             },
-            expected_allowance: expected_allowance.map(|value| Nat::from(value.get_e8s())),
+            expected_allowance: expected_allowance.clone(),
             expires_at,
         };
         match canister
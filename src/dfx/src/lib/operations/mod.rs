@@ -4,7 +4,9 @@ pub mod cycles_ledger;
 pub mod ledger;
 
 const ICRC1_BALANCE_OF_METHOD: &str = "icrc1_balance_of";
+const ICRC1_METADATA_METHOD: &str = "icrc1_metadata";
 const ICRC1_TRANSFER_METHOD: &str = "icrc1_transfer";
 const ICRC2_ALLOWANCE_METHOD: &str = "icrc2_allowance";
 const ICRC2_APPROVE_METHOD: &str = "icrc2_approve";
 const ICRC2_TRANSFER_FROM_METHOD: &str = "icrc2_transfer_from";
+const ICRC3_GET_BLOCKS_METHOD: &str = "icrc3_get_blocks";
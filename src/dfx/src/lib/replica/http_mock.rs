@@ -0,0 +1,332 @@
+//! Answers HTTPS outcalls of a local PocketIC instance from mock rules,
+//! for `canister_http.mock` in the network configuration.
+use crate::lib::error::DfxResult;
+use anyhow::{bail, Context};
+use candid::Principal;
+use dfx_core::fs;
+use dfx_core::json::load_json_file;
+use fn_error_context::context;
+use pocket_ic::common::rest::{
+    CanisterHttpHeader, CanisterHttpMethod, CanisterHttpReject, CanisterHttpReply,
+    CanisterHttpRequest, CanisterHttpResponse, MockCanisterHttpResponse,
+};
+use pocket_ic::nonblocking::PocketIc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The reject code of `SYS_TRANSIENT`, which canisters also get if the request fails on mainnet.
+const SYS_TRANSIENT: u64 = 2;
+
+/// The contents of the mock rules file.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct HttpMockRulesFile {
+    rules: Vec<HttpMockRule>,
+}
+
+/// Answers the outcalls whose URL matches `url` (and whose method matches `method`, if set).
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct HttpMockRule {
+    /// A regular expression that must match the whole URL.
+    url: String,
+    /// GET, POST or HEAD.
+    method: Option<String>,
+    #[serde(default = "default_status")]
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    /// A file with the body, relative to the rules file.
+    body_file: Option<PathBuf>,
+    /// How long to wait before answering.
+    #[serde(default)]
+    latency_ms: u64,
+    /// Rejects the outcall with this message instead of answering it.
+    reject: Option<String>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+struct CompiledRule {
+    url: Regex,
+    method: Option<String>,
+    response: CanisterHttpResponse,
+    latency: Duration,
+}
+
+/// An outcall in the request log.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HttpOutcallLogEntry {
+    /// RFC 3339
+    pub time: String,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// The index of the rule that answered the outcall.
+    pub rule: Option<usize>,
+    /// The status of the reply, or `None` if the outcall was rejected.
+    pub status: Option<u16>,
+}
+
+pub struct HttpMock {
+    rules_path: PathBuf,
+    rules: Vec<CompiledRule>,
+    rules_modified: Option<SystemTime>,
+    log_path: PathBuf,
+    /// Answers that wait for the latency of their rule to pass.
+    pending: BTreeMap<(Principal, u64), (Instant, CanisterHttpResponse)>,
+}
+
+impl HttpMock {
+    pub fn new(rules_path: PathBuf, log_path: PathBuf) -> DfxResult<Self> {
+        let mut mock = Self {
+            rules_path,
+            rules: vec![],
+            rules_modified: None,
+            log_path,
+            pending: BTreeMap::new(),
+        };
+        mock.reload_rules()?;
+        Ok(mock)
+    }
+
+    /// Reloads the rules if the file changed, so that they can be edited while the network runs.
+    #[context("Failed to load HTTPS outcall mock rules from {}.", self.rules_path.display())]
+    fn reload_rules(&mut self) -> DfxResult {
+        let modified = fs::metadata(&self.rules_path)?.modified().ok();
+        if self.rules_modified.is_some() && modified == self.rules_modified {
+            return Ok(());
+        }
+        let file: HttpMockRulesFile = load_json_file(&self.rules_path)?;
+        let base_dir = self.rules_path.parent().unwrap_or(Path::new("."));
+        self.rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                compile_rule(rule, base_dir).with_context(|| format!("Invalid rule {index}."))
+            })
+            .collect::<DfxResult<_>>()?;
+        self.rules_modified = modified;
+        Ok(())
+    }
+
+    /// Answers the pending outcalls whose latency has passed, and schedules answers for new ones.
+    pub async fn answer_outcalls(&mut self, pocketic: &PocketIc) -> DfxResult {
+        self.reload_rules()?;
+        for request in pocketic.get_canister_http().await {
+            let key = (request.subnet_id, request.request_id);
+            if self.pending.contains_key(&key) {
+                continue;
+            }
+            let (rule, response, latency) = self.answer(&request);
+            self.log(&request, rule, &response)?;
+            self.pending
+                .insert(key, (Instant::now() + latency, response));
+        }
+
+        let now = Instant::now();
+        let due: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, (at, _))| *at <= now)
+            .map(|(key, _)| *key)
+            .collect();
+        for key @ (subnet_id, request_id) in due {
+            let (_, response) = self.pending.remove(&key).unwrap();
+            pocketic
+                .mock_canister_http_response(MockCanisterHttpResponse {
+                    subnet_id,
+                    request_id,
+                    response,
+                    additional_responses: vec![],
+                })
+                .await;
+        }
+        Ok(())
+    }
+
+    fn answer(
+        &self,
+        request: &CanisterHttpRequest,
+    ) -> (Option<usize>, CanisterHttpResponse, Duration) {
+        let method = method_name(&request.http_method);
+        let matched = self.rules.iter().enumerate().find(|(_, rule)| {
+            rule.url.is_match(&request.url)
+                && rule
+                    .method
+                    .as_ref()
+                    .map_or(true, |m| m.eq_ignore_ascii_case(method))
+        });
+        match matched {
+            Some((index, rule)) => (Some(index), rule.response.clone(), rule.latency),
+            None => (
+                None,
+                CanisterHttpResponse::CanisterHttpReject(CanisterHttpReject {
+                    reject_code: SYS_TRANSIENT,
+                    message: format!("No mock rule matches {method} {}", request.url),
+                }),
+                Duration::ZERO,
+            ),
+        }
+    }
+
+    fn log(
+        &self,
+        request: &CanisterHttpRequest,
+        rule: Option<usize>,
+        response: &CanisterHttpResponse,
+    ) -> DfxResult {
+        let entry = HttpOutcallLogEntry {
+            time: humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+            method: method_name(&request.http_method).to_string(),
+            url: request.url.clone(),
+            headers: request
+                .headers
+                .iter()
+                .map(|header| (header.name.clone(), header.value.clone()))
+                .collect(),
+            body: String::from_utf8_lossy(&request.body).to_string(),
+            rule,
+            status: match response {
+                CanisterHttpResponse::CanisterHttpReply(reply) => Some(reply.status),
+                _ => None,
+            },
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("Failed to open {}.", self.log_path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .with_context(|| format!("Failed to write to {}.", self.log_path.display()))?;
+        Ok(())
+    }
+}
+
+fn compile_rule(rule: HttpMockRule, base_dir: &Path) -> DfxResult<CompiledRule> {
+    let url = Regex::new(&format!("^(?:{})$", rule.url))?;
+    if let Some(method) = &rule.method {
+        if !["GET", "POST", "HEAD"].contains(&method.to_uppercase().as_str()) {
+            bail!("Unsupported method '{method}'. Expected GET, POST or HEAD.");
+        }
+    }
+    let response = if let Some(message) = rule.reject {
+        CanisterHttpResponse::CanisterHttpReject(CanisterHttpReject {
+            reject_code: SYS_TRANSIENT,
+            message,
+        })
+    } else {
+        let body = match (rule.body, rule.body_file) {
+            (Some(_), Some(_)) => bail!("Only one of `body` and `body_file` can be set."),
+            (Some(body), None) => body.into_bytes(),
+            (None, Some(body_file)) => fs::read(&base_dir.join(body_file))?,
+            (None, None) => vec![],
+        };
+        CanisterHttpResponse::CanisterHttpReply(CanisterHttpReply {
+            status: rule.status,
+            headers: rule
+                .headers
+                .into_iter()
+                .map(|(name, value)| CanisterHttpHeader { name, value })
+                .collect(),
+            body,
+        })
+    };
+    Ok(CompiledRule {
+        url,
+        method: rule.method,
+        response,
+        latency: Duration::from_millis(rule.latency_ms),
+    })
+}
+
+fn method_name(method: &CanisterHttpMethod) -> &'static str {
+    match method {
+        CanisterHttpMethod::GET => "GET",
+        CanisterHttpMethod::POST => "POST",
+        CanisterHttpMethod::HEAD => "HEAD",
+    }
+}
+
+/// Reads the outcalls that were answered from mock rules.
+#[context("Failed to read the HTTPS outcall log {}.", log_path.display())]
+pub fn read_outcall_log(log_path: &Path) -> DfxResult<Vec<HttpOutcallLogEntry>> {
+    if !log_path.exists() {
+        return Ok(vec![]);
+    }
+    fs::read_to_string(log_path)?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: CanisterHttpMethod, url: &str) -> CanisterHttpRequest {
+        CanisterHttpRequest {
+            subnet_id: Principal::anonymous(),
+            request_id: 0,
+            http_method: method,
+            url: url.to_string(),
+            headers: vec![],
+            body: vec![],
+            max_response_bytes: None,
+        }
+    }
+
+    #[test]
+    fn rules_match_whole_url_and_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules_path = dir.path().join("mocks.json");
+        std::fs::write(
+            &rules_path,
+            r#"{ "rules": [
+                { "url": "https://example\\.com/price", "method": "POST", "status": 201 },
+                { "url": "https://example\\.com/.*", "body": "hello", "latency_ms": 50 }
+            ] }"#,
+        )
+        .unwrap();
+        let mock = HttpMock::new(rules_path, dir.path().join("log")).unwrap();
+
+        let (rule, response, _) = mock.answer(&request(
+            CanisterHttpMethod::POST,
+            "https://example.com/price",
+        ));
+        assert_eq!(rule, Some(0));
+        assert!(
+            matches!(response, CanisterHttpResponse::CanisterHttpReply(reply) if reply.status == 201)
+        );
+
+        let (rule, response, latency) = mock.answer(&request(
+            CanisterHttpMethod::GET,
+            "https://example.com/price",
+        ));
+        assert_eq!(rule, Some(1));
+        assert_eq!(latency, Duration::from_millis(50));
+        assert!(
+            matches!(response, CanisterHttpResponse::CanisterHttpReply(reply) if reply.body == b"hello")
+        );
+
+        let (rule, response, _) = mock.answer(&request(
+            CanisterHttpMethod::GET,
+            "https://example.com.evil/",
+        ));
+        assert_eq!(rule, None);
+        assert!(matches!(
+            response,
+            CanisterHttpResponse::CanisterHttpReject(_)
+        ));
+    }
+}
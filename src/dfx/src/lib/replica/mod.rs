@@ -1 +1,3 @@
+pub mod http_mock;
 pub mod status;
+pub mod time;
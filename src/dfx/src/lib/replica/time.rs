@@ -0,0 +1,149 @@
+use crate::lib::error::DfxResult;
+use anyhow::{bail, Context};
+use dfx_core::config::model::network_descriptor::NetworkDescriptor;
+use dfx_core::config::model::replica_config::CachedReplicaConfig;
+use pocket_ic::common::rest::{AutoProgressConfig, RawTime};
+use pocket_ic::nonblocking::PocketIc;
+use reqwest::Client;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Controls the time of the PocketIC instance of a running local network.
+pub struct PocketIcTime {
+    client: Client,
+    server_url: Url,
+    instance_id: usize,
+    artificial_delay: u32,
+    /// With mocked HTTPS outcalls, dfx executes rounds itself instead of PocketIC.
+    http_mock: bool,
+}
+
+impl PocketIcTime {
+    pub fn connect(network_descriptor: &NetworkDescriptor) -> DfxResult<Self> {
+        let local_server_descriptor = network_descriptor.local_server_descriptor()?;
+        let Some(port) = local_server_descriptor.get_running_pocketic_port(None)? else {
+            bail!(
+                "No running PocketIC instance found for network '{}'. Start it with `dfx start`.",
+                network_descriptor.name
+            );
+        };
+        let Some(effective_config) = local_server_descriptor.effective_config()? else {
+            bail!(
+                "No running PocketIC instance found for network '{}'. Start it with `dfx start`.",
+                network_descriptor.name
+            );
+        };
+        let artificial_delay = match &effective_config.config {
+            CachedReplicaConfig::PocketIc { config } => config.artificial_delay,
+            CachedReplicaConfig::Replica { .. } => {
+                bail!("Time can only be controlled on PocketIC, not on the replica.")
+            }
+        };
+        let Some(instance_id) = effective_config.get_instance_id() else {
+            bail!(
+                "The PocketIC instance of network '{}' was started by an older dfx. Restart it with `dfx start`.",
+                network_descriptor.name
+            );
+        };
+        let mut socket_addr = local_server_descriptor.bind_address;
+        socket_addr.set_port(port);
+        let server_url = Url::parse(&format!("http://{}", socket_addr))?;
+        Ok(Self {
+            client: Client::new(),
+            server_url,
+            instance_id,
+            artificial_delay,
+            http_mock: local_server_descriptor.canister_http.mock.is_some(),
+        })
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.server_url
+            .join(&format!("instances/{}/{path}", self.instance_id))
+            .expect("Invalid PocketIC endpoint")
+    }
+
+    pub async fn get_time(&self) -> DfxResult<SystemTime> {
+        let time: RawTime = self
+            .client
+            .get(self.endpoint("read/get_time"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to read the time of PocketIC.")?;
+        Ok(UNIX_EPOCH + Duration::from_nanos(time.nanos_since_epoch))
+    }
+
+    pub async fn set_time(&self, time: SystemTime) -> DfxResult {
+        let current = self.get_time().await?;
+        if time < current {
+            bail!("The time of PocketIC cannot be set back.");
+        }
+        let nanos_since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .context("The time must be after the UNIX epoch.")?
+            .as_nanos()
+            .try_into()
+            .context("The time is too far in the future.")?;
+        self.client
+            .post(self.endpoint("update/set_time"))
+            .json(&RawTime { nanos_since_epoch })
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to set the time of PocketIC.")?;
+        Ok(())
+    }
+
+    pub async fn advance_time(&self, duration: Duration) -> DfxResult<SystemTime> {
+        let time = self.get_time().await? + duration;
+        self.set_time(time).await?;
+        Ok(time)
+    }
+
+    /// Stops executing rounds and advancing time automatically.
+    pub async fn pause(&self) -> DfxResult {
+        self.ensure_not_mocking()?;
+        self.client
+            .post(self.endpoint("stop_progress"))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to pause PocketIC.")?;
+        Ok(())
+    }
+
+    /// Resumes executing rounds and advancing time automatically, like after `dfx start`.
+    pub async fn resume(&self) -> DfxResult {
+        self.ensure_not_mocking()?;
+        self.client
+            .post(self.endpoint("auto_progress"))
+            .json(&AutoProgressConfig {
+                artificial_delay_ms: Some(self.artificial_delay as u64),
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to resume PocketIC.")?;
+        Ok(())
+    }
+
+    fn ensure_not_mocking(&self) -> DfxResult {
+        if self.http_mock {
+            bail!("Automatic progress cannot be paused or resumed while HTTPS outcalls are mocked with `canister_http.mock`.");
+        }
+        Ok(())
+    }
+
+    /// Executes `rounds` rounds on all subnets.
+    pub async fn tick(&self, rounds: u64) -> DfxResult {
+        let pocketic =
+            PocketIc::new_from_existing_instance(self.server_url.clone(), self.instance_id, None);
+        for _ in 0..rounds {
+            pocketic.tick().await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+use crate::lib::error::DfxResult;
+use crate::lib::sign::signed_message::{verify_envelope, SignedMessageV1};
+use anyhow::{bail, Context};
+use candid::types::value::VariantValue;
+use candid::types::{Function, TypeEnv};
+use candid::{IDLArgs, IDLValue, Principal};
+use dfx_core::json::{load_json_file, save_json_file};
+use ic_agent::agent::EnvelopeContent;
+use ic_agent::RequestId;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Blobs longer than this are shown by their length and hash when reviewing a proposal.
+const MAX_SHOWN_BLOB_LENGTH: usize = 64;
+
+/// A canister call that must be approved by several identities before it is sent.
+/// Each approval is the call signed by one approver.
+///
+/// Each approval is a complete ingress message that anyone holding the bundle can send on its own,
+/// so the threshold is only a guarantee if the canister enforces it, e.g. by acting only once
+/// enough of its controllers made the same call. dfx merely refuses to send the call without
+/// enough valid approvals. Approvals are only valid until their ingress expiry, at most 5 minutes
+/// after signing.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct ApprovalBundleV1 {
+    version: usize,
+    pub proposal: ProposedCall,
+    /// The number of approvals needed to send the call.
+    pub threshold: usize,
+    /// The principals that may approve the call. Anyone may approve it if empty.
+    #[serde(default)]
+    pub approvers: Vec<String>,
+    #[serde(default)]
+    pub submit: SubmitMode,
+    #[serde(default)]
+    pub approvals: Vec<SignedMessageV1>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct ProposedCall {
+    pub description: String,
+    pub network: String, // url of the network
+    pub is_ic: bool,
+    pub canister_id: String,
+    pub method_name: String,
+    pub arg: Vec<u8>,
+    /// The Candid interface of the canister, to show the decoded arguments to reviewers.
+    pub candid: Option<String>,
+}
+
+/// Which approvals are sent once the threshold is reached.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SubmitMode {
+    /// All approvals are sent, so that the canister can enforce the threshold.
+    #[default]
+    All,
+    /// Only the first approval is sent. The threshold is then only checked by dfx, and holds
+    /// nothing: any single approval can be sent directly.
+    UnsafeFirst,
+}
+
+impl ApprovalBundleV1 {
+    pub fn new(
+        proposal: ProposedCall,
+        threshold: usize,
+        approvers: Vec<String>,
+        submit: SubmitMode,
+    ) -> Self {
+        Self {
+            version: 1,
+            proposal,
+            threshold,
+            approvers,
+            submit,
+            approvals: vec![],
+        }
+    }
+
+    /// Whether the file is an approval bundle rather than a single signed message.
+    pub fn is_approval_bundle(path: &Path) -> DfxResult<bool> {
+        let value: serde_json::Value = load_json_file(path)?;
+        Ok(value.get("proposal").is_some())
+    }
+
+    pub fn load(path: &Path) -> DfxResult<Self> {
+        let bundle: Self = load_json_file(path)
+            .with_context(|| format!("Invalid approval bundle [{}].", path.display()))?;
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    pub fn save(&self, path: &Path) -> DfxResult {
+        save_json_file(path, self)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> DfxResult {
+        if self.version != 1 {
+            bail!("Invalid approval bundle: version must be 1");
+        }
+        if self.threshold == 0 {
+            bail!("Invalid approval bundle: the threshold must be at least 1");
+        }
+        if !self.approvers.is_empty() && self.threshold > self.approvers.len() {
+            bail!(
+                "Invalid approval bundle: the threshold of {} is more than the {} approvers",
+                self.threshold,
+                self.approvers.len()
+            );
+        }
+        let mut senders = vec![];
+        for approval in &self.approvals {
+            self.check_approval(approval, &senders)?;
+            senders.push(approval.sender.clone());
+        }
+        Ok(())
+    }
+
+    /// Checks that an approval signs the proposed call, by an approver that did not approve it yet,
+    /// and that it has not expired.
+    fn check_approval(&self, approval: &SignedMessageV1, senders: &[String]) -> DfxResult {
+        let proposal = &self.proposal;
+        if approval.call_type != "update"
+            || approval.network != proposal.network
+            || approval.canister_id != proposal.canister_id
+            || approval.method_name != proposal.method_name
+            || approval.arg != proposal.arg
+        {
+            bail!(
+                "The approval by {} does not sign the proposed call.",
+                approval.sender
+            );
+        }
+        if !self.approvers.is_empty() && !self.approvers.contains(&approval.sender) {
+            bail!("{} is not an approver of this proposal.", approval.sender);
+        }
+        if senders.contains(&approval.sender) {
+            bail!("{} already approved this proposal.", approval.sender);
+        }
+        approval
+            .validate()
+            .and_then(|()| self.verify_approval(approval))
+            .with_context(|| format!("The approval by {} is not valid.", approval.sender))
+    }
+
+    /// Verifies the signed call in an approval, rather than the fields of the approval that describe it.
+    fn verify_approval(&self, approval: &SignedMessageV1) -> DfxResult {
+        let envelope = approval.envelope()?;
+        verify_envelope(&envelope)?;
+        let EnvelopeContent::Call {
+            sender,
+            canister_id,
+            method_name,
+            arg,
+            ..
+        } = envelope.content.as_ref()
+        else {
+            bail!("The approval is not an update call.");
+        };
+        let request_id = approval
+            .request_id
+            .as_ref()
+            .context("The approval has no request ID.")?
+            .parse::<RequestId>()
+            .context("Failed to decode request ID.")?;
+        if request_id != envelope.content.to_request_id() {
+            bail!("The request ID does not match the signed call.");
+        }
+        if sender.to_text() != approval.sender {
+            bail!("The call is signed by {sender}.");
+        }
+        let proposal = &self.proposal;
+        let proposed_canister_id = Principal::from_text(&proposal.canister_id)
+            .with_context(|| format!("Invalid canister id {}.", proposal.canister_id))?;
+        if *canister_id != proposed_canister_id
+            || *method_name != proposal.method_name
+            || *arg != proposal.arg
+        {
+            bail!("The signed call is not the proposed call.");
+        }
+        Ok(())
+    }
+
+    pub fn add_approval(&mut self, approval: SignedMessageV1) -> DfxResult {
+        let senders: Vec<String> = self
+            .approvals
+            .iter()
+            .map(|approval| approval.sender.clone())
+            .collect();
+        self.check_approval(&approval, &senders)?;
+        self.approvals.push(approval);
+        Ok(())
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.approvals.len() >= self.threshold
+    }
+
+    /// The SHA-256 hash of the encoded arguments, to compare proposals out of band.
+    pub fn arg_hash(&self) -> String {
+        hex::encode(Sha256::digest(&self.proposal.arg))
+    }
+
+    /// The arguments of the proposed call, decoded with the given method type if any,
+    /// with long blobs like Wasm modules replaced by their length and hash.
+    pub fn decoded_args(&self, method_type: &Option<(TypeEnv, Function)>) -> DfxResult<String> {
+        let args = match method_type {
+            Some((env, func)) => {
+                IDLArgs::from_bytes_with_types(&self.proposal.arg, env, &func.args)
+            }
+            None => IDLArgs::from_bytes(&self.proposal.arg),
+        }
+        .context("Failed to decode the arguments of the proposed call.")?;
+        let args = IDLArgs::new(&args.args.into_iter().map(summarize).collect::<Vec<_>>());
+        Ok(args.to_string())
+    }
+}
+
+/// Replaces the blobs longer than 64 bytes in a value by their length and hash.
+pub(crate) fn summarize(value: IDLValue) -> IDLValue {
+    let describe = |bytes: &[u8]| {
+        IDLValue::Text(format!(
+            "<{} bytes with sha256 {}>",
+            bytes.len(),
+            hex::encode(Sha256::digest(bytes))
+        ))
+    };
+    match value {
+        IDLValue::Blob(bytes) if bytes.len() > MAX_SHOWN_BLOB_LENGTH => describe(&bytes),
+        IDLValue::Vec(values)
+            if values.len() > MAX_SHOWN_BLOB_LENGTH
+                && values.iter().all(|v| matches!(v, IDLValue::Nat8(_))) =>
+        {
+            let bytes: Vec<u8> = values
+                .iter()
+                .map(|v| match v {
+                    IDLValue::Nat8(byte) => *byte,
+                    _ => unreachable!(),
+                })
+                .collect();
+            describe(&bytes)
+        }
+        IDLValue::Vec(values) => IDLValue::Vec(values.into_iter().map(summarize).collect()),
+        IDLValue::Opt(value) => IDLValue::Opt(Box::new(summarize(*value))),
+        IDLValue::Record(fields) => IDLValue::Record(
+            fields
+                .into_iter()
+                .map(|mut field| {
+                    field.val = summarize(field.val);
+                    field
+                })
+                .collect(),
+        ),
+        IDLValue::Variant(VariantValue(field, index)) => {
+            let mut field = *field;
+            field.val = summarize(field.val);
+            IDLValue::Variant(VariantValue(Box::new(field), index))
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::{Encode, Principal};
+    use time::OffsetDateTime;
+
+    fn proposal() -> ProposedCall {
+        ProposedCall {
+            description: "upgrade".to_string(),
+            network: "http://localhost:4943".to_string(),
+            is_ic: false,
+            canister_id: Principal::management_canister().to_text(),
+            method_name: "install_code".to_string(),
+            arg: Encode!(&vec![0u8; 100], &"short".as_bytes().to_vec()).unwrap(),
+            candid: None,
+        }
+    }
+
+    fn approval(sender: Principal, arg: Vec<u8>) -> SignedMessageV1 {
+        SignedMessageV1::new(
+            OffsetDateTime::now_utc(),
+            OffsetDateTime::now_utc(),
+            "http://localhost:4943".to_string(),
+            false,
+            sender,
+            Principal::management_canister(),
+            "install_code".to_string(),
+            arg,
+        )
+        .with_call_type("update".to_string())
+    }
+
+    #[test]
+    fn approvals_must_match_the_proposal() {
+        let alice = Principal::from_slice(&[1]);
+        let bob = Principal::from_slice(&[2]);
+        let mut bundle =
+            ApprovalBundleV1::new(proposal(), 2, vec![alice.to_text()], SubmitMode::All);
+        assert!(bundle.validate().is_err());
+        bundle.approvers.push(bob.to_text());
+        assert!(bundle.validate().is_ok());
+
+        let other_arg = Encode!(&()).unwrap();
+        assert!(bundle.add_approval(approval(alice, other_arg)).is_err());
+        let carol = Principal::from_slice(&[3]);
+        assert!(bundle
+            .add_approval(approval(carol, proposal().arg))
+            .is_err());
+        assert!(!bundle.is_approved());
+    }
+
+    fn signed_approval(seed: u8, arg: Vec<u8>) -> SignedMessageV1 {
+        let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+        let identity = ic_agent::identity::BasicIdentity::from_key_pair(key_pair);
+        let sender = ic_agent::Identity::sender(&identity).unwrap();
+        let agent = ic_agent::Agent::builder()
+            .with_url("http://localhost:4943")
+            .with_identity(identity)
+            .build()
+            .unwrap();
+        let creation = OffsetDateTime::now_utc();
+        let expiration = creation + time::Duration::minutes(5);
+        let signed = agent
+            .update(&Principal::management_canister(), "install_code")
+            .with_arg(arg.clone())
+            .expire_at(std::time::SystemTime::from(expiration))
+            .sign()
+            .unwrap();
+        SignedMessageV1::new(
+            creation,
+            expiration,
+            "http://localhost:4943".to_string(),
+            false,
+            sender,
+            Principal::management_canister(),
+            "install_code".to_string(),
+            arg,
+        )
+        .with_call_type("update".to_string())
+        .with_request_id(signed.request_id)
+        .with_content(hex::encode(signed.signed_update))
+    }
+
+    #[test]
+    fn approvals_are_verified() {
+        let alice = signed_approval(1, proposal().arg);
+        let bob = signed_approval(2, proposal().arg);
+        let approvers = vec![alice.sender.clone(), bob.sender.clone()];
+        let mut bundle = ApprovalBundleV1::new(proposal(), 2, approvers, SubmitMode::All);
+        bundle.add_approval(alice.clone()).unwrap();
+        assert!(bundle.add_approval(alice).is_err());
+
+        let mut forged = bob.clone();
+        forged.request_id = bundle.approvals[0].request_id.clone();
+        assert!(bundle.add_approval(forged.clone()).is_err());
+        bundle.approvals.push(forged);
+        assert!(bundle.validate().is_err());
+        bundle.approvals.pop();
+
+        bundle.add_approval(bob).unwrap();
+        assert!(bundle.is_approved());
+        assert!(bundle.validate().is_ok());
+    }
+
+    #[test]
+    fn long_blobs_are_summarized() {
+        let bundle = ApprovalBundleV1::new(proposal(), 1, vec![], SubmitMode::All);
+        let decoded = bundle.decoded_args(&None).unwrap();
+        assert!(decoded.contains("<100 bytes with sha256 "));
+        assert!(decoded.contains("short") || decoded.contains("\\73\\68\\6f\\72\\74"));
+    }
+}
@@ -1 +1,3 @@
+pub mod approval_bundle;
+pub mod signed_bundle;
 pub mod signed_message;
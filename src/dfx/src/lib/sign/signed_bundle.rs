@@ -0,0 +1,88 @@
+use crate::lib::error::DfxResult;
+use crate::lib::sign::signed_message::SignedMessageV1;
+use anyhow::{bail, Context};
+use dfx_core::json::{load_json_file, save_json_file};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Signed messages that `dfx send` submits in order, written by the `--sign-only` option of
+/// commands that move tokens.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct SignedBundleV1 {
+    version: usize,
+    /// What the messages do, shown before they are sent.
+    pub description: String,
+    pub messages: Vec<SignedMessageV1>,
+    /// A call that can only be made once the messages were executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up: Option<FollowUp>,
+}
+
+/// Calls that need the result of the signed messages, so they cannot be signed in advance.
+/// They do not need to be signed, because they do not depend on the caller.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum FollowUp {
+    /// Notifies the cycles minting canister of the ICP transfer of the last message,
+    /// so that it tops up the canister with the minted cycles.
+    NotifyTopUp { canister_id: String },
+}
+
+impl SignedBundleV1 {
+    pub fn new(description: String, messages: Vec<SignedMessageV1>) -> Self {
+        Self {
+            version: 1,
+            description,
+            messages,
+            follow_up: None,
+        }
+    }
+
+    pub fn with_follow_up(mut self, follow_up: FollowUp) -> Self {
+        self.follow_up = Some(follow_up);
+        self
+    }
+
+    /// Loads a bundle, or a single message signed by `dfx canister sign`.
+    pub fn load(path: &Path) -> DfxResult<Self> {
+        let value: serde_json::Value = load_json_file(path)?;
+        let bundle = if value.get("messages").is_some() {
+            serde_json::from_value(value)
+                .with_context(|| format!("Invalid signed bundle [{}].", path.display()))?
+        } else {
+            let message: SignedMessageV1 = serde_json::from_value(value)
+                .with_context(|| format!("Invalid signed message [{}].", path.display()))?;
+            let description = format!(
+                "{} call to {}.{}",
+                message.call_type, message.canister_id, message.method_name
+            );
+            Self::new(description, vec![message])
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    pub fn save(&self, path: &Path) -> DfxResult {
+        if path.exists() {
+            bail!(
+                "[{}] already exists, please specify a different output file name.",
+                path.display()
+            );
+        }
+        save_json_file(path, self)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> DfxResult {
+        if self.version != 1 {
+            bail!("Invalid bundle: version must be 1");
+        }
+        if self.messages.is_empty() {
+            bail!("Invalid bundle: no messages");
+        }
+        for message in &self.messages {
+            message.validate()?;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
+use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use anyhow::{anyhow, bail, Context};
 use candid::Principal;
+use dfx_core::identity::signature::{key_type, verify_request_signature, verify_signature};
 use fn_error_context::context;
+use ic_agent::agent::{Envelope, EnvelopeContent};
 use ic_agent::RequestId;
 use serde::{Deserialize, Serialize};
 use serde_cbor::Value;
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
+use std::time::SystemTime;
 use time::{Duration, OffsetDateTime};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -55,6 +59,59 @@ impl SignedMessageV1 {
         }
     }
 
+    /// Signs an update call, and the request_status call that reads its result,
+    /// with the selected identity and without contacting the network.
+    pub fn sign_update(
+        env: &dyn Environment,
+        canister_id: Principal,
+        effective_canister_id: Principal,
+        method_name: &str,
+        arg: Vec<u8>,
+        timeout: std::time::Duration,
+    ) -> DfxResult<Self> {
+        let agent = env.get_agent();
+        let network_descriptor = env.get_network_descriptor();
+        let network = network_descriptor
+            .providers
+            .first()
+            .expect("Cannot get network provider (url).")
+            .to_string();
+        let sender = env
+            .get_selected_identity_principal()
+            .expect("Selected identity not instantiated.");
+
+        let expiration_system_time = SystemTime::now()
+            .checked_add(timeout)
+            .ok_or_else(|| anyhow!("Time wrapped around."))?;
+        let creation = OffsetDateTime::now_utc();
+        let expiration = creation
+            .checked_add(timeout.try_into()?)
+            .ok_or_else(|| anyhow!("Expiration datetime overflow."))?;
+
+        let signed_update = agent
+            .update(&canister_id, method_name)
+            .with_effective_canister_id(effective_canister_id)
+            .with_arg(arg.clone())
+            .expire_at(expiration_system_time)
+            .sign()?;
+        let request_id = signed_update.request_id;
+        let signed_request_status = agent.sign_request_status(effective_canister_id, request_id)?;
+        Ok(Self::new(
+            creation,
+            expiration,
+            network,
+            network_descriptor.is_ic,
+            sender,
+            canister_id,
+            method_name.to_string(),
+            arg,
+        )
+        .with_call_type("update".to_string())
+        .with_request_id(request_id)
+        .with_content(hex::encode(&signed_update.signed_update))
+        .with_signed_request_status(hex::encode(signed_request_status.signed_request_status)))
+    }
+
     pub fn with_call_type(mut self, request_type: String) -> Self {
         self.call_type = request_type;
         self
@@ -179,6 +236,69 @@ impl SignedMessageV1 {
         }
         Ok(())
     }
+
+    /// Decodes the signed envelope in the content of the message.
+    pub fn envelope(&self) -> DfxResult<Envelope<'static>> {
+        let content = hex::decode(&self.content).context("Failed to decode content.")?;
+        serde_cbor::from_slice(&content)
+            .map_err(|_| anyhow!("Invalid cbor data in the content of the message."))
+    }
+}
+
+/// Verifies that the envelope is signed by its sender, directly or through a chain of delegations.
+/// Returns the type of the key of the sender.
+pub(crate) fn verify_envelope(envelope: &Envelope) -> DfxResult<&'static str> {
+    let (sender, canister_id) = match envelope.content.as_ref() {
+        EnvelopeContent::Call {
+            sender,
+            canister_id,
+            ..
+        }
+        | EnvelopeContent::Query {
+            sender,
+            canister_id,
+            ..
+        } => (*sender, Some(*canister_id)),
+        EnvelopeContent::ReadState { sender, .. } => (*sender, None),
+    };
+    let (Some(public_key), Some(signature)) = (&envelope.sender_pubkey, &envelope.sender_sig)
+    else {
+        if sender == Principal::anonymous() {
+            return Ok("anonymous");
+        }
+        bail!("The message is not signed.");
+    };
+    if Principal::self_authenticating(public_key) != sender {
+        bail!("The public key of the signature does not belong to the sender {sender}.");
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp_nanos();
+    let mut signing_key = public_key;
+    for (index, signed) in envelope.sender_delegation.iter().flatten().enumerate() {
+        let delegation = &signed.delegation;
+        verify_signature(signing_key, &delegation.signable(), &signed.signature).with_context(
+            || {
+                format!(
+                    "Delegation {} is not signed by the key it extends.",
+                    index + 1
+                )
+            },
+        )?;
+        if i128::from(delegation.expiration) < now {
+            bail!("Delegation {} has expired.", index + 1);
+        }
+        if let (Some(targets), Some(canister_id)) = (&delegation.targets, canister_id) {
+            if !targets.contains(&canister_id) {
+                bail!(
+                    "Delegation {} does not allow calls to {canister_id}.",
+                    index + 1
+                );
+            }
+        }
+        signing_key = &delegation.pubkey;
+    }
+    verify_request_signature(signing_key, &envelope.content.to_request_id(), signature)?;
+    Ok(key_type(public_key))
 }
 
 mod date_time_utc {
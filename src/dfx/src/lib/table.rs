@@ -0,0 +1,47 @@
+//! Plain text tables, with each column as wide as its widest cell.
+
+/// Prints the header and the rows, with two spaces between columns.
+pub fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    for line in format_table(header, rows) {
+        println!("{}", line);
+    }
+}
+
+fn format_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> Vec<String> {
+    let header = header.map(String::from);
+    let mut widths = header.clone().map(|column| column.len());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    std::iter::once(&header)
+        .chain(rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_as_wide_as_their_widest_cell() {
+        let rows = [
+            ["backend".to_string(), "1".to_string(), String::new()],
+            ["a".to_string(), "1,000".to_string(), "x".to_string()],
+        ];
+        assert_eq!(
+            format_table(["NAME", "AMOUNT", "NOTE"], &rows),
+            ["NAME     AMOUNT  NOTE", "backend  1", "a        1,000   x",]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Tokens of ICRC-1 ledgers, with amounts scaled by the decimals that the ledger reports.
+use crate::lib::error::DfxResult;
+use crate::lib::operations::ledger::icrc1_metadata;
+use anyhow::{anyhow, bail, Context};
+use candid::{Nat, Principal};
+use ic_agent::Agent;
+use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
+
+const DECIMALS_KEY: &str = "icrc1:decimals";
+const SYMBOL_KEY: &str = "icrc1:symbol";
+const FEE_KEY: &str = "icrc1:fee";
+
+/// A token of an ICRC-1 ledger, as described by the `icrc1_metadata` of the ledger.
+pub struct IcrcToken {
+    pub canister_id: Principal,
+    pub symbol: String,
+    pub decimals: u8,
+    /// The fee of a transfer, in the smallest unit of the token.
+    pub fee: Option<Nat>,
+    pub metadata: Vec<(String, MetadataValue)>,
+}
+
+impl IcrcToken {
+    pub async fn fetch(agent: &Agent, canister_id: Principal) -> DfxResult<Self> {
+        let metadata = icrc1_metadata(agent, &canister_id)
+            .await
+            .with_context(|| format!("Failed to read the metadata of ledger {canister_id}."))?;
+        Self::from_metadata(canister_id, metadata)
+    }
+
+    fn from_metadata(
+        canister_id: Principal,
+        metadata: Vec<(String, MetadataValue)>,
+    ) -> DfxResult<Self> {
+        let value = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value)
+        };
+        let decimals = match value(DECIMALS_KEY) {
+            Some(MetadataValue::Nat(decimals)) => u8::try_from(&decimals.0)
+                .map_err(|_| anyhow!("Ledger {canister_id} reports {decimals} decimals."))?,
+            _ => bail!("Ledger {canister_id} does not report its decimals in '{DECIMALS_KEY}'."),
+        };
+        let symbol = match value(SYMBOL_KEY) {
+            Some(MetadataValue::Text(symbol)) => symbol.clone(),
+            _ => bail!("Ledger {canister_id} does not report its symbol in '{SYMBOL_KEY}'."),
+        };
+        let fee = match value(FEE_KEY) {
+            Some(MetadataValue::Nat(fee)) => Some(fee.clone()),
+            _ => None,
+        };
+        Ok(Self {
+            canister_id,
+            symbol,
+            decimals,
+            fee,
+            metadata,
+        })
+    }
+
+    /// Parses a decimal amount of the token, like `1.5`, into its smallest unit.
+    pub fn parse_amount(&self, amount: &str) -> DfxResult<Nat> {
+        let decimals = usize::from(self.decimals);
+        let invalid = || {
+            anyhow!(
+                "Invalid amount '{amount}'. Expected {} with up to {decimals} decimals.",
+                self.symbol
+            )
+        };
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || fraction.len() > decimals
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        format!("{whole}{fraction:0<decimals$}")
+            .parse::<Nat>()
+            .map_err(|_| invalid())
+    }
+
+    /// Formats an amount in the smallest unit of the token, like `1.5 ckBTC`.
+    pub fn format_amount(&self, amount: &Nat) -> String {
+        let decimals = usize::from(self.decimals);
+        let digits = format!("{:0>width$}", amount.0.to_string(), width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            format!("{whole} {}", self.symbol)
+        } else {
+            format!("{whole}.{fraction} {}", self.symbol)
+        }
+    }
+}
+
+/// Formats a metadata value for display.
+pub fn format_metadata_value(value: &MetadataValue) -> String {
+    match value {
+        MetadataValue::Nat(nat) => nat.0.to_string(),
+        MetadataValue::Int(int) => int.0.to_string(),
+        MetadataValue::Text(text) => text.clone(),
+        MetadataValue::Blob(blob) => format!("0x{}", hex::encode(blob)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(decimals: u8) -> IcrcToken {
+        IcrcToken::from_metadata(
+            Principal::anonymous(),
+            vec![
+                (
+                    DECIMALS_KEY.to_string(),
+                    MetadataValue::Nat(Nat::from(decimals)),
+                ),
+                (
+                    SYMBOL_KEY.to_string(),
+                    MetadataValue::Text("TKN".to_string()),
+                ),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn token_amounts() {
+        let token8 = token(8);
+        assert_eq!(token8.parse_amount("1").unwrap(), Nat::from(100_000_000u64));
+        assert_eq!(
+            token8.parse_amount("0.5").unwrap(),
+            Nat::from(50_000_000u64)
+        );
+        assert_eq!(token8.parse_amount(".00000001").unwrap(), Nat::from(1u8));
+        assert_eq!(token8.parse_amount("0").unwrap(), Nat::from(0u8));
+        assert!(token8.parse_amount("0.123456789").is_err());
+        assert!(token8.parse_amount("-1").is_err());
+        assert!(token8.parse_amount(".").is_err());
+        assert_eq!(token8.format_amount(&Nat::from(150_000_000u64)), "1.5 TKN");
+        assert_eq!(token8.format_amount(&Nat::from(1u8)), "0.00000001 TKN");
+        assert_eq!(token8.format_amount(&Nat::from(0u8)), "0 TKN");
+
+        let token18 = token(18);
+        let amount = token18
+            .parse_amount("1000000000.000000000000000001")
+            .unwrap();
+        assert_eq!(amount.0.to_string(), "1000000000000000000000000001");
+        assert_eq!(
+            token18.format_amount(&amount),
+            "1000000000.000000000000000001 TKN"
+        );
+
+        let token0 = token(0);
+        assert_eq!(token0.parse_amount("42").unwrap(), Nat::from(42u8));
+        assert!(token0.parse_amount("4.2").is_err());
+        assert_eq!(token0.format_amount(&Nat::from(42u8)), "42 TKN");
+    }
+
+    #[test]
+    fn metadata_requires_decimals_and_symbol() {
+        assert!(IcrcToken::from_metadata(Principal::anonymous(), vec![]).is_err());
+        assert_eq!(token(6).fee, None);
+    }
+}
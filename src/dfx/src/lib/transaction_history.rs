@@ -0,0 +1,410 @@
+//! The transactions of ICRC ledgers, read from their ICRC-3 block log or from an index canister.
+use crate::lib::error::DfxResult;
+use crate::lib::history_types::icrc3::{GetBlocksArgs, Icrc3Value};
+use crate::lib::history_types::index::{
+    GetAccountTransactionsArgs, GetTransactionsResult, TransactionWithId,
+};
+use crate::lib::operations::ledger::{icrc3_get_blocks, index_get_account_transactions};
+use crate::lib::table;
+use crate::lib::token::IcrcToken;
+use anyhow::{anyhow, bail, Context};
+use candid::{Nat, Principal};
+use ic_agent::Agent;
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// How many blocks or transactions to request at once. Ledgers and index canisters may return fewer.
+const PAGE_SIZE: u64 = 500;
+
+/// A transaction of a ledger, in the form that `dfx ledger history` and `dfx cycles history` print.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Transaction {
+    /// The index of the block of the transaction.
+    pub index: u64,
+    /// Nanoseconds since the epoch.
+    pub timestamp: Option<u64>,
+    /// `transfer`, `mint`, `burn` or `approve`, or the block type for other blocks.
+    pub kind: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub spender: Option<String>,
+    /// In the smallest unit of the token.
+    #[serde(serialize_with = "serialize_nat")]
+    pub amount: Nat,
+    #[serde(serialize_with = "serialize_opt_nat")]
+    pub fee: Option<Nat>,
+    /// Hex-encoded.
+    pub memo: Option<String>,
+}
+
+impl Transaction {
+    /// Whether the account sent, received or spent the tokens of the transaction.
+    pub fn involves(&self, account: &Account) -> bool {
+        let account = account.to_string();
+        [&self.from, &self.to, &self.spender]
+            .into_iter()
+            .any(|party| party.as_deref() == Some(account.as_str()))
+    }
+
+    fn from_block(index: u64, block: &Icrc3Value) -> DfxResult<Self> {
+        let invalid = || anyhow!("Block {index} is not a valid ICRC-3 block.");
+        let tx = block.get("tx").ok_or_else(invalid)?;
+        let kind = match block
+            .get("btype")
+            .or_else(|| tx.get("op"))
+            .and_then(Icrc3Value::as_text)
+        {
+            Some("1xfer" | "2xfer" | "xfer") => "transfer",
+            Some("1mint" | "mint") => "mint",
+            Some("1burn" | "burn") => "burn",
+            Some("2approve" | "approve") => "approve",
+            Some(other) => other,
+            None => return Err(invalid()),
+        };
+        Ok(Self {
+            index,
+            timestamp: block
+                .get("ts")
+                .and_then(Icrc3Value::as_nat)
+                .map(nat_to_u64)
+                .transpose()?,
+            kind: kind.to_string(),
+            from: block_account(tx.get("from")).with_context(invalid)?,
+            to: block_account(tx.get("to")).with_context(invalid)?,
+            spender: block_account(tx.get("spender")).with_context(invalid)?,
+            amount: tx
+                .get("amt")
+                .and_then(Icrc3Value::as_nat)
+                .cloned()
+                .unwrap_or_else(|| Nat::from(0u64)),
+            fee: tx
+                .get("fee")
+                .or_else(|| block.get("fee"))
+                .and_then(Icrc3Value::as_nat)
+                .cloned(),
+            memo: tx
+                .get("memo")
+                .and_then(Icrc3Value::as_blob)
+                .map(hex::encode),
+        })
+    }
+
+    fn from_index(transaction: TransactionWithId) -> DfxResult<Self> {
+        let index = nat_to_u64(&transaction.id)?;
+        let tx = transaction.transaction;
+        let (from, to, spender, amount, fee, memo) = if let Some(mint) = tx.mint {
+            (None, Some(mint.to), None, mint.amount, None, mint.memo)
+        } else if let Some(burn) = tx.burn {
+            (
+                Some(burn.from),
+                None,
+                burn.spender,
+                burn.amount,
+                None,
+                burn.memo,
+            )
+        } else if let Some(transfer) = tx.transfer {
+            (
+                Some(transfer.from),
+                Some(transfer.to),
+                transfer.spender,
+                transfer.amount,
+                transfer.fee,
+                transfer.memo,
+            )
+        } else if let Some(approve) = tx.approve {
+            (
+                Some(approve.from),
+                None,
+                Some(approve.spender),
+                approve.amount,
+                approve.fee,
+                approve.memo,
+            )
+        } else {
+            bail!(
+                "Transaction {index} has no details for its kind '{}'.",
+                tx.kind
+            );
+        };
+        Ok(Self {
+            index,
+            timestamp: Some(tx.timestamp),
+            kind: tx.kind,
+            from: from.map(|account| account.to_string()),
+            to: to.map(|account| account.to_string()),
+            spender: spender.map(|account| account.to_string()),
+            amount,
+            fee,
+            memo: memo.map(hex::encode),
+        })
+    }
+}
+
+/// Reads the transactions in the blocks `start..start + limit` of the block log of a ledger,
+/// including the blocks that the ledger moved to its archives.
+/// Without `start`, reads the last `limit` blocks.
+pub async fn ledger_transactions(
+    agent: &Agent,
+    ledger_id: Principal,
+    start: Option<u64>,
+    limit: u64,
+) -> DfxResult<Vec<Transaction>> {
+    let context = || format!("Failed to read the blocks of ledger {ledger_id}.");
+    let tip = icrc3_get_blocks(
+        agent,
+        &ledger_id,
+        None,
+        vec![GetBlocksArgs {
+            start: Nat::from(0u64),
+            length: Nat::from(0u64),
+        }],
+    )
+    .await
+    .with_context(context)?;
+    let log_length = nat_to_u64(&tip.log_length)?;
+    let start = start.unwrap_or_else(|| log_length.saturating_sub(limit));
+    let end = start.saturating_add(limit).min(log_length);
+
+    let mut blocks = BTreeMap::new();
+    let mut next = start;
+    while next < end {
+        let args = vec![GetBlocksArgs {
+            start: Nat::from(next),
+            length: Nat::from((end - next).min(PAGE_SIZE)),
+        }];
+        let result = icrc3_get_blocks(agent, &ledger_id, None, args)
+            .await
+            .with_context(context)?;
+        let mut found = result.blocks;
+        for archived in result.archived_blocks {
+            let archive = archived.callback.0;
+            let archived_result = icrc3_get_blocks(
+                agent,
+                &archive.principal,
+                Some(&archive.method),
+                archived.args,
+            )
+            .await
+            .with_context(|| {
+                format!("Failed to read blocks from archive {}.", archive.principal)
+            })?;
+            found.extend(archived_result.blocks);
+        }
+        for block in found {
+            let id = nat_to_u64(&block.id)?;
+            if (start..end).contains(&id) {
+                blocks.insert(id, block.block);
+            }
+        }
+        let previous = next;
+        while blocks.contains_key(&next) {
+            next += 1;
+        }
+        if next == previous {
+            bail!("Ledger {ledger_id} did not return block {next}.");
+        }
+    }
+
+    blocks
+        .iter()
+        .map(|(id, block)| Transaction::from_block(*id, block))
+        .collect()
+}
+
+/// Reads the last `limit` transactions of an account from an index canister of the ledger.
+pub async fn index_transactions(
+    agent: &Agent,
+    index_id: Principal,
+    account: Account,
+    limit: u64,
+) -> DfxResult<Vec<Transaction>> {
+    let mut transactions = vec![];
+    let mut oldest: Option<Nat> = None;
+    while (transactions.len() as u64) < limit {
+        // Ask for one more, in case the index canister returns the `start` transaction again.
+        let max_results = (limit - transactions.len() as u64 + 1).min(PAGE_SIZE);
+        let args = GetAccountTransactionsArgs {
+            account,
+            start: oldest.clone(),
+            max_results: Nat::from(max_results),
+        };
+        let page = match index_get_account_transactions(agent, &index_id, args)
+            .await
+            .with_context(|| format!("Failed to read transactions from index {index_id}."))?
+        {
+            GetTransactionsResult::Ok(page) => page,
+            GetTransactionsResult::Err(err) => bail!(
+                "Index {index_id} failed to return transactions: {}",
+                err.message
+            ),
+        };
+        let new: Vec<_> = page
+            .transactions
+            .into_iter()
+            .filter(|tx| oldest.as_ref().map_or(true, |oldest| tx.id < *oldest))
+            .collect();
+        let Some(last) = new.last() else {
+            break;
+        };
+        oldest = Some(last.id.clone());
+        for tx in new {
+            transactions.push(Transaction::from_index(tx)?);
+        }
+    }
+    transactions.truncate(limit as usize);
+    transactions.reverse();
+    Ok(transactions)
+}
+
+/// Prints the transactions as a table, with amounts in the units of the token.
+pub fn print_table(token: &IcrcToken, transactions: &[Transaction]) {
+    let none = || "-".to_string();
+    let rows: Vec<[String; 8]> = transactions
+        .iter()
+        .map(|tx| {
+            [
+                tx.index.to_string(),
+                tx.timestamp.map_or_else(none, format_timestamp),
+                tx.kind.clone(),
+                tx.from.clone().unwrap_or_else(none),
+                tx.to.clone().unwrap_or_else(none),
+                token.format_amount(&tx.amount),
+                tx.fee
+                    .as_ref()
+                    .map_or_else(none, |fee| token.format_amount(fee)),
+                tx.memo.clone().unwrap_or_else(none),
+            ]
+        })
+        .collect();
+    table::print_table(
+        [
+            "INDEX", "TIME", "TYPE", "FROM", "TO", "AMOUNT", "FEE", "MEMO",
+        ],
+        &rows,
+    );
+}
+
+fn format_timestamp(nanos: u64) -> String {
+    humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_nanos(nanos)).to_string()
+}
+
+/// Decodes an ICRC-3 account, an array of the owner and, optionally, the subaccount.
+fn block_account(value: Option<&Icrc3Value>) -> DfxResult<Option<String>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let Icrc3Value::Array(parts) = value else {
+        bail!("Invalid account {value:?}.");
+    };
+    let owner = parts
+        .first()
+        .and_then(Icrc3Value::as_blob)
+        .ok_or_else(|| anyhow!("Account {value:?} has no owner."))?;
+    let subaccount = parts
+        .get(1)
+        .and_then(Icrc3Value::as_blob)
+        .map(<[u8; 32]>::try_from)
+        .transpose()
+        .map_err(|_| anyhow!("Account {value:?} has an invalid subaccount."))?;
+    let account = Account {
+        owner: Principal::try_from_slice(owner)?,
+        subaccount,
+    };
+    Ok(Some(account.to_string()))
+}
+
+fn nat_to_u64(nat: &Nat) -> DfxResult<u64> {
+    u64::try_from(&nat.0).map_err(|_| anyhow!("{nat} does not fit into 64 bits."))
+}
+
+fn serialize_nat<S: Serializer>(nat: &Nat, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&nat.0.to_string())
+}
+
+fn serialize_opt_nat<S: Serializer>(nat: &Option<Nat>, serializer: S) -> Result<S::Ok, S::Error> {
+    match nat {
+        Some(nat) => serialize_nat(nat, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_bytes::ByteBuf;
+
+    fn map(entries: Vec<(&str, Icrc3Value)>) -> Icrc3Value {
+        Icrc3Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn account(owner: Principal, subaccount: Option<[u8; 32]>) -> Icrc3Value {
+        let mut parts = vec![Icrc3Value::Blob(ByteBuf::from(owner.as_slice().to_vec()))];
+        if let Some(subaccount) = subaccount {
+            parts.push(Icrc3Value::Blob(ByteBuf::from(subaccount.to_vec())));
+        }
+        Icrc3Value::Array(parts)
+    }
+
+    #[test]
+    fn transactions_from_icrc3_blocks() {
+        let alice = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let bob = Principal::anonymous();
+        let block = map(vec![
+            ("btype", Icrc3Value::Text("1xfer".to_string())),
+            (
+                "ts",
+                Icrc3Value::Nat(Nat::from(1_700_000_000_000_000_000u64)),
+            ),
+            ("fee", Icrc3Value::Nat(Nat::from(10u64))),
+            (
+                "tx",
+                map(vec![
+                    ("from", account(alice, None)),
+                    ("to", account(bob, Some([1; 32]))),
+                    ("amt", Icrc3Value::Nat(Nat::from(500u64))),
+                    ("memo", Icrc3Value::Blob(ByteBuf::from(vec![0xab]))),
+                ]),
+            ),
+        ]);
+        let tx = Transaction::from_block(7, &block).unwrap();
+        assert_eq!(tx.kind, "transfer");
+        assert_eq!(tx.timestamp, Some(1_700_000_000_000_000_000));
+        assert_eq!(tx.amount, Nat::from(500u64));
+        assert_eq!(tx.fee, Some(Nat::from(10u64)));
+        assert_eq!(tx.memo.as_deref(), Some("ab"));
+        assert!(tx.involves(&Account::from(alice)));
+        assert!(!tx.involves(&Account::from(bob)));
+        assert!(tx.involves(&Account {
+            owner: bob,
+            subaccount: Some([1; 32])
+        }));
+
+        // Older ledgers put the operation into the transaction.
+        let block = map(vec![(
+            "tx",
+            map(vec![
+                ("op", Icrc3Value::Text("mint".to_string())),
+                ("to", account(alice, None)),
+                ("amt", Icrc3Value::Nat(Nat::from(1u64))),
+            ]),
+        )]);
+        let tx = Transaction::from_block(0, &block).unwrap();
+        assert_eq!(tx.kind, "mint");
+        assert_eq!(tx.from, None);
+        // A default subaccount is the same account as no subaccount.
+        assert!(tx.involves(&Account {
+            owner: alice,
+            subaccount: Some([0; 32])
+        }));
+
+        assert!(Transaction::from_block(0, &map(vec![])).is_err());
+    }
+}
@@ -0,0 +1,425 @@
+//! Watch mode for `dfx build --watch` and `dfx deploy --watch`.
+use crate::lib::error::DfxResult;
+use anyhow::{bail, Context};
+use dfx_core::config::model::dfinity::{CanisterTypeProperties, Config, ConfigCanistersCanister};
+use regex::Regex;
+use slog::{error, info, warn, Logger};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use walkdir::{DirEntry, WalkDir};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Directories that never contain canister sources.
+const IGNORED_DIRECTORIES: [&str; 2] = ["node_modules", "target"];
+
+type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// The files and directories whose changes affect a canister.
+#[derive(Debug, Default)]
+struct WatchedPaths {
+    roots: Vec<PathBuf>,
+    excluded: Vec<PathBuf>,
+}
+
+impl WatchedPaths {
+    fn contains(&self, file: &Path) -> bool {
+        self.roots.iter().any(|root| file.starts_with(root))
+            && !self
+                .excluded
+                .iter()
+                .any(|excluded| file.starts_with(excluded))
+    }
+}
+
+/// Watches the sources of `canisters` and calls `rebuild` with the canisters affected by each change,
+/// in dependency order.
+/// Errors returned by `rebuild` are logged, and watching continues.
+/// The canisters of a failed rebuild, and the `failed` ones from before watching started,
+/// are rebuilt again with the next change.
+pub fn watch_canisters(
+    log: &Logger,
+    config: &Config,
+    canisters: &[String],
+    failed: &[String],
+    mut rebuild: impl FnMut(&[String]) -> DfxResult,
+) -> DfxResult {
+    let canister_map = config
+        .get_config()
+        .canisters
+        .as_ref()
+        .context("No canisters are defined in dfx.json.")?;
+    let project_root = config.get_project_root();
+    let declarations: Vec<PathBuf> = canister_map
+        .iter()
+        .map(|(name, canister)| {
+            project_root.join(
+                canister
+                    .declarations
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| Path::new("src/declarations").join(name)),
+            )
+        })
+        .collect();
+
+    let mut watched = BTreeMap::new();
+    for name in canisters {
+        let canister = canister_map
+            .get(name)
+            .with_context(|| format!("Cannot find canister '{}' in dfx.json.", name))?;
+        let mut paths = watched_paths(project_root, canister)?;
+        if paths.roots.is_empty() {
+            warn!(
+                log,
+                "No files are watched for canister '{}'. List its sources in the 'watch' field in dfx.json.",
+                name
+            );
+        }
+        // Generated declarations must not trigger another rebuild.
+        paths.excluded.extend(declarations.iter().cloned());
+        watched.insert(name.clone(), paths);
+    }
+    let dependencies: BTreeMap<String, Vec<String>> = canister_map
+        .iter()
+        .map(|(name, canister)| (name.clone(), canister.dependencies.clone()))
+        .collect();
+
+    let mut failed: BTreeSet<String> = failed.iter().cloned().collect();
+    let mut snapshot = take_snapshot(&watched);
+    info!(log, "Watching for changes. Press Ctrl-C to stop.");
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let mut current = take_snapshot(&watched);
+        if current == snapshot {
+            continue;
+        }
+        // Wait until files stop changing, e.g. while an editor saves several files.
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let next = take_snapshot(&watched);
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+
+        let changed_files = changed_files(&snapshot, &current);
+        let changed: BTreeSet<String> = watched
+            .iter()
+            .filter(|(_, paths)| changed_files.iter().any(|file| paths.contains(file)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        snapshot = current;
+        if changed.is_empty() {
+            continue;
+        }
+
+        info!(
+            log,
+            "Detected changes in {}.",
+            changed.iter().cloned().collect::<Vec<_>>().join(", ")
+        );
+        let changed: BTreeSet<String> = changed.into_iter().chain(failed).collect();
+        let affected = affected_canisters(&changed, canisters, &dependencies);
+        info!(log, "Rebuilding {}.", affected.join(", "));
+        failed = match rebuild(&affected) {
+            Ok(()) => BTreeSet::new(),
+            Err(err) => {
+                error!(log, "{:#}", err);
+                affected.into_iter().collect()
+            }
+        };
+        // Files written by the rebuild itself are not changes to react to.
+        snapshot = take_snapshot(&watched);
+        info!(log, "Watching for changes. Press Ctrl-C to stop.");
+    }
+}
+
+fn watched_paths(
+    project_root: &Path,
+    canister: &ConfigCanistersCanister,
+) -> DfxResult<WatchedPaths> {
+    let mut paths = WatchedPaths::default();
+    match &canister.type_specific {
+        CanisterTypeProperties::Motoko => {
+            if let Some(main) = &canister.main {
+                paths.roots.extend(motoko_sources(&project_root.join(main)));
+            }
+        }
+        CanisterTypeProperties::Rust {
+            package, candid, ..
+        } => {
+            paths
+                .roots
+                .extend(rust_package_dirs(project_root, package)?);
+            paths.roots.push(project_root.join(candid));
+        }
+        CanisterTypeProperties::Assets {
+            source,
+            build,
+            workspace,
+        } => {
+            if build.clone().into_vec().is_empty() && workspace.is_none() {
+                paths
+                    .roots
+                    .extend(source.iter().map(|s| project_root.join(s)));
+            } else {
+                // The source directories are the output of the build, so watch what is next to them.
+                for source in source {
+                    let source = project_root.join(source);
+                    if let Some(parent) = source.parent() {
+                        paths.roots.push(parent.to_path_buf());
+                    }
+                    paths.excluded.push(source);
+                }
+            }
+        }
+        CanisterTypeProperties::Custom {
+            wasm,
+            candid,
+            build,
+        } => {
+            // Without build commands, the wasm and candid files are the sources.
+            // Otherwise the inputs of the build have to be listed in 'watch'.
+            if build.clone().into_vec().is_empty() {
+                for file in [wasm, candid] {
+                    if url::Url::parse(file).is_err() {
+                        paths.roots.push(project_root.join(file));
+                    }
+                }
+            }
+        }
+        CanisterTypeProperties::Pull { .. } => {}
+    }
+    paths
+        .roots
+        .extend(canister.watch.iter().map(|p| project_root.join(p)));
+    Ok(paths)
+}
+
+/// The Motoko files imported by `main`, directly or indirectly, including `main` itself.
+/// Imports of packages (`mo:`) and canisters (`canister:`) are not followed.
+fn motoko_sources(main: &Path) -> BTreeSet<PathBuf> {
+    let import = Regex::new(r#"import\s+[^";]*?"([^"]+)""#).unwrap();
+    let mut sources = BTreeSet::new();
+    let mut queue = vec![main.to_path_buf()];
+    while let Some(file) = queue.pop() {
+        if !sources.insert(file.clone()) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let dir = file.parent().unwrap_or_else(|| Path::new("."));
+        for capture in import.captures_iter(&content) {
+            let path = &capture[1];
+            if path.contains(':') {
+                continue;
+            }
+            let candidates = [
+                dir.join(path),
+                dir.join(format!("{path}.mo")),
+                dir.join(path).join("lib.mo"),
+            ];
+            if let Some(imported) = candidates.into_iter().find(|c| c.is_file()) {
+                queue.push(imported);
+            }
+        }
+    }
+    sources
+}
+
+/// The directory of the cargo package, and of the local packages it depends on.
+fn rust_package_dirs(project_root: &Path, package: &str) -> DfxResult<Vec<PathBuf>> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(project_root)
+        .output()
+        .context("Failed to run 'cargo metadata'.")?;
+    if !output.status.success() {
+        bail!(
+            "'cargo metadata' failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let metadata: serde_json::Value =
+        serde_json::from_slice(&output.stdout).context("Failed to parse 'cargo metadata'.")?;
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+    let find = |name: &str| packages.iter().find(|p| p["name"] == name);
+
+    let mut dirs = vec![];
+    let mut visited = BTreeSet::new();
+    let mut queue = vec![package.to_string()];
+    while let Some(name) = queue.pop() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        let Some(found) = find(&name) else {
+            if name == package {
+                bail!("Cannot find package '{}' in the cargo workspace.", name);
+            }
+            continue;
+        };
+        if let Some(dir) = found["manifest_path"]
+            .as_str()
+            .and_then(|manifest| Path::new(manifest).parent())
+        {
+            dirs.push(dir.to_path_buf());
+        }
+        for dependency in found["dependencies"].as_array().into_iter().flatten() {
+            if let (Some(name), Some(_)) =
+                (dependency["name"].as_str(), dependency["path"].as_str())
+            {
+                queue.push(name.to_string());
+            }
+        }
+    }
+    Ok(dirs)
+}
+
+fn take_snapshot(watched: &BTreeMap<String, WatchedPaths>) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    for paths in watched.values() {
+        for root in &paths.roots {
+            let entries = WalkDir::new(root)
+                .into_iter()
+                .filter_entry(|entry| !is_ignored(entry, &paths.excluded))
+                .filter_map(Result::ok);
+            for entry in entries.filter(|entry| entry.file_type().is_file()) {
+                if let Some(modified) = entry.metadata().ok().and_then(|m| m.modified().ok()) {
+                    snapshot.insert(entry.into_path(), modified);
+                }
+            }
+        }
+    }
+    snapshot
+}
+
+fn is_ignored(entry: &DirEntry, excluded: &[PathBuf]) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    (entry.depth() > 0 && (name.starts_with('.') || IGNORED_DIRECTORIES.contains(&&*name)))
+        || excluded.iter().any(|e| entry.path().starts_with(e))
+}
+
+fn changed_files(before: &Snapshot, after: &Snapshot) -> BTreeSet<PathBuf> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|file| before.get(*file) != after.get(*file))
+        .cloned()
+        .collect()
+}
+
+/// The selected canisters that changed or depend on a changed canister, in dependency order.
+fn affected_canisters(
+    changed: &BTreeSet<String>,
+    selected: &[String],
+    dependencies: &BTreeMap<String, Vec<String>>,
+) -> Vec<String> {
+    fn depends_on_changed(
+        name: &str,
+        changed: &BTreeSet<String>,
+        dependencies: &BTreeMap<String, Vec<String>>,
+        visited: &mut BTreeSet<String>,
+    ) -> bool {
+        if changed.contains(name) {
+            return true;
+        }
+        if !visited.insert(name.to_string()) {
+            return false;
+        }
+        dependencies
+            .get(name)
+            .into_iter()
+            .flatten()
+            .any(|dep| depends_on_changed(dep, changed, dependencies, visited))
+    }
+
+    fn visit(
+        name: &str,
+        affected: &BTreeSet<String>,
+        dependencies: &BTreeMap<String, Vec<String>>,
+        visited: &mut BTreeSet<String>,
+        ordered: &mut Vec<String>,
+    ) {
+        if !visited.insert(name.to_string()) {
+            return;
+        }
+        for dep in dependencies.get(name).into_iter().flatten() {
+            visit(dep, affected, dependencies, visited, ordered);
+        }
+        if affected.contains(name) {
+            ordered.push(name.to_string());
+        }
+    }
+
+    let affected: BTreeSet<String> = selected
+        .iter()
+        .filter(|name| depends_on_changed(name, changed, dependencies, &mut BTreeSet::new()))
+        .cloned()
+        .collect();
+    let mut ordered = vec![];
+    let mut visited = BTreeSet::new();
+    for name in &affected {
+        visit(name, &affected, dependencies, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn motoko_sources_follow_relative_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("types")).unwrap();
+        std::fs::write(
+            root.join("main.mo"),
+            "import Debug \"mo:base/Debug\";\nimport Util \"util\";\nimport { t } \"types\";\nimport B \"canister:backend\";\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("util.mo"), "import Types \"./types/lib\";\n").unwrap();
+        std::fs::write(root.join("types/lib.mo"), "module {}\n").unwrap();
+        std::fs::write(root.join("unused.mo"), "module {}\n").unwrap();
+
+        let sources = motoko_sources(&root.join("main.mo"));
+        assert_eq!(
+            sources,
+            BTreeSet::from([
+                root.join("main.mo"),
+                root.join("types/lib.mo"),
+                root.join("util.mo"),
+            ])
+        );
+    }
+
+    #[test]
+    fn affected_canisters_include_dependents_in_order() {
+        let dependencies = BTreeMap::from([
+            ("frontend".to_string(), vec!["backend".to_string()]),
+            ("backend".to_string(), vec!["store".to_string()]),
+            ("store".to_string(), vec![]),
+            ("other".to_string(), vec![]),
+        ]);
+        let selected = ["backend", "frontend", "other", "store"].map(String::from);
+
+        let affected = affected_canisters(
+            &BTreeSet::from(["store".to_string()]),
+            &selected,
+            &dependencies,
+        );
+        assert_eq!(affected, ["store", "backend", "frontend"]);
+
+        let affected = affected_canisters(
+            &BTreeSet::from(["other".to_string()]),
+            &selected,
+            &dependencies,
+        );
+        assert_eq!(affected, ["other"]);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::token::IcrcToken;
+use crate::lib::transaction_history::{index_transactions, ledger_transactions, print_table};
+use crate::util::clap::parsers::icrc_subaccount_parser;
+use candid::Principal;
+use clap::Args;
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+
+/// Options to select and print the transactions of a ledger.
+#[derive(Args, Clone, Debug)]
+pub struct HistoryOpt {
+    /// Show the transactions of this principal. Defaults to the current identity.
+    #[arg(long)]
+    of: Option<Principal>,
+
+    /// Show the transactions of this subaccount.
+    #[arg(long, value_parser = icrc_subaccount_parser)]
+    subaccount: Option<Subaccount>,
+
+    /// Show the transactions of all accounts.
+    #[arg(long, conflicts_with_all = ["of", "subaccount", "index"])]
+    all_accounts: bool,
+
+    /// The first block to read. Defaults to the first of the last --limit blocks.
+    #[arg(long, conflicts_with = "index")]
+    start: Option<u64>,
+
+    /// The number of blocks to read, or with --index, the number of transactions to show.
+    #[arg(long, default_value = "100")]
+    limit: u64,
+
+    /// Read the transactions of the account from this index canister, instead of reading
+    /// the blocks of the ledger. Takes a canister ID or the name of a canister of the project.
+    #[arg(long)]
+    index: Option<String>,
+
+    /// Print the transactions as JSON, with amounts in the smallest unit of the token.
+    #[arg(long)]
+    json: bool,
+}
+
+impl HistoryOpt {
+    /// Prints the transactions of the ledger that the options select.
+    pub async fn print_transactions(
+        self,
+        env: &dyn Environment,
+        ledger_id: Principal,
+    ) -> DfxResult {
+        let agent = env.get_agent();
+        let account = Account {
+            owner: self.of.unwrap_or_else(|| {
+                env.get_selected_identity_principal()
+                    .expect("Selected identity not instantiated.")
+            }),
+            subaccount: self.subaccount,
+        };
+
+        let transactions = if let Some(index) = &self.index {
+            let index_id = match Principal::from_text(index) {
+                Ok(index_id) => index_id,
+                Err(_) => env.get_canister_id_store()?.get(index)?,
+            };
+            index_transactions(agent, index_id, account, self.limit).await?
+        } else {
+            let mut transactions =
+                ledger_transactions(agent, ledger_id, self.start, self.limit).await?;
+            if !self.all_accounts {
+                transactions.retain(|tx| tx.involves(&account));
+            }
+            transactions
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&transactions)?);
+        } else {
+            let token = IcrcToken::fetch(agent, ledger_id).await?;
+            print_table(&token, &transactions);
+        }
+        Ok(())
+    }
+}
@@ -2,8 +2,10 @@ use anstyle::{AnsiColor, Style};
 use clap::builder::Styles;
 
 pub mod argument_from_cli;
+pub mod history_opt;
 pub mod install_mode;
 pub mod parsers;
+pub mod sign_only_opt;
 pub mod subnet_selection_opt;
 
 pub fn style() -> Styles {
@@ -0,0 +1,64 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::sign::signed_bundle::{FollowUp, SignedBundleV1};
+use crate::lib::sign::signed_message::SignedMessageV1;
+use anyhow::anyhow;
+use candid::Principal;
+use clap::Args;
+use slog::info;
+use std::path::PathBuf;
+
+/// Options to sign a transaction on an offline machine instead of sending it.
+#[derive(Args, Clone, Debug)]
+pub struct SignOnlyOpt {
+    /// Sign the transaction with the current identity and write it to this file instead of sending it.
+    /// The network is not contacted, so this works on an air-gapped machine.
+    /// Send the file later with `dfx send`.
+    #[arg(long)]
+    sign_only: Option<PathBuf>,
+
+    /// How long the signed transaction remains valid.
+    /// The Internet Computer only accepts messages that expire within 5 minutes.
+    #[arg(long, default_value = "5m", requires = "sign_only")]
+    expire_after: String,
+}
+
+impl SignOnlyOpt {
+    pub fn is_sign_only(&self) -> bool {
+        self.sign_only.is_some()
+    }
+
+    /// Signs an update call to `canister_id` and writes it, with the follow-up call if any,
+    /// to the file of the --sign-only option.
+    pub fn sign_update(
+        &self,
+        env: &dyn Environment,
+        description: String,
+        canister_id: Principal,
+        method_name: &str,
+        arg: Vec<u8>,
+        follow_up: Option<FollowUp>,
+    ) -> DfxResult {
+        let file = self
+            .sign_only
+            .as_ref()
+            .expect("sign_update requires --sign-only");
+        let timeout = humantime::parse_duration(&self.expire_after).map_err(|_| {
+            anyhow!("Cannot parse expire_after as a duration (e.g. `1h`, `1h 30m`)")
+        })?;
+        let message =
+            SignedMessageV1::sign_update(env, canister_id, canister_id, method_name, arg, timeout)?;
+        let mut bundle = SignedBundleV1::new(description, vec![message]);
+        if let Some(follow_up) = follow_up {
+            bundle = bundle.with_follow_up(follow_up);
+        }
+        bundle.save(file)?;
+        info!(
+            env.get_logger(),
+            "Signed transaction written to [{}]. Send it with `dfx send {}`.",
+            file.display(),
+            file.display()
+        );
+        Ok(())
+    }
+}
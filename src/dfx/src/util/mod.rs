@@ -1,6 +1,9 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::integrations::bitcoin::MAINNET_BITCOIN_CANISTER_ID;
+use crate::lib::integrations::system_canisters::{
+    MAINNET_GOVERNANCE_CANISTER_ID, MAINNET_II_CANISTER_ID,
+};
 use crate::lib::ledger_types::{MAINNET_CYCLE_MINTER_CANISTER_ID, MAINNET_LEDGER_CANISTER_ID};
 use crate::lib::subnet::MAINNET_REGISTRY_CANISTER_ID;
 use crate::{error_invalid_argument, error_invalid_data, error_unknown};
@@ -341,6 +344,14 @@ pub fn format_as_trillions(amount: u128) -> String {
     }
 }
 
+/// Formats cycles in trillions with thousand separators, e.g. `1,234.500 TC`.
+pub fn format_as_tc(cycles: u128) -> String {
+    format!(
+        "{} TC",
+        pretty_thousand_separators(format_as_trillions(cycles))
+    )
+}
+
 /// Formats a number provided as string, by dividing digits into groups of 3 using a delimiter
 /// <https://en.wikipedia.org/wiki/Decimal_separator#Digit_grouping>
 pub fn pretty_thousand_separators(num: String) -> String {
@@ -449,10 +460,6 @@ pub fn ask_for_consent(env: &dyn Environment, message: &str) -> Result<(), UserC
 }
 
 pub fn default_allowlisted_canisters() -> &'static [Principal] {
-    const MAINNET_GOVERNANCE_CANISTER_ID: Principal =
-        Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01]);
-    const MAINNET_II_CANISTER_ID: Principal =
-        Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x01, 0x01]);
     &[
         MAINNET_LEDGER_CANISTER_ID,
         MAINNET_REGISTRY_CANISTER_ID,